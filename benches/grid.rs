@@ -0,0 +1,123 @@
+//! Run with `cargo bench`, and again with `--features unchecked-indexing`
+//! to compare the fast paths it enables.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use md_grid::{Connectivity, DStarLite, Grid, GridLike};
+
+const SIZE: usize = 256;
+
+fn ramp() -> Grid<f64> {
+    let mut grid = Grid::new(0.0, vec![SIZE, SIZE]);
+    for (i, v) in grid.iter_mut().enumerate() {
+        *v = (i % 97) as f64;
+    }
+    grid
+}
+
+fn indexing(c: &mut Criterion) {
+    let grid = ramp();
+    c.bench_function("get", |b| {
+        b.iter(|| {
+            let mut sum = 0.0;
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    sum += grid.get(black_box(&[row, col])).unwrap();
+                }
+            }
+            sum
+        })
+    });
+    c.bench_function("get_opt", |b| {
+        b.iter(|| {
+            let mut sum = 0.0;
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    sum += grid.get_opt(black_box(&[row, col])).unwrap();
+                }
+            }
+            sum
+        })
+    });
+    c.bench_function("accessor", |b| {
+        let cells = grid.accessor::<2>().unwrap();
+        b.iter(|| {
+            let mut sum = 0.0;
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    sum += cells.get(black_box([row, col])).unwrap();
+                }
+            }
+            sum
+        })
+    });
+}
+
+fn iteration(c: &mut Criterion) {
+    let grid = ramp();
+    c.bench_function("iter", |b| b.iter(|| black_box(&grid).iter().sum::<f64>()));
+    c.bench_function("iter_indexed", |b| {
+        b.iter(|| {
+            black_box(&grid)
+                .iter_indexed()
+                .map(|(coords, v)| coords[0] as f64 * v)
+                .sum::<f64>()
+        })
+    });
+}
+
+fn neighbors(c: &mut Criterion) {
+    let grid = ramp();
+    c.bench_function("successors", |b| {
+        b.iter(|| {
+            let mut count = 0;
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    count += grid
+                        .successors(&[row, col], Connectivity::Diagonal, |&v| v > 10.0)
+                        .len();
+                }
+            }
+            count
+        })
+    });
+}
+
+fn convolution(c: &mut Criterion) {
+    let grid = ramp();
+    let kernel = Grid::new(1.0 / 9.0, vec![3, 3]);
+    c.bench_function("convolve_3x3", |b| {
+        b.iter(|| black_box(&grid).convolve(&kernel).unwrap())
+    });
+}
+
+fn pathfinding(c: &mut Criterion) {
+    let mut walls = Grid::new(false, vec![SIZE, SIZE]);
+    for row in 0..SIZE - 1 {
+        walls.set(&[row, SIZE / 2], true).unwrap();
+    }
+    c.bench_function("dstar_lite", |b| {
+        b.iter(|| {
+            let mut search = DStarLite::new(&walls, &[0, 0], &[SIZE - 1, SIZE - 1], |&w| {
+                if w {
+                    f64::INFINITY
+                } else {
+                    1.0
+                }
+            })
+            .unwrap();
+            search.path().map(|p| p.len())
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    indexing,
+    iteration,
+    neighbors,
+    convolution,
+    pathfinding
+);
+criterion_main!(benches);
@@ -0,0 +1,84 @@
+//! Builds Arrow record batches from arbitrary columns and index triplets,
+//! and checks the grids read from them hold exactly those values.
+
+#![no_main]
+
+use std::{collections::HashMap, sync::Arc};
+
+use arrow_array::{types::*, ArrayRef, Float64Array, Int32Array, RecordBatch, UInt64Array};
+use libfuzzer_sys::fuzz_target;
+use md_grid::{ColumnarError, Grid};
+
+/// Larger triplet grids are skipped so sparse indices can't exhaust memory.
+const MAX_CELLS: u128 = 1 << 16;
+
+fn columns(batches: &[Vec<Vec<i32>>]) {
+    let batches: Vec<RecordBatch> = batches
+        .iter()
+        .filter_map(|columns| {
+            let columns = columns.iter().enumerate().map(|(i, values)| {
+                let array: ArrayRef = Arc::new(Int32Array::from(values.clone()));
+                (i.to_string(), array)
+            });
+            RecordBatch::try_from_iter(columns).ok()
+        })
+        .collect();
+    let Ok(grid) = Grid::from_arrow_columns::<Int32Type>(&batches) else {
+        return;
+    };
+
+    let rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+    let cols = batches.first().map_or(0, |b| b.num_columns());
+    assert_eq!(grid.dimensions(), &[rows, cols]);
+    let mut row = 0;
+    for batch in &batches {
+        for (col, array) in batch.columns().iter().enumerate() {
+            let values = array.as_any().downcast_ref::<Int32Array>().unwrap();
+            for (r, &v) in values.values().iter().enumerate() {
+                assert_eq!(grid.get_opt(&[row + r, col]), Some(&v));
+            }
+        }
+        row += batch.num_rows();
+    }
+}
+
+fn triplets(records: &[(u64, u64, f64)]) {
+    let batch = RecordBatch::try_from_iter([
+        (
+            "x",
+            Arc::new(UInt64Array::from_iter_values(records.iter().map(|r| r.0))) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(UInt64Array::from_iter_values(records.iter().map(|r| r.1))),
+        ),
+        (
+            "v",
+            Arc::new(Float64Array::from_iter_values(records.iter().map(|r| r.2))),
+        ),
+    ])
+    .unwrap();
+
+    let extent = |max: Option<u64>| max.map_or(0, |m| u128::from(m) + 1);
+    let cols = extent(records.iter().map(|r| r.0).max());
+    let rows = extent(records.iter().map(|r| r.1).max());
+    let cells = rows.checked_mul(cols);
+    if cells.is_some_and(|n| n <= MAX_CELLS) {
+        let grid = Grid::from_arrow_triplets::<Float64Type>(&[batch], "x", "y", "v", 0.0).unwrap();
+        let last: HashMap<_, _> = records.iter().map(|&(x, y, v)| ((y, x), v)).collect();
+        for ((y, x), v) in last {
+            let cell = grid.get_opt(&[y as usize, x as usize]).unwrap();
+            assert_eq!(cell.to_bits(), v.to_bits());
+        }
+    } else if cells.map_or(true, |n| n > isize::MAX as u128 / 8) {
+        // Anything smaller might really be allocated, so it is skipped.
+        let result = Grid::from_arrow_triplets::<Float64Type>(&[batch], "x", "y", "v", 0.0);
+        assert!(matches!(result, Err(ColumnarError::TooLarge)));
+    }
+}
+
+fuzz_target!(|input: (Vec<Vec<Vec<i32>>>, Vec<(u64, u64, f64)>)| {
+    let (batches, records) = input;
+    columns(&batches);
+    triplets(&records);
+});
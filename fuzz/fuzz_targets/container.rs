@@ -0,0 +1,30 @@
+//! Feeds arbitrary bytes to the container reader, and re-encodes anything it
+//! accepts to check the round trip is lossless.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use md_grid::{container::SavedGrid, Shape};
+
+/// Larger grids are skipped so run-length payloads can't exhaust memory.
+const MAX_CELLS: usize = 1 << 16;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(saved) = SavedGrid::from_bytes(data) else {
+        return;
+    };
+    assert_eq!(SavedGrid::from_bytes(&saved.to_bytes()).unwrap().payload, saved.payload);
+
+    let small = Shape::from(&saved.dimensions[..])
+        .checked_cell_count()
+        .is_some_and(|n| n <= MAX_CELLS);
+    if !small {
+        return;
+    }
+    if let Ok(grid) = saved.decode::<u16>() {
+        let again = SavedGrid::encode(&grid, saved.version, saved.codec)
+            .decode::<u16>()
+            .unwrap();
+        assert!(grid.compare(&again).is_equal());
+    }
+});
@@ -0,0 +1,43 @@
+//! Feeds arbitrary bytes to the grid set reader, and re-saves anything it
+//! accepts to check every layer survives the round trip.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use md_grid::{GridFormat, Shape};
+
+/// Larger sets are skipped so run-length payloads can't exhaust memory.
+const MAX_CELLS: usize = 1 << 16;
+
+/// The cell count in a set's header: magic, format, axes, then the length
+/// of each axis.
+fn header_cells(data: &[u8]) -> Option<usize> {
+    let axes = u32::from_le_bytes(data.get(6..10)?.try_into().ok()?) as usize;
+    let end = axes.checked_mul(8)?.checked_add(10)?;
+    let dimensions = data
+        .get(10..end)?
+        .chunks(8)
+        .map(|d| usize::try_from(u64::from_le_bytes(d.try_into().unwrap())).ok())
+        .collect::<Option<Vec<_>>>()?;
+    Shape::new(dimensions).checked_cell_count()
+}
+
+fuzz_target!(|data: &[u8]| {
+    if !header_cells(data).is_some_and(|n| n <= MAX_CELLS) {
+        return;
+    }
+    let format = GridFormat::new(1);
+    let Ok(set) = format.load_set(data) else {
+        return;
+    };
+
+    let again = format.load_set(&format.save_set(&set)).unwrap();
+    assert_eq!(again.dimensions(), set.dimensions());
+    assert!(again.names().eq(set.names()));
+    for name in set.names() {
+        assert_eq!(again.schema(name), set.schema(name));
+        if let (Some(a), Some(b)) = (set.get::<u16>(name), again.get::<u16>(name)) {
+            assert!(a.compare(b).is_equal());
+        }
+    }
+});
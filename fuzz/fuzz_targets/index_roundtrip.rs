@@ -0,0 +1,32 @@
+//! Round-trips coordinates and flat indices through `Shape` for arbitrary
+//! (including overflowing) shapes, and checks small grids agree with it.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use md_grid::{Grid, Shape};
+
+const MAX_CELLS: usize = 4096;
+
+fuzz_target!(|input: (Vec<usize>, Vec<usize>, usize)| {
+    let (dimensions, coords, index) = input;
+    let shape = Shape::new(dimensions.clone());
+
+    if let Some(i) = shape.flat_index(&coords) {
+        assert_eq!(shape.coords_of(i).unwrap()[..], coords[..]);
+    }
+    if let Some(c) = shape.coords_of(index) {
+        assert_eq!(shape.flat_index(&c), Some(index));
+    }
+
+    if shape.checked_cell_count().is_some_and(|n| n <= MAX_CELLS) {
+        let mut grid = Grid::new(0usize, dimensions);
+        for (i, v) in grid.iter_mut().enumerate() {
+            *v = i;
+        }
+        assert_eq!(grid.get_opt(&coords).copied(), shape.flat_index(&coords));
+        for (c, &v) in grid.iter_indexed() {
+            assert_eq!(shape.flat_index(&c), Some(v));
+        }
+    }
+});
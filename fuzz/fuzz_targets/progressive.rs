@@ -0,0 +1,26 @@
+//! Feeds arbitrary bytes to the progressive decoder. Anything it accepts
+//! must also decode, only more coarsely, from a shorter prefix, and a
+//! complete grid must survive re-encoding.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use md_grid::progressive;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(Some(preview)) = progressive::decode_prefix::<u16>(data) else {
+        return;
+    };
+
+    let half = progressive::decode_prefix::<u16>(&data[..data.len() / 2]).unwrap();
+    if let Some(coarser) = half {
+        assert!(coarser.stride() >= preview.stride());
+        assert_eq!(coarser.full_dimensions(), preview.full_dimensions());
+    }
+
+    if preview.is_complete() {
+        let grid = preview.grid();
+        let again = progressive::decode::<u16>(&progressive::encode(grid)).unwrap();
+        assert!(grid.compare(&again).is_equal());
+    }
+});
@@ -0,0 +1,41 @@
+/* C interface to md-grid, available when built with the `ffi` feature. */
+#ifndef MD_GRID_H
+#define MD_GRID_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MD_GRID_OK 0
+#define MD_GRID_NULL -1
+#define MD_GRID_OUT_OF_BOUNDS -2
+
+/* An opaque handle to a grid of doubles, stored in row-major order. */
+typedef struct MdGrid MdGrid;
+
+/* Return nonzero to stop iteration early. */
+typedef int (*md_grid_callback)(const size_t *coords, double value, void *user_data);
+
+/* Returns NULL if dimensions is NULL or the cells can't be allocated. */
+MdGrid *md_grid_new(const size_t *dimensions, size_t axes, double fill);
+void md_grid_free(MdGrid *grid);
+
+size_t md_grid_axes(const MdGrid *grid);
+const size_t *md_grid_dimensions(const MdGrid *grid);
+size_t md_grid_len(const MdGrid *grid);
+
+const double *md_grid_data(const MdGrid *grid);
+double *md_grid_data_mut(MdGrid *grid);
+int md_grid_strides(const MdGrid *grid, size_t *strides);
+
+int md_grid_get(const MdGrid *grid, const size_t *coords, double *out);
+int md_grid_set(MdGrid *grid, const size_t *coords, double value);
+int md_grid_for_each(const MdGrid *grid, md_grid_callback callback, void *user_data);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MD_GRID_H */
@@ -0,0 +1,201 @@
+use crate::{Grid, GridError};
+
+/// Read access to a grid with `D` axes whose shape is checked and strides
+/// computed once, up front. Lookups take `[usize; D]`, so they never check
+/// the number of coordinates and compile to a fixed multiply-add per axis.
+///
+/// ```
+/// use md_grid::Grid;
+///
+/// let image = Grid::from_fn(vec![64, 64], |c| (c[0] + c[1]) as f64);
+/// let pixels = image.accessor::<2>().unwrap();
+/// let mut sum = 0.0;
+/// for row in 1..63 {
+///     for col in 1..63 {
+///         // SAFETY: both neighbors are inside the 64x64 grid.
+///         sum += unsafe { pixels.get_unchecked([row - 1, col]) + pixels.get_unchecked([row + 1, col]) };
+///     }
+/// }
+/// assert_eq!(sum, 2.0 * 62.0 * 62.0 * 63.0);
+/// assert_eq!(pixels.get([64, 0]), None);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Accessor<'a, T, const D: usize> {
+    cells: &'a [T],
+    dimensions: [usize; D],
+    strides: [usize; D],
+}
+
+/// Mutable counterpart of [`Accessor`].
+#[derive(Debug)]
+pub struct AccessorMut<'a, T, const D: usize> {
+    cells: &'a mut [T],
+    dimensions: [usize; D],
+    strides: [usize; D],
+}
+
+fn shape<T, const D: usize>(grid: &Grid<T>) -> Result<([usize; D], [usize; D]), GridError> {
+    let dimensions: [usize; D] =
+        grid.dimensions[..]
+            .try_into()
+            .map_err(|_| GridError::UnsupportedAxes {
+                expected: D,
+                shape: grid.dimensions.clone(),
+            })?;
+    let mut strides = [1; D];
+    for axis in (0..D.saturating_sub(1)).rev() {
+        strides[axis] = strides[axis + 1] * dimensions[axis + 1];
+    }
+    Ok((dimensions, strides))
+}
+
+/// Flat index of `target` if every coordinate is inside `dimensions`.
+#[inline]
+fn checked<const D: usize>(
+    target: [usize; D],
+    dimensions: &[usize; D],
+    strides: &[usize; D],
+) -> Option<usize> {
+    let mut index = 0;
+    for axis in 0..D {
+        if target[axis] >= dimensions[axis] {
+            return None;
+        }
+        index += target[axis] * strides[axis];
+    }
+    Some(index)
+}
+
+#[inline]
+fn unchecked<const D: usize>(target: [usize; D], strides: &[usize; D]) -> usize {
+    (0..D).map(|axis| target[axis] * strides[axis]).sum()
+}
+
+impl<'a, T, const D: usize> Accessor<'a, T, D> {
+    pub fn dimensions(&self) -> [usize; D] {
+        self.dimensions
+    }
+
+    pub fn strides(&self) -> [usize; D] {
+        self.strides
+    }
+
+    #[inline]
+    pub fn get(&self, target: [usize; D]) -> Option<&'a T> {
+        checked(target, &self.dimensions, &self.strides).map(|i| &self.cells[i])
+    }
+
+    /// The cell at `target` without any bounds checks.
+    ///
+    /// # Safety
+    ///
+    /// Every coordinate must be less than its axis's length.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, target: [usize; D]) -> &'a T {
+        debug_assert!(checked(target, &self.dimensions, &self.strides).is_some());
+        // SAFETY: the caller guarantees `target` is inside the grid.
+        unsafe { self.cells.get_unchecked(unchecked(target, &self.strides)) }
+    }
+}
+
+impl<T, const D: usize> AccessorMut<'_, T, D> {
+    pub fn dimensions(&self) -> [usize; D] {
+        self.dimensions
+    }
+
+    pub fn strides(&self) -> [usize; D] {
+        self.strides
+    }
+
+    #[inline]
+    pub fn get(&self, target: [usize; D]) -> Option<&T> {
+        checked(target, &self.dimensions, &self.strides).map(|i| &self.cells[i])
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, target: [usize; D]) -> Option<&mut T> {
+        checked(target, &self.dimensions, &self.strides).map(|i| &mut self.cells[i])
+    }
+
+    /// The cell at `target` without any bounds checks.
+    ///
+    /// # Safety
+    ///
+    /// Every coordinate must be less than its axis's length.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, target: [usize; D]) -> &T {
+        debug_assert!(checked(target, &self.dimensions, &self.strides).is_some());
+        // SAFETY: the caller guarantees `target` is inside the grid.
+        unsafe { self.cells.get_unchecked(unchecked(target, &self.strides)) }
+    }
+
+    /// Mutable counterpart of [`AccessorMut::get_unchecked`].
+    ///
+    /// # Safety
+    ///
+    /// Every coordinate must be less than its axis's length.
+    #[inline]
+    pub unsafe fn get_unchecked_mut(&mut self, target: [usize; D]) -> &mut T {
+        debug_assert!(checked(target, &self.dimensions, &self.strides).is_some());
+        // SAFETY: the caller guarantees `target` is inside the grid.
+        unsafe {
+            self.cells
+                .get_unchecked_mut(unchecked(target, &self.strides))
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// An [`Accessor`] for fast repeated lookups, failing with
+    /// [`GridError::UnsupportedAxes`] unless the grid has `D` axes.
+    pub fn accessor<const D: usize>(&self) -> Result<Accessor<'_, T, D>, GridError> {
+        let (dimensions, strides) = shape(self)?;
+        Ok(Accessor {
+            cells: &self.grid,
+            dimensions,
+            strides,
+        })
+    }
+
+    /// An [`AccessorMut`] for fast repeated lookups and writes, failing with
+    /// [`GridError::UnsupportedAxes`] unless the grid has `D` axes.
+    pub fn accessor_mut<const D: usize>(&mut self) -> Result<AccessorMut<'_, T, D>, GridError> {
+        let (dimensions, strides) = shape(self)?;
+        Ok(AccessorMut {
+            cells: &mut self.grid,
+            dimensions,
+            strides,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accessors_match_checked_lookups() {
+        let mut grid = Grid::from_fn(vec![3, 4, 5], |c| c[0] * 100 + c[1] * 10 + c[2]);
+        let cells = grid.accessor::<3>().unwrap();
+        assert_eq!(cells.strides(), [20, 5, 1]);
+        assert_eq!(cells.get([2, 3, 4]), Some(&234));
+        assert_eq!(cells.get([2, 4, 0]), None);
+        // SAFETY: every coordinate below is inside the 3x4x5 grid.
+        unsafe {
+            assert_eq!(*cells.get_unchecked([1, 2, 3]), 123);
+            assert_eq!(*grid.get_unchecked(&[0, 3, 1]), 31);
+        }
+        assert!(grid.accessor::<2>().is_err());
+
+        let mut cells = grid.accessor_mut::<3>().unwrap();
+        *cells.get_mut([0, 0, 4]).unwrap() = 7;
+        // SAFETY: as above.
+        unsafe {
+            *cells.get_unchecked_mut([2, 0, 0]) += 1;
+            *grid.get_unchecked_mut(&[1, 1, 1]) = 0;
+        }
+        assert_eq!(grid[[0, 0, 4]], 7);
+        assert_eq!(grid[[2, 0, 0]], 201);
+        assert_eq!(grid[[1, 1, 1]], 0);
+    }
+}
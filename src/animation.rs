@@ -0,0 +1,172 @@
+use std::{error::Error, fmt, io::Write, marker::PhantomData};
+
+use gif::{Encoder, EncodingError, Frame, Repeat};
+
+use crate::{Color, Coord, Grid, GridError};
+
+#[derive(Debug)]
+pub enum AnimationError {
+    Gif(EncodingError),
+    /// No frames have been captured.
+    Empty,
+    /// A frame is wider or taller than GIF's 65535 pixel limit.
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+}
+
+impl fmt::Display for AnimationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnimationError::Gif(err) => write!(f, "ERROR: {}", err),
+            AnimationError::Empty => write!(f, "ERROR: No frames to write"),
+            AnimationError::TooLarge { width, height } => write!(
+                f,
+                "ERROR: Frames of {}x{} pixels are too large for a GIF",
+                width, height
+            ),
+        }
+    }
+}
+
+impl Error for AnimationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AnimationError::Gif(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<EncodingError> for AnimationError {
+    fn from(err: EncodingError) -> Self {
+        AnimationError::Gif(err)
+    }
+}
+
+/// Records snapshots of an evolving 2D grid as the frames of an animation.
+///
+/// ```
+/// use md_grid::{animation::AnimationRecorder, Color, Grid};
+///
+/// let mut grid = Grid::new(false, vec![8, 8]);
+/// let mut recorder = AnimationRecorder::new(4, |_, &alive: &bool| {
+///     if alive { Color::WHITE } else { Color::BLACK }
+/// });
+/// for i in 0..8 {
+///     grid.set(&[i, i], true).unwrap();
+///     recorder.capture(&grid).unwrap();
+/// }
+///
+/// let mut gif = Vec::new();
+/// recorder.write_gif(&mut gif).unwrap();
+/// assert!(gif.starts_with(b"GIF89a"));
+/// ```
+pub struct AnimationRecorder<T, F> {
+    color: F,
+    scale: usize,
+    delay: u16,
+    frames: Vec<Grid<Color>>,
+    /// Dimensions of the first captured grid.
+    dimensions: Option<Vec<usize>>,
+    cell: PhantomData<fn(&T)>,
+}
+
+impl<T, F> AnimationRecorder<T, F>
+where
+    F: FnMut(&Coord, &T) -> Color,
+{
+    /// A recorder drawing each cell as a `scale × scale` square of the color
+    /// `color` picks for it.
+    pub fn new(scale: usize, color: F) -> Self {
+        Self {
+            color,
+            scale,
+            delay: 10,
+            frames: Vec::new(),
+            dimensions: None,
+            cell: PhantomData,
+        }
+    }
+
+    /// Sets how long each frame is shown, in hundredths of a second.
+    pub fn delay(mut self, centiseconds: u16) -> Self {
+        self.delay = centiseconds;
+        self
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Renders `grid` as the next frame. Every frame must have the same
+    /// dimensions as the first.
+    pub fn capture(&mut self, grid: &Grid<T>) -> Result<(), GridError> {
+        match &self.dimensions {
+            Some(expected) if *expected != grid.dimensions => {
+                return Err(GridError::ShapeMismatch {
+                    expected: expected.clone(),
+                    got: grid.dimensions.clone(),
+                });
+            }
+            _ => {}
+        }
+        let frame = grid.render(self.scale, &mut self.color)?;
+        self.dimensions = Some(grid.dimensions.clone());
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    /// Writes the captured frames as a looping animated GIF, quantizing each
+    /// frame to at most 256 colors.
+    pub fn write_gif<W: Write>(&self, writer: W) -> Result<(), AnimationError> {
+        let first = self.frames.first().ok_or(AnimationError::Empty)?;
+        let (height, width) = (first.dimensions[0], first.dimensions[1]);
+        let too_large = |_| AnimationError::TooLarge { width, height };
+        let (w, h) = (
+            u16::try_from(width).map_err(too_large)?,
+            u16::try_from(height).map_err(too_large)?,
+        );
+
+        let mut encoder = Encoder::new(writer, w, h, &[])?;
+        encoder.set_repeat(Repeat::Infinite)?;
+        for pixels in &self.frames {
+            let rgb: Vec<u8> = pixels.grid.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+            let mut frame = Frame::from_rgb(w, h, &rgb);
+            frame.delay = self.delay;
+            encoder.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_checks_shape() {
+        let mut recorder = AnimationRecorder::new(2, |_, &v: &u8| Color::rgb(v, v, v)).delay(5);
+        let mut gif = Vec::new();
+        assert!(matches!(
+            recorder.write_gif(&mut gif),
+            Err(AnimationError::Empty)
+        ));
+
+        recorder.capture(&Grid::new(0, vec![3, 4])).unwrap();
+        recorder.capture(&Grid::new(255, vec![3, 4])).unwrap();
+        assert!(recorder.capture(&Grid::new(0, vec![4, 3])).is_err());
+        assert_eq!(recorder.frame_count(), 2);
+
+        recorder.write_gif(&mut gif).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(&gif[..]).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (8, 6));
+        let mut frames = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 5);
+            frames += 1;
+        }
+        assert_eq!(frames, 2);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Coord, Grid, GridError, Region};
+
+/// A read-only view of one tile visited by [`Grid::for_each_blocked`].
+pub struct BlockView<'a, T> {
+    grid: &'a Grid<T>,
+    region: Region,
+}
+
+impl<'a, T> BlockView<'a, T> {
+    /// The cells of the grid this tile covers.
+    pub fn region(&self) -> &Region {
+        &self.region
+    }
+
+    /// Gets a cell by its coordinates relative to the tile's origin.
+    pub fn get(&self, local: &[usize]) -> Option<&'a T> {
+        self.grid.get_opt(&self.region.to_grid_coords(local)?)
+    }
+
+    /// Iterates over the tile's rows along the last axis, each as the
+    /// grid coordinates of its first cell and a contiguous slice of cells.
+    pub fn rows(&self) -> impl Iterator<Item = (Coord, &'a [T])> {
+        let grid = self.grid;
+        let (starts, row_len) = self.region.row_starts();
+        let starts: Vec<Coord> = starts.iter().collect();
+        starts.into_iter().map(move |coords| {
+            let start = grid.checked_index(&coords).unwrap();
+            (coords, &grid.grid[start..start + row_len])
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Calls `f` on each tile of at most `block_shape` cells, tiles visited
+    /// in row-major order. Working tile by tile keeps neighbor-heavy or
+    /// transpose-like passes within cache on large grids. Fails with
+    /// [`GridError::InvalidParameter`] if any length in `block_shape` is
+    /// zero.
+    pub fn for_each_blocked<F>(&self, block_shape: &[usize], mut f: F) -> Result<(), GridError>
+    where
+        F: FnMut(BlockView<'_, T>),
+    {
+        if block_shape.len() != self.axes {
+            return Err(GridError::DimensionMismatch {
+                coords: Coord::from_slice(block_shape),
+                shape: self.dimensions.clone(),
+            });
+        }
+        if block_shape.contains(&0) {
+            return Err(GridError::InvalidParameter {
+                name: "block_shape",
+                expected: "nonzero lengths",
+                shape: self.dimensions.clone(),
+            });
+        }
+
+        let blocks = Region::new(
+            self.dimensions
+                .iter()
+                .zip(block_shape)
+                .map(|(d, b)| 0..d.div_ceil(*b))
+                .collect(),
+        );
+        for block in &blocks {
+            let origin: Coord = block.iter().zip(block_shape).map(|(i, b)| i * b).collect();
+            let region = Region::from_origin_shape(&origin, block_shape).clamp_to(&self.dimensions);
+            f(BlockView { grid: self, region });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocked_transpose() {
+        let mut grid = Grid::new(0, vec![5, 3]);
+        for (i, v) in grid.iter_mut().enumerate() {
+            *v = i;
+        }
+
+        let mut transposed = Grid::new(0, vec![3, 5]);
+        let mut tiles = 0;
+        grid.for_each_blocked(&[2, 2], |view| {
+            tiles += 1;
+            for (coords, row) in view.rows() {
+                for (offset, &v) in row.iter().enumerate() {
+                    transposed.set(&[coords[1] + offset, coords[0]], v).unwrap();
+                }
+            }
+        })
+        .unwrap();
+
+        assert_eq!(tiles, 6);
+        for row in 0..5 {
+            for col in 0..3 {
+                assert_eq!(transposed.get_opt(&[col, row]), grid.get_opt(&[row, col]));
+            }
+        }
+        assert!(grid.for_each_blocked(&[2], |_| {}).is_err());
+        assert!(matches!(
+            grid.for_each_blocked(&[2, 0], |_| tiles += 1),
+            Err(GridError::InvalidParameter {
+                name: "block_shape",
+                ..
+            })
+        ));
+        assert_eq!(tiles, 6);
+    }
+}
@@ -0,0 +1,210 @@
+use std::ops::Range;
+
+use crate::{rng::Rng, Grid, GridError, Region};
+
+/// Parameters for [`Grid::carve_bsp_dungeon`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BspDungeon {
+    /// Seed for the splits and room sizes. The same seed and parameters
+    /// always carve the same dungeon.
+    pub seed: u64,
+    /// Smallest side of a partition; partitions are only split while both
+    /// halves would be at least this long.
+    pub min_leaf: usize,
+    /// Smallest side of a room.
+    pub min_room: usize,
+}
+
+impl BspDungeon {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    pub fn min_leaf(mut self, min_leaf: usize) -> Self {
+        self.min_leaf = min_leaf;
+        self
+    }
+
+    pub fn min_room(mut self, min_room: usize) -> Self {
+        self.min_room = min_room;
+        self
+    }
+}
+
+impl Default for BspDungeon {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            min_leaf: 8,
+            min_room: 3,
+        }
+    }
+}
+
+/// A uniformly random length in `[min, max]`.
+fn between(rng: &mut Rng, min: usize, max: usize) -> usize {
+    min + rng.below(max - min + 1)
+}
+
+struct Carver<'a> {
+    rng: Rng,
+    params: &'a BspDungeon,
+    rooms: Vec<Region>,
+    corridors: Vec<(usize, usize)>,
+}
+
+impl Carver<'_> {
+    /// Splits `rows` x `cols` into rooms and returns the index of one room
+    /// in it, joining the rooms of both halves with a corridor.
+    fn split(&mut self, rows: Range<usize>, cols: Range<usize>) -> Option<usize> {
+        let min_leaf = self.params.min_leaf.max(1);
+        let can_split_rows = rows.len() >= 2 * min_leaf;
+        let can_split_cols = cols.len() >= 2 * min_leaf;
+        let split_rows = match (can_split_rows, can_split_cols) {
+            (false, false) => return self.place_room(rows, cols),
+            (true, false) => true,
+            (false, true) => false,
+            (true, true) if rows.len() > cols.len() * 5 / 4 => true,
+            (true, true) if cols.len() > rows.len() * 5 / 4 => false,
+            (true, true) => self.rng.below(2) == 0,
+        };
+
+        let (first, second) = if split_rows {
+            let at = rows.start + between(&mut self.rng, min_leaf, rows.len() - min_leaf);
+            (
+                self.split(rows.start..at, cols.clone()),
+                self.split(at..rows.end, cols),
+            )
+        } else {
+            let at = cols.start + between(&mut self.rng, min_leaf, cols.len() - min_leaf);
+            (
+                self.split(rows.clone(), cols.start..at),
+                self.split(rows, at..cols.end),
+            )
+        };
+        if let (Some(a), Some(b)) = (first, second) {
+            self.corridors.push((a, b));
+        }
+        first.or(second)
+    }
+
+    /// A room inside the leaf, leaving at least one wall cell on each side.
+    fn place_room(&mut self, rows: Range<usize>, cols: Range<usize>) -> Option<usize> {
+        let min_room = self.params.min_room.max(1);
+        if rows.len() < min_room + 2 || cols.len() < min_room + 2 {
+            return None;
+        }
+        let mut span = |leaf: Range<usize>| {
+            let len = between(&mut self.rng, min_room, leaf.len() - 2);
+            let start = leaf.start + 1 + self.rng.below(leaf.len() - 1 - len);
+            start..start + len
+        };
+        let room = Region::new(vec![span(rows), span(cols)]);
+        self.rooms.push(room);
+        Some(self.rooms.len() - 1)
+    }
+}
+
+/// The middle cell of a room.
+fn center(room: &Region) -> (usize, usize) {
+    let [rows, cols] = room.ranges() else {
+        unreachable!("rooms are 2D")
+    };
+    ((rows.start + rows.end) / 2, (cols.start + cols.end) / 2)
+}
+
+impl<T: Clone> Grid<T> {
+    /// Carves a room-and-corridor dungeon into a 2D grid by binary space
+    /// partitioning: the grid is split in two at random until partitions
+    /// reach [`BspDungeon::min_leaf`], each partition with space for one
+    /// gets a room, and the rooms of every pair of halves are joined by an
+    /// L-shaped corridor, so all rooms are connected.
+    ///
+    /// Every cell is set to `wall` first, then rooms and corridors to
+    /// `floor`. Returns the rooms, for placing things in later.
+    ///
+    /// ```
+    /// use md_grid::{BspDungeon, Grid};
+    ///
+    /// let mut map = Grid::new(' ', vec![40, 80]);
+    /// let rooms = map.carve_bsp_dungeon('.', '#', &BspDungeon::new(3)).unwrap();
+    /// assert!(rooms.len() > 4);
+    /// for cell in rooms[0].iter() {
+    ///     assert_eq!(map[&cell[..]], '.');
+    /// }
+    /// ```
+    pub fn carve_bsp_dungeon(
+        &mut self,
+        floor: T,
+        wall: T,
+        params: &BspDungeon,
+    ) -> Result<Vec<Region>, GridError> {
+        let &[rows, cols] = &self.dimensions[..] else {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            });
+        };
+
+        let mut carver = Carver {
+            rng: Rng::for_stream(params.seed, 0),
+            params,
+            rooms: Vec::new(),
+            corridors: Vec::new(),
+        };
+        carver.split(0..rows, 0..cols);
+
+        self.fill(wall);
+        for room in &carver.rooms {
+            self.fill_region(&room.origin(), &room.shape(), floor.clone())?;
+        }
+        for &(a, b) in &carver.corridors {
+            let (from, to) = (center(&carver.rooms[a]), center(&carver.rooms[b]));
+            let (low_row, high_row) = (from.0.min(to.0), from.0.max(to.0));
+            let (low_col, high_col) = (from.1.min(to.1), from.1.max(to.1));
+            // Along `from`'s row to `to`'s column, then along that column.
+            self.fill_region(
+                &[from.0, low_col],
+                &[1, high_col - low_col + 1],
+                floor.clone(),
+            )?;
+            self.fill_region(
+                &[low_row, to.1],
+                &[high_row - low_row + 1, 1],
+                floor.clone(),
+            )?;
+        }
+        Ok(carver.rooms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GridLike;
+
+    #[test]
+    fn rooms_are_connected() {
+        let mut map = Grid::new(0u8, vec![50, 70]);
+        let params = BspDungeon::new(11).min_leaf(10).min_room(4);
+        let rooms = map.carve_bsp_dungeon(1, 2, &params).unwrap();
+        assert!(rooms.len() >= 4);
+        for room in &rooms {
+            assert!(room.shape().iter().all(|&len| len >= 4));
+            assert!(room.iter().all(|cell| map[&cell[..]] == 1));
+        }
+
+        let start = rooms[0].origin();
+        let reachable = map.flood_region(&start, |&v| v == 1).unwrap().len();
+        assert_eq!(reachable, map.iter().filter(|&&v| v == 1).count());
+
+        let mut again = Grid::new(0u8, vec![50, 70]);
+        assert_eq!(again.carve_bsp_dungeon(1, 2, &params).unwrap(), rooms);
+        assert!(Grid::new(0u8, vec![5, 5, 5])
+            .carve_bsp_dungeon(1, 2, &params)
+            .is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{rng::Rng, Grid};
+
+/// Number of the 8 cells around `[row, col]` that are wall, counting cells
+/// beyond the edge as wall.
+fn wall_neighbors(cave: &Grid<bool>, row: usize, col: usize) -> usize {
+    let (rows, cols) = (cave.dimensions[0], cave.dimensions[1]);
+    let mut count = 0;
+    for dr in -1..=1 {
+        for dc in -1..=1 {
+            if (dr, dc) == (0, 0) {
+                continue;
+            }
+            let cell = row
+                .checked_add_signed(dr)
+                .zip(col.checked_add_signed(dc))
+                .filter(|&(r, c)| r < rows && c < cols);
+            if cell.is_none_or(|(r, c)| cave.grid[r * cols + c]) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+impl Grid<bool> {
+    /// A 2D cave map where `true` is wall and `false` is open floor.
+    ///
+    /// Each cell starts as wall with probability `fill_prob`, then
+    /// `smoothing_iterations` rounds of the 4-5 rule apply: a wall stays a
+    /// wall with at least 4 wall neighbors out of 8, and floor becomes wall
+    /// with at least 5. Cells beyond the edge count as wall. Finally every
+    /// floor pocket not connected to the largest cavern is filled in, so all
+    /// open cells are reachable from each other.
+    ///
+    /// ```
+    /// use md_grid::{Grid, GridLike};
+    ///
+    /// let cave = Grid::generate_caves([40, 60], 0.45, 4, 9);
+    /// let floor = cave.iter().filter(|&&wall| !wall).count();
+    /// let start = cave.iter().position(|&wall| !wall).unwrap();
+    /// let cavern = cave.flood_region(&start, |&wall| !wall).unwrap();
+    /// assert_eq!(cavern.len(), floor);
+    /// ```
+    pub fn generate_caves(
+        dimensions: [usize; 2],
+        fill_prob: f64,
+        smoothing_iterations: usize,
+        seed: u64,
+    ) -> Grid<bool> {
+        let mut rng = Rng::for_stream(seed, 0);
+        let mut cave = Grid::from_fn(dimensions.to_vec(), |_| rng.next_f64() < fill_prob);
+
+        for _ in 0..smoothing_iterations {
+            cave = Grid::from_fn(dimensions.to_vec(), |c| {
+                let walls = wall_neighbors(&cave, c[0], c[1]);
+                walls >= if cave[[c[0], c[1]]] { 4 } else { 5 }
+            });
+        }
+
+        let partition = cave.partition_regions(|a, b| a == b);
+        let labels = &partition.labels().grid;
+        let largest = (labels.iter().zip(&cave.grid))
+            .filter(|(_, &wall)| !wall)
+            .map(|(&label, _)| label)
+            .max_by_key(|&label| partition.sizes()[label]);
+        for (wall, &label) in cave.grid.iter_mut().zip(labels) {
+            *wall |= Some(label) != largest;
+        }
+        cave
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_connected_caves() {
+        let cave = Grid::generate_caves([30, 50], 0.45, 5, 1);
+        assert_eq!(cave.dimensions(), &[30, 50]);
+        assert!(cave
+            .iter()
+            .eq(Grid::generate_caves([30, 50], 0.45, 5, 1).iter()));
+
+        let regions = cave.partition_regions(|a, b| a == b);
+        let floor_regions: std::collections::BTreeSet<_> = (regions.labels().iter())
+            .zip(cave.iter())
+            .filter(|(_, &wall)| !wall)
+            .map(|(label, _)| label)
+            .collect();
+        assert_eq!(floor_regions.len(), 1);
+
+        assert!(Grid::generate_caves([10, 10], 1.0, 3, 0).iter().all(|&w| w));
+    }
+}
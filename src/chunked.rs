@@ -0,0 +1,163 @@
+use crate::{error::check_coords, GridError, Shape};
+
+/// A grid stored as fixed-size chunks that are only allocated once a cell
+/// in them is written, for huge maps that are mostly one value. Reading an
+/// unwritten cell gives the fill value.
+///
+/// ```
+/// use md_grid::ChunkedGrid;
+///
+/// let mut world = ChunkedGrid::new('~', vec![100_000, 100_000], vec![64, 64]);
+/// world.set(&[5_000, 70_000], '#').unwrap();
+/// assert_eq!(world.allocated_chunks(), 1);
+/// assert_eq!(world.get(&[5_000, 70_000]), Ok(&'#'));
+/// assert_eq!(world.get(&[0, 0]), Ok(&'~'));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChunkedGrid<T> {
+    fill: T,
+    dimensions: Vec<usize>,
+    chunk_shape: Shape,
+    /// Number of chunks along each axis.
+    chunk_counts: Shape,
+    chunks: Vec<Option<Box<[T]>>>,
+}
+
+impl<T> ChunkedGrid<T> {
+    /// A grid of `dimensions` with every cell set to `fill`, stored in
+    /// chunks of `chunk_shape` cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_shape` has a different number of axes than
+    /// `dimensions` or any length in it is zero.
+    pub fn new(fill: T, dimensions: Vec<usize>, chunk_shape: Vec<usize>) -> Self {
+        assert_eq!(
+            chunk_shape.len(),
+            dimensions.len(),
+            "chunk shape must have one length per axis"
+        );
+        assert!(
+            chunk_shape.iter().all(|&len| len > 0),
+            "chunk lengths must be positive"
+        );
+        let chunk_counts: Vec<usize> = (dimensions.iter().zip(&chunk_shape))
+            .map(|(len, chunk)| len.div_ceil(*chunk))
+            .collect();
+        let chunk_counts = Shape::new(chunk_counts);
+        let mut chunks = Vec::new();
+        chunks.resize_with(chunk_counts.cell_count(), || None);
+        Self {
+            fill,
+            dimensions,
+            chunk_shape: Shape::new(chunk_shape),
+            chunk_counts,
+            chunks,
+        }
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    pub fn chunk_shape(&self) -> &[usize] {
+        &self.chunk_shape
+    }
+
+    /// The value of every cell that hasn't been written.
+    pub fn fill_value(&self) -> &T {
+        &self.fill
+    }
+
+    /// Number of chunks holding written cells.
+    pub fn allocated_chunks(&self) -> usize {
+        self.chunks.iter().filter(|c| c.is_some()).count()
+    }
+
+    /// The chunk holding `target` and the cell's index within it, if
+    /// `target` is on the grid.
+    fn locate(&self, target: &[usize]) -> Option<(usize, usize)> {
+        if target.len() != self.dimensions.len()
+            || target.iter().zip(&self.dimensions).any(|(c, len)| c >= len)
+        {
+            return None;
+        }
+        let (mut chunk, mut local) = (0, 0);
+        for ((&c, &chunk_len), &count) in target
+            .iter()
+            .zip(&*self.chunk_shape)
+            .zip(&*self.chunk_counts)
+        {
+            chunk = chunk * count + c / chunk_len;
+            local = local * chunk_len + c % chunk_len;
+        }
+        Some((chunk, local))
+    }
+
+    pub fn get(&self, target: &[usize]) -> Result<&T, GridError> {
+        check_coords(target, &self.dimensions)?;
+        Ok(self
+            .get_opt(target)
+            .expect("checked against the dimensions"))
+    }
+
+    pub fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        let (chunk, local) = self.locate(target)?;
+        Some(match &self.chunks[chunk] {
+            Some(cells) => &cells[local],
+            None => &self.fill,
+        })
+    }
+
+    /// Drops every chunk, returning all cells to the fill value.
+    pub fn clear(&mut self) {
+        self.chunks.iter_mut().for_each(|c| *c = None);
+    }
+}
+
+impl<T: Clone> ChunkedGrid<T> {
+    /// Mutable access to a cell, allocating its chunk if needed.
+    pub fn get_mut(&mut self, target: &[usize]) -> Result<&mut T, GridError> {
+        check_coords(target, &self.dimensions)?;
+        let (chunk, local) = self.locate(target).expect("checked against the dimensions");
+        let len = self.chunk_shape.cell_count();
+        let fill = &self.fill;
+        let cells = self.chunks[chunk].get_or_insert_with(|| vec![fill.clone(); len].into());
+        Ok(&mut cells[local])
+    }
+
+    pub fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        *self.get_mut(target)? = val;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grid, GridLike};
+
+    #[test]
+    fn chunks_allocate_on_write() {
+        let mut grid = ChunkedGrid::new(0u8, vec![10, 7], vec![4, 4]);
+        assert_eq!(grid.allocated_chunks(), 0);
+        grid.set(&[9, 6], 3).unwrap();
+        grid.set(&[8, 4], 2).unwrap();
+        *grid.get_mut(&[0, 0]).unwrap() += 1;
+        assert_eq!(grid.allocated_chunks(), 2);
+        assert!(grid.set(&[10, 0], 1).is_err());
+        assert!(grid.get(&[0, 7]).is_err());
+
+        let dense = GridLike::to_grid(&grid);
+        let expected = Grid::from_fn(vec![10, 7], |c| match (c[0], c[1]) {
+            (9, 6) => 3,
+            (8, 4) => 2,
+            (0, 0) => 1,
+            _ => 0,
+        });
+        assert!(dense.iter().eq(expected.iter()));
+
+        grid.clear();
+        assert_eq!(grid.get(&[9, 6]), Ok(&0));
+    }
+}
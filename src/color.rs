@@ -0,0 +1,97 @@
+use crate::{false_index, Coord, Grid, GridError};
+
+/// An opaque 8-bit RGB color, used by the grid renderers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Blends from `self` at `t = 0.0` to `other` at `t = 1.0`, clamping `t`
+    /// to that range.
+    pub fn lerp(self, other: Color, t: f64) -> Color {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color::rgb(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+
+    /// The color as `#rrggbb`.
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Renders a 2D grid to pixels, drawing each cell as a `scale × scale`
+    /// square of the color `color` picks for it.
+    pub fn render<F>(&self, scale: usize, mut color: F) -> Result<Grid<Color>, GridError>
+    where
+        F: FnMut(&Coord, &T) -> Color,
+    {
+        let &[rows, cols] = &self.dimensions[..] else {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            });
+        };
+
+        let colors: Vec<Color> = self
+            .grid
+            .iter()
+            .enumerate()
+            .map(|(i, val)| color(&false_index(i, &self.dimensions), val))
+            .collect();
+        let width = cols * scale;
+        let mut pixels = Vec::with_capacity(rows * scale * width);
+        for row in colors.chunks(cols.max(1)).take(rows) {
+            let line: Vec<Color> = row
+                .iter()
+                .flat_map(|&c| std::iter::repeat_n(c, scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+
+        Ok(Grid::from_parts(pixels, vec![rows * scale, width]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lerp_and_hex() {
+        let mid = Color::BLACK.lerp(Color::rgb(200, 100, 10), 0.5);
+        assert_eq!(mid, Color::rgb(100, 50, 5));
+        assert_eq!(Color::BLACK.lerp(Color::WHITE, 7.0), Color::WHITE);
+        assert_eq!(Color::rgb(255, 16, 1).to_hex(), "#ff1001");
+    }
+
+    #[test]
+    fn render() {
+        let mut grid = Grid::new(false, vec![2, 3]);
+        grid.set(&[0, 1], true).unwrap();
+
+        let pixels = grid
+            .render(2, |_, &v| if v { Color::WHITE } else { Color::BLACK })
+            .unwrap();
+        assert_eq!(pixels.dimensions(), &[4, 6]);
+        assert_eq!(*pixels.get(&[1, 3]).unwrap(), Color::WHITE);
+        assert_eq!(*pixels.get(&[2, 3]).unwrap(), Color::BLACK);
+    }
+}
@@ -0,0 +1,351 @@
+use std::{error::Error, fmt};
+
+use arrow_array::{cast::AsArray, types::*, Array, ArrowPrimitiveType, RecordBatch};
+use arrow_schema::{ArrowError, DataType};
+
+use crate::Grid;
+
+#[derive(Debug)]
+pub enum ColumnarError {
+    Arrow(ArrowError),
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
+    MissingColumn(String),
+    /// A column didn't have the Arrow type the grid was read as.
+    ColumnType {
+        column: String,
+        found: DataType,
+    },
+    /// A cell was null; grids have no representation for missing values.
+    Null {
+        column: String,
+        row: usize,
+    },
+    /// An x or y value was negative.
+    NegativeIndex {
+        column: String,
+        row: usize,
+    },
+    /// Record batches in one read had different numbers of columns.
+    ColumnCount {
+        expected: usize,
+        got: usize,
+    },
+    /// The x and y values index a grid too large to allocate.
+    TooLarge,
+}
+
+impl fmt::Display for ColumnarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnarError::Arrow(err) => write!(f, "ERROR: {}", err),
+            #[cfg(feature = "parquet")]
+            ColumnarError::Parquet(err) => write!(f, "ERROR: {}", err),
+            ColumnarError::MissingColumn(column) => {
+                write!(f, "ERROR: No column named {:?}", column)
+            }
+            ColumnarError::ColumnType { column, found } => write!(
+                f,
+                "ERROR: Column {:?} has unsupported type {}",
+                column, found
+            ),
+            ColumnarError::Null { column, row } => {
+                write!(f, "ERROR: Column {:?} is null at row {}", column, row)
+            }
+            ColumnarError::NegativeIndex { column, row } => write!(
+                f,
+                "ERROR: Column {:?} has a negative index at row {}",
+                column, row
+            ),
+            ColumnarError::ColumnCount { expected, got } => write!(
+                f,
+                "ERROR: Expected record batches with {} columns, got {}",
+                expected, got
+            ),
+            ColumnarError::TooLarge => {
+                write!(f, "ERROR: Indices describe a grid too large to allocate")
+            }
+        }
+    }
+}
+
+impl Error for ColumnarError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ColumnarError::Arrow(err) => Some(err),
+            #[cfg(feature = "parquet")]
+            ColumnarError::Parquet(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ArrowError> for ColumnarError {
+    fn from(err: ArrowError) -> Self {
+        ColumnarError::Arrow(err)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for ColumnarError {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        ColumnarError::Parquet(err)
+    }
+}
+
+fn column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a dyn Array, ColumnarError> {
+    batch
+        .column_by_name(name)
+        .map(|c| c.as_ref())
+        .ok_or_else(|| ColumnarError::MissingColumn(name.to_string()))
+}
+
+fn values<'a, A: ArrowPrimitiveType>(
+    array: &'a dyn Array,
+    name: &str,
+) -> Result<impl Iterator<Item = A::Native> + 'a, ColumnarError> {
+    let array = array
+        .as_primitive_opt::<A>()
+        .ok_or_else(|| ColumnarError::ColumnType {
+            column: name.to_string(),
+            found: array.data_type().clone(),
+        })?;
+    if let Some(row) = (0..array.len()).find(|&row| array.is_null(row)) {
+        return Err(ColumnarError::Null {
+            column: name.to_string(),
+            row,
+        });
+    }
+    Ok(array.values().iter().copied())
+}
+
+/// Reads an integer column of any width as indices.
+fn indices(array: &dyn Array, name: &str) -> Result<Vec<usize>, ColumnarError> {
+    macro_rules! convert {
+        ($($ty:ty),*) => {
+            match array.data_type() {
+                $(dt if dt == &<$ty>::DATA_TYPE => values::<$ty>(array, name)?
+                    .enumerate()
+                    .map(|(row, v)| {
+                        usize::try_from(v).map_err(|_| ColumnarError::NegativeIndex {
+                            column: name.to_string(),
+                            row,
+                        })
+                    })
+                    .collect(),)*
+                found => Err(ColumnarError::ColumnType {
+                    column: name.to_string(),
+                    found: found.clone(),
+                }),
+            }
+        };
+    }
+    convert!(
+        Int8Type, Int16Type, Int32Type, Int64Type, UInt8Type, UInt16Type, UInt32Type, UInt64Type
+    )
+}
+
+impl<T: Clone> Grid<T> {
+    /// Builds a 2D grid with one row per record and one grid column per
+    /// Arrow column, reading every column as `A`. Batches are stacked in order.
+    pub fn from_arrow_columns<A>(batches: &[RecordBatch]) -> Result<Self, ColumnarError>
+    where
+        A: ArrowPrimitiveType<Native = T>,
+    {
+        let cols = batches.first().map_or(0, |b| b.num_columns());
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        let mut grid = Vec::with_capacity(rows * cols);
+
+        for batch in batches {
+            if batch.num_columns() != cols {
+                return Err(ColumnarError::ColumnCount {
+                    expected: cols,
+                    got: batch.num_columns(),
+                });
+            }
+            let schema = batch.schema();
+            let mut columns = batch
+                .columns()
+                .iter()
+                .zip(schema.fields())
+                .map(|(array, field)| values::<A>(array.as_ref(), field.name()))
+                .collect::<Result<Vec<_>, _>>()?;
+            for _ in 0..batch.num_rows() {
+                grid.extend(columns.iter_mut().map(|c| c.next().unwrap()));
+            }
+        }
+
+        Ok(Grid::from_parts(grid, vec![rows, cols]))
+    }
+
+    /// Builds a 2D grid from `x`, `y` and `value` columns, placing each value
+    /// at `[y, x]`. The grid is just large enough for the largest indices;
+    /// cells without a record are `default_value`, and later records
+    /// overwrite earlier ones. Index columns may be any integer type.
+    pub fn from_arrow_triplets<A>(
+        batches: &[RecordBatch],
+        x: &str,
+        y: &str,
+        value: &str,
+        default_value: T,
+    ) -> Result<Self, ColumnarError>
+    where
+        A: ArrowPrimitiveType<Native = T>,
+    {
+        let mut cells = Vec::new();
+        for batch in batches {
+            let xs = indices(column(batch, x)?, x)?;
+            let ys = indices(column(batch, y)?, y)?;
+            let vals = values::<A>(column(batch, value)?, value)?;
+            cells.extend(ys.into_iter().zip(xs).zip(vals));
+        }
+
+        let extent = |max: Option<usize>| max.map_or(Some(0), |max| max.checked_add(1));
+        let rows = extent(cells.iter().map(|((r, _), _)| *r).max());
+        let cols = extent(cells.iter().map(|((_, c), _)| *c).max());
+        let (Some(rows), Some(cols)) = (rows, cols) else {
+            return Err(ColumnarError::TooLarge);
+        };
+        let mut grid =
+            Grid::try_new(default_value, vec![rows, cols]).ok_or(ColumnarError::TooLarge)?;
+        for ((r, c), v) in cells {
+            grid.grid[r * cols + c] = v;
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn read_batches<R>(reader: R) -> Result<Vec<RecordBatch>, ColumnarError>
+where
+    R: parquet::file::reader::ChunkReader + 'static,
+{
+    parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(reader)?
+        .build()?
+        .map(|batch| batch.map_err(ColumnarError::from))
+        .collect()
+}
+
+#[cfg(feature = "parquet")]
+impl<T: Clone> Grid<T> {
+    /// Reads a Parquet file laid out as for [`Grid::from_arrow_columns`].
+    pub fn read_parquet_columns<A, R>(reader: R) -> Result<Self, ColumnarError>
+    where
+        A: ArrowPrimitiveType<Native = T>,
+        R: parquet::file::reader::ChunkReader + 'static,
+    {
+        Self::from_arrow_columns::<A>(&read_batches(reader)?)
+    }
+
+    /// Reads a Parquet file laid out as for [`Grid::from_arrow_triplets`].
+    pub fn read_parquet_triplets<A, R>(
+        reader: R,
+        x: &str,
+        y: &str,
+        value: &str,
+        default_value: T,
+    ) -> Result<Self, ColumnarError>
+    where
+        A: ArrowPrimitiveType<Native = T>,
+        R: parquet::file::reader::ChunkReader + 'static,
+    {
+        Self::from_arrow_triplets::<A>(&read_batches(reader)?, x, y, value, default_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Float64Array, Int32Array, UInt16Array, UInt64Array};
+
+    use super::*;
+
+    fn batch(columns: Vec<(&str, ArrayRef)>) -> RecordBatch {
+        RecordBatch::try_from_iter(columns).unwrap()
+    }
+
+    #[test]
+    fn columns_and_triplets() {
+        let a = batch(vec![
+            (
+                "a",
+                Arc::new(Float64Array::from(vec![1.0, 2.0])) as ArrayRef,
+            ),
+            ("b", Arc::new(Float64Array::from(vec![3.0, 4.0]))),
+        ]);
+        let b = batch(vec![
+            ("a", Arc::new(Float64Array::from(vec![5.0])) as ArrayRef),
+            ("b", Arc::new(Float64Array::from(vec![6.0]))),
+        ]);
+        let grid = Grid::from_arrow_columns::<Float64Type>(&[a, b]).unwrap();
+        assert_eq!(grid.dimensions(), &[3, 2]);
+        assert!(grid.iter().eq(&[1.0, 3.0, 2.0, 4.0, 5.0, 6.0]));
+
+        let nulls = batch(vec![(
+            "a",
+            Arc::new(Float64Array::from(vec![Some(1.0), None])) as ArrayRef,
+        )]);
+        assert!(matches!(
+            Grid::from_arrow_columns::<Float64Type>(&[nulls]),
+            Err(ColumnarError::Null { row: 1, .. })
+        ));
+
+        let triplets = batch(vec![
+            ("x", Arc::new(UInt16Array::from(vec![0, 2])) as ArrayRef),
+            ("y", Arc::new(Int32Array::from(vec![1, 0]))),
+            ("v", Arc::new(Float64Array::from(vec![7.0, 8.0]))),
+        ]);
+        let grid =
+            Grid::from_arrow_triplets::<Float64Type>(&[triplets], "x", "y", "v", 0.0).unwrap();
+        assert_eq!(grid.dimensions(), &[2, 3]);
+        assert_eq!(*grid.get(&[1, 0]).unwrap(), 7.0);
+        assert_eq!(*grid.get(&[0, 2]).unwrap(), 8.0);
+        assert_eq!(*grid.get(&[1, 2]).unwrap(), 0.0);
+
+        let huge = batch(vec![
+            (
+                "x",
+                Arc::new(UInt64Array::from(vec![u64::MAX / 2])) as ArrayRef,
+            ),
+            ("y", Arc::new(UInt64Array::from(vec![u64::MAX / 2]))),
+            ("v", Arc::new(Float64Array::from(vec![1.0]))),
+        ]);
+        assert!(matches!(
+            Grid::from_arrow_triplets::<Float64Type>(&[huge], "x", "y", "v", 0.0),
+            Err(ColumnarError::TooLarge)
+        ));
+        let wide = batch(vec![
+            (
+                "x",
+                Arc::new(UInt64Array::from(vec![1u64 << 31])) as ArrayRef,
+            ),
+            ("y", Arc::new(UInt64Array::from(vec![1u64 << 31]))),
+            ("v", Arc::new(Float64Array::from(vec![1.0]))),
+        ]);
+        assert!(matches!(
+            Grid::from_arrow_triplets::<Float64Type>(&[wide], "x", "y", "v", 0.0),
+            Err(ColumnarError::TooLarge)
+        ));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_round_trip() {
+        let data = batch(vec![
+            ("a", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            ("b", Arc::new(Int32Array::from(vec![4, 5, 6]))),
+        ]);
+        let path = std::env::temp_dir().join(format!("md-grid-{}.parquet", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(file, data.schema(), None).unwrap();
+        writer.write(&data).unwrap();
+        writer.close().unwrap();
+
+        let grid = Grid::read_parquet_columns::<Int32Type, _>(std::fs::File::open(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+        let grid = grid.unwrap();
+        assert_eq!(grid.dimensions(), &[3, 2]);
+        assert!(grid.iter().eq(&[1, 4, 2, 5, 3, 6]));
+    }
+}
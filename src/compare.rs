@@ -0,0 +1,452 @@
+use std::fmt::{self, Debug, Display};
+
+use crate::{false_index, Coord, Grid, GridError, Region, Shape};
+
+/// Number of mismatches recorded by [`Grid::compare`].
+pub const DEFAULT_MISMATCH_LIMIT: usize = 10;
+
+/// Largest axis length that [`GridComparison::side_by_side`] will render.
+pub const SIDE_BY_SIDE_MAX_LEN: usize = 32;
+
+/// A single cell that differs between two grids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch<'a, T> {
+    pub coords: Coord,
+    pub expected: &'a T,
+    pub actual: &'a T,
+}
+
+/// Result of comparing an expected grid against an actual one.
+#[derive(Debug, Clone)]
+pub struct GridComparison<'a, T> {
+    expected: &'a Grid<T>,
+    actual: &'a Grid<T>,
+    mismatch_count: usize,
+    mismatches: Vec<Mismatch<'a, T>>,
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Compares `self` (expected) against `actual`, recording the first
+    /// [`DEFAULT_MISMATCH_LIMIT`] differing cells.
+    pub fn compare<'a>(&'a self, actual: &'a Grid<T>) -> GridComparison<'a, T> {
+        self.compare_with_limit(actual, DEFAULT_MISMATCH_LIMIT)
+    }
+
+    /// Like [`Grid::compare`], but records at most `limit` mismatches.
+    pub fn compare_with_limit<'a>(
+        &'a self,
+        actual: &'a Grid<T>,
+        limit: usize,
+    ) -> GridComparison<'a, T> {
+        let mut mismatch_count = 0;
+        let mut mismatches = Vec::new();
+
+        if self.dimensions == actual.dimensions {
+            for (i, (e, a)) in self.grid.iter().zip(actual.grid.iter()).enumerate() {
+                if e == a {
+                    continue;
+                }
+                mismatch_count += 1;
+                if mismatches.len() < limit {
+                    mismatches.push(Mismatch {
+                        coords: false_index(i, &self.dimensions),
+                        expected: e,
+                        actual: a,
+                    });
+                }
+            }
+        }
+
+        GridComparison {
+            expected: self,
+            actual,
+            mismatch_count,
+            mismatches,
+        }
+    }
+
+    /// The first origin, in row-major order, at which `other` appears
+    /// cell for cell inside `self`, or `None` if it appears nowhere.
+    pub fn find_subgrid(&self, other: &Grid<T>) -> Option<Coord> {
+        if other.axes != self.axes {
+            return None;
+        }
+        let origins = Region::new(
+            (self.dimensions.iter().zip(&other.dimensions))
+                .map(|(&outer, &inner)| 0..(outer + 1).saturating_sub(inner))
+                .collect(),
+        );
+        origins.iter().find(|origin| {
+            other.grid.iter().enumerate().all(|(i, cell)| {
+                let mut coords = false_index(i, &other.dimensions);
+                coords.iter_mut().zip(origin).for_each(|(c, o)| *c += o);
+                self.get_opt(&coords) == Some(cell)
+            })
+        })
+    }
+
+    /// Whether `other` appears cell for cell somewhere inside `self`.
+    pub fn contains_subgrid(&self, other: &Grid<T>) -> bool {
+        self.find_subgrid(other).is_some()
+    }
+
+    /// Whether both grids hold the same cells once the borders made up only
+    /// of `default` are trimmed away, wherever each one sits in its grid.
+    pub fn equals_ignoring_padding(&self, other: &Grid<T>, default: &T) -> bool {
+        if other.axes != self.axes {
+            return false;
+        }
+        match (self.content_bounds(default), other.content_bounds(default)) {
+            (None, None) => true,
+            (Some(a), Some(b)) if a.shape() == b.shape() => a
+                .iter()
+                .zip(&b)
+                .all(|(x, y)| self.get_opt(&x) == other.get_opt(&y)),
+            _ => false,
+        }
+    }
+
+    /// The smallest region holding every cell that isn't `default`.
+    fn content_bounds(&self, default: &T) -> Option<Region> {
+        let mut extent: Option<Vec<(usize, usize)>> = None;
+        for (i, _) in self.grid.iter().enumerate().filter(|(_, v)| *v != default) {
+            let coords = false_index(i, &self.dimensions);
+            let extent = extent.get_or_insert_with(|| coords.iter().map(|&c| (c, c)).collect());
+            for ((lo, hi), &c) in extent.iter_mut().zip(&coords) {
+                (*lo, *hi) = ((*lo).min(c), (*hi).max(c));
+            }
+        }
+        extent.map(|e| Region::new(e.iter().map(|&(lo, hi)| lo..hi + 1).collect()))
+    }
+}
+
+impl<'a, T> GridComparison<'a, T> {
+    pub fn is_equal(&self) -> bool {
+        !self.shape_mismatch() && self.mismatch_count == 0
+    }
+
+    pub fn shape_mismatch(&self) -> bool {
+        self.expected.dimensions != self.actual.dimensions
+    }
+
+    /// Total number of differing cells, including ones past the recording limit.
+    pub fn mismatch_count(&self) -> usize {
+        self.mismatch_count
+    }
+
+    /// The recorded mismatches, in row-major order.
+    pub fn mismatches(&self) -> &[Mismatch<'a, T>] {
+        &self.mismatches
+    }
+}
+
+impl<'a, T: Debug> GridComparison<'a, T> {
+    /// Renders both grids next to each other with differing cells marked by
+    /// `*`. Returns `None` unless both grids are 2D, share a shape, and no
+    /// axis is longer than [`SIDE_BY_SIDE_MAX_LEN`].
+    pub fn side_by_side(&self) -> Option<String> {
+        let dims = &self.expected.dimensions;
+        if dims.len() != 2
+            || self.shape_mismatch()
+            || dims.iter().any(|&d| d > SIDE_BY_SIDE_MAX_LEN)
+        {
+            return None;
+        }
+        let (rows, cols) = (dims[0], dims[1]);
+
+        let expected: Vec<String> = self
+            .expected
+            .grid
+            .iter()
+            .map(|v| format!("{v:?}"))
+            .collect();
+        let actual: Vec<String> = self.actual.grid.iter().map(|v| format!("{v:?}")).collect();
+        let width = expected
+            .iter()
+            .chain(actual.iter())
+            .map(|s| s.len())
+            .max()
+            .unwrap_or(0);
+
+        let render_row = |cells: &[String], row: usize| -> String {
+            (0..cols)
+                .map(|col| {
+                    let i = row * cols + col;
+                    let marker = if expected[i] != actual[i] { '*' } else { ' ' };
+                    format!("{:>width$}{marker}", cells[i])
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let row_width = (cols * (width + 2)).saturating_sub(1);
+        let mut out = format!("{:<row_width$} | actual\n", "expected");
+        for row in 0..rows {
+            out.push_str(&format!(
+                "{} | {}\n",
+                render_row(&expected, row),
+                render_row(&actual, row)
+            ));
+        }
+        Some(out)
+    }
+}
+
+impl<'a, T: Debug> Display for GridComparison<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.shape_mismatch() {
+            return writeln!(
+                f,
+                "grids differ in shape: expected {:?}, actual {:?}",
+                self.expected.dimensions, self.actual.dimensions
+            );
+        }
+        if self.mismatch_count == 0 {
+            return writeln!(f, "grids are equal");
+        }
+
+        writeln!(f, "grids differ in {} cell(s):", self.mismatch_count)?;
+        for m in &self.mismatches {
+            writeln!(
+                f,
+                "  at {:?}: expected {:?}, actual {:?}",
+                m.coords, m.expected, m.actual
+            )?;
+        }
+        if self.mismatch_count > self.mismatches.len() {
+            writeln!(
+                f,
+                "  ... and {} more",
+                self.mismatch_count - self.mismatches.len()
+            )?;
+        }
+        if let Some(diff) = self.side_by_side() {
+            write!(f, "\n{diff}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A connected group of differing cells found by
+/// [`Grid::compare_with_tolerance`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffRegion {
+    /// The smallest region containing every cell of the group.
+    pub bounds: Region,
+    /// Number of differing cells in the group.
+    pub cells: usize,
+}
+
+/// Differing cells between two grids, grouped into orthogonally connected
+/// regions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffReport {
+    mismatch_count: usize,
+    regions: Vec<DiffRegion>,
+}
+
+impl DiffReport {
+    pub fn is_equal(&self) -> bool {
+        self.mismatch_count == 0
+    }
+
+    pub fn mismatch_count(&self) -> usize {
+        self.mismatch_count
+    }
+
+    /// The groups of differing cells, in row-major order of their first
+    /// cell.
+    pub fn regions(&self) -> &[DiffRegion] {
+        &self.regions
+    }
+}
+
+impl Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_equal() {
+            return writeln!(f, "grids are equal within tolerance");
+        }
+        writeln!(
+            f,
+            "grids differ in {} cell(s) across {} region(s):",
+            self.mismatch_count,
+            self.regions.len()
+        )?;
+        for region in &self.regions {
+            writeln!(
+                f,
+                "  {:?}: {} cell(s)",
+                region.bounds.ranges(),
+                region.cells
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Compares `self` against `other` cell by cell, treating cells as equal
+    /// when `matches(a, b)` is true (e.g. `|a, b| (a - b).abs() < 1e-9`), and
+    /// groups the cells that differ into connected regions.
+    pub fn compare_with_tolerance<F>(
+        &self,
+        other: &Grid<T>,
+        mut matches: F,
+    ) -> Result<DiffReport, GridError>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        Shape::from(&self.dimensions[..]).check_eq(&other.dimensions)?;
+        let differs = Grid::from_parts(
+            (self.grid.iter().zip(&other.grid))
+                .map(|(a, b)| !matches(a, b))
+                .collect(),
+            self.dimensions.clone(),
+        );
+        let partition = differs.partition_regions(|a, b| a == b);
+
+        // Bounds of each region of differing cells, indexed by label.
+        let mut bounds: Vec<Option<Vec<(usize, usize)>>> = vec![None; partition.region_count()];
+        for (i, &label) in partition.labels().grid.iter().enumerate() {
+            if !differs.grid[i] {
+                continue;
+            }
+            let coords = false_index(i, &self.dimensions);
+            let extent =
+                bounds[label].get_or_insert_with(|| coords.iter().map(|&c| (c, c)).collect());
+            for ((lo, hi), &c) in extent.iter_mut().zip(&coords) {
+                (*lo, *hi) = ((*lo).min(c), (*hi).max(c));
+            }
+        }
+
+        let mut seen = vec![false; partition.region_count()];
+        let mut regions = Vec::new();
+        for (i, &label) in partition.labels().grid.iter().enumerate() {
+            if !differs.grid[i] || std::mem::replace(&mut seen[label], true) {
+                continue;
+            }
+            let extent = bounds[label].as_ref().expect("every region has a cell");
+            regions.push(DiffRegion {
+                bounds: Region::new(extent.iter().map(|&(lo, hi)| lo..hi + 1).collect()),
+                cells: partition.sizes()[label],
+            });
+        }
+
+        Ok(DiffReport {
+            mismatch_count: differs.grid.iter().filter(|&&d| d).count(),
+            regions,
+        })
+    }
+}
+
+/// Asserts that two grids are equal, panicking with a [`GridComparison`]
+/// report otherwise.
+#[macro_export]
+macro_rules! assert_grid_eq {
+    ($expected:expr, $actual:expr $(,)?) => {
+        match (&$expected, &$actual) {
+            (expected, actual) => {
+                let comparison = $crate::Grid::compare(expected, actual);
+                if !comparison.is_equal() {
+                    panic!("assertion `expected == actual` failed\n{}", comparison);
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn compare_reports_mismatches() {
+        let expected = Grid::new(0, vec![3, 4]);
+        let mut actual = expected.clone();
+        actual.set(&[1, 2], 7).unwrap();
+        actual.set(&[2, 0], 3).unwrap();
+
+        let comparison = expected.compare_with_limit(&actual, 1);
+        assert!(!comparison.is_equal());
+        assert_eq!(comparison.mismatch_count(), 2);
+        assert_eq!(
+            comparison.mismatches(),
+            &[Mismatch {
+                coords: smallvec![1, 2],
+                expected: &0,
+                actual: &7
+            }]
+        );
+
+        let diff = comparison.side_by_side().unwrap();
+        assert_eq!(diff.lines().nth(2).unwrap(), "0  0  0* 0  | 0  0  7* 0 ");
+
+        assert!(!expected.compare(&Grid::new(0, vec![4, 3])).is_equal());
+        assert_grid_eq!(expected, expected.clone());
+
+        for dims in [vec![3, 0], vec![0, 0]] {
+            let empty = Grid::new(0, dims);
+            let diff = empty.compare(&empty).side_by_side().unwrap();
+            assert!(diff.starts_with("expected | actual"));
+        }
+    }
+
+    #[test]
+    fn subgrids_and_padding() {
+        let room = Grid::from_fn(vec![2, 2], |c| c[0] * 2 + c[1] + 1);
+        let mut map = Grid::new(0, vec![4, 5]);
+        for (coords, &v) in room.iter_indexed() {
+            map.set(&[coords[0] + 1, coords[1] + 2], v).unwrap();
+        }
+        assert_eq!(map.find_subgrid(&room), Some(smallvec![1, 2]));
+        assert!(map.contains_subgrid(&Grid::new(0, vec![1, 5])));
+        assert!(!map.contains_subgrid(&Grid::new(9, vec![1, 1])));
+        assert!(!room.contains_subgrid(&map));
+
+        let mut shifted = Grid::new(0, vec![3, 3]);
+        for (coords, &v) in room.iter_indexed() {
+            shifted.set(&coords, v).unwrap();
+        }
+        assert!(map.equals_ignoring_padding(&shifted, &0));
+        shifted.set(&[2, 2], 9).unwrap();
+        assert!(!map.equals_ignoring_padding(&shifted, &0));
+        assert!(Grid::new(0, vec![2]).equals_ignoring_padding(&Grid::new(0, vec![7]), &0));
+    }
+
+    #[test]
+    fn tolerance_regions() {
+        let serial = Grid::from_fn(vec![4, 5], |c| (c[0] * 5 + c[1]) as f64);
+        let mut parallel = serial.clone();
+        *parallel.get_mut(&[0, 0]).unwrap() += 1e-12;
+        *parallel.get_mut(&[1, 3]).unwrap() += 0.5;
+        *parallel.get_mut(&[2, 3]).unwrap() += 0.5;
+        *parallel.get_mut(&[2, 4]).unwrap() -= 0.5;
+        *parallel.get_mut(&[3, 0]).unwrap() += 0.5;
+
+        let close = |a: &f64, b: &f64| (a - b).abs() < 1e-9;
+        let report = serial.compare_with_tolerance(&parallel, close).unwrap();
+        assert_eq!(report.mismatch_count(), 4);
+        assert_eq!(
+            report.regions(),
+            &[
+                DiffRegion {
+                    bounds: Region::new(vec![1..3, 3..5]),
+                    cells: 3
+                },
+                DiffRegion {
+                    bounds: Region::new(vec![3..4, 0..1]),
+                    cells: 1
+                },
+            ]
+        );
+        assert!(report.to_string().contains("across 2 region(s)"));
+
+        assert!(serial
+            .compare_with_tolerance(&serial, close)
+            .unwrap()
+            .is_equal());
+        let other = Grid::new(0.0, vec![5, 4]);
+        assert!(serial.compare_with_tolerance(&other, close).is_err());
+    }
+}
@@ -0,0 +1,638 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    io::{self, Read, Write},
+};
+
+use crate::{Grid, GridSet, Shape};
+
+const MAGIC: &[u8; 4] = b"MDGR";
+/// Starts a container sealed with [`GridFormat::key`].
+const SEALED_MAGIC: &[u8; 4] = b"MDGS";
+const FORMAT_VERSION: u16 = 1;
+
+/// A cell type that can be stored in the binary container.
+pub trait ElementCodec: Sized {
+    /// Names the element encoding. It is stored in every file and must match
+    /// on load, so change it whenever the encoding changes.
+    const SCHEMA: &'static str;
+
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decodes one element from the front of `input`, advancing it.
+    fn decode(input: &mut &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_element_codec {
+    ($($ty:ty => $schema:literal),* $(,)?) => {$(
+        impl ElementCodec for $ty {
+            const SCHEMA: &'static str = $schema;
+
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn decode(input: &mut &[u8]) -> Option<Self> {
+                let (bytes, rest) = input.split_first_chunk()?;
+                *input = rest;
+                Some(<$ty>::from_le_bytes(*bytes))
+            }
+        }
+    )*};
+}
+
+impl_element_codec!(
+    u8 => "u8", u16 => "u16", u32 => "u32", u64 => "u64",
+    i8 => "i8", i16 => "i16", i32 => "i32", i64 => "i64",
+    f32 => "f32", f64 => "f64",
+);
+
+impl ElementCodec for bool {
+    const SCHEMA: &'static str = "bool";
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+/// A stable name for a cell type, written into grids serialized with serde
+/// and checked when they're read back. Unlike [`std::any::type_name`] it
+/// doesn't change between compiler versions or when a type is moved, so
+/// keep it the same for as long as the serialized form is.
+///
+/// Every [`ElementCodec`] type is tagged with its schema; implement this
+/// directly for other cell types.
+pub trait ElementTag {
+    const TAG: &'static str;
+}
+
+impl<T: ElementCodec> ElementTag for T {
+    const TAG: &'static str = T::SCHEMA;
+}
+
+macro_rules! impl_element_tag {
+    ($($ty:ty => $tag:literal),* $(,)?) => {$(
+        impl ElementTag for $ty {
+            const TAG: &'static str = $tag;
+        }
+    )*};
+}
+
+impl_element_tag!(usize => "usize", isize => "isize", char => "char", String => "String");
+
+/// How the cells are laid out in the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Codec {
+    /// Every cell in row-major order.
+    #[default]
+    Raw,
+    /// Runs of identical cells stored once with a `u32` repeat count.
+    RunLength,
+}
+
+impl Codec {
+    fn id(self) -> u8 {
+        match self {
+            Codec::Raw => 0,
+            Codec::RunLength => 1,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Codec::Raw),
+            1 => Some(Codec::RunLength),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ContainerError {
+    Io(io::Error),
+    /// The data doesn't start with the container's magic bytes.
+    BadMagic,
+    /// The container layout is newer than this version of the crate.
+    UnsupportedFormat(u16),
+    UnknownCodec(u8),
+    /// The data ended early or a cell couldn't be decoded.
+    Corrupt,
+    /// The file was written by a newer version of the application.
+    VersionTooNew {
+        found: u32,
+        supported: u32,
+    },
+    /// No migration is registered to upgrade from this version.
+    MissingMigration {
+        from: u32,
+    },
+    /// A migration didn't move the data to a later version.
+    MigrationStalled {
+        from: u32,
+    },
+    SchemaMismatch {
+        expected: String,
+        found: String,
+    },
+    /// A migration or caller rejected the data.
+    Invalid(String),
+    /// The data is sealed and no key or the wrong key was given, or it was
+    /// modified after sealing.
+    Unauthenticated,
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerError::Io(err) => write!(f, "ERROR: {}", err),
+            ContainerError::BadMagic => write!(f, "ERROR: Not a grid container"),
+            ContainerError::UnsupportedFormat(v) => {
+                write!(f, "ERROR: Unsupported container format version {}", v)
+            }
+            ContainerError::UnknownCodec(id) => write!(f, "ERROR: Unknown payload codec {}", id),
+            ContainerError::Corrupt => write!(f, "ERROR: Container data is truncated or corrupt"),
+            ContainerError::VersionTooNew { found, supported } => write!(
+                f,
+                "ERROR: Data version {} is newer than supported version {}",
+                found, supported
+            ),
+            ContainerError::MissingMigration { from } => {
+                write!(f, "ERROR: No migration registered from version {}", from)
+            }
+            ContainerError::MigrationStalled { from } => write!(
+                f,
+                "ERROR: Migration from version {} did not produce a later version",
+                from
+            ),
+            ContainerError::SchemaMismatch { expected, found } => write!(
+                f,
+                "ERROR: Expected elements of schema {:?}, found {:?}",
+                expected, found
+            ),
+            ContainerError::Invalid(msg) => write!(f, "ERROR: {}", msg),
+            ContainerError::Unauthenticated => {
+                write!(f, "ERROR: Container is sealed and failed authentication")
+            }
+        }
+    }
+}
+
+impl Error for ContainerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ContainerError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ContainerError {
+    fn from(err: io::Error) -> Self {
+        ContainerError::Io(err)
+    }
+}
+
+/// A container's contents before its cells are decoded, as handed to
+/// migrations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedGrid {
+    pub version: u32,
+    pub schema: String,
+    pub codec: Codec,
+    pub dimensions: Vec<usize>,
+    pub payload: Vec<u8>,
+}
+
+impl SavedGrid {
+    pub fn encode<T: ElementCodec>(grid: &Grid<T>, version: u32, codec: Codec) -> Self {
+        let mut payload = Vec::new();
+        match codec {
+            Codec::Raw => grid.grid.iter().for_each(|v| v.encode(&mut payload)),
+            Codec::RunLength => {
+                let mut encoded = Vec::new();
+                let mut run: Option<(Vec<u8>, u32)> = None;
+                for v in &grid.grid {
+                    encoded.clear();
+                    v.encode(&mut encoded);
+                    match &mut run {
+                        Some((bytes, count)) if *bytes == encoded && *count < u32::MAX => {
+                            *count += 1
+                        }
+                        _ => {
+                            if let Some((bytes, count)) = run.take() {
+                                payload.extend_from_slice(&count.to_le_bytes());
+                                payload.extend_from_slice(&bytes);
+                            }
+                            run = Some((encoded.clone(), 1));
+                        }
+                    }
+                }
+                if let Some((bytes, count)) = run {
+                    payload.extend_from_slice(&count.to_le_bytes());
+                    payload.extend_from_slice(&bytes);
+                }
+            }
+        }
+
+        Self {
+            version,
+            schema: T::SCHEMA.to_string(),
+            codec,
+            dimensions: grid.dimensions.clone(),
+            payload,
+        }
+    }
+
+    /// Decodes the cells, checking that they were written as `T`.
+    pub fn decode<T: Clone + ElementCodec>(&self) -> Result<Grid<T>, ContainerError> {
+        if self.schema != T::SCHEMA {
+            return Err(ContainerError::SchemaMismatch {
+                expected: T::SCHEMA.to_string(),
+                found: self.schema.clone(),
+            });
+        }
+
+        let len = Shape::from(&self.dimensions[..])
+            .checked_cell_count()
+            .ok_or(ContainerError::Corrupt)?;
+        // Don't trust the header with the allocation size: a raw payload has
+        // at least one byte per cell, and run-length data grows as it goes.
+        let mut grid = Vec::with_capacity(len.min(self.payload.len()));
+        let mut input = &self.payload[..];
+        while grid.len() < len {
+            let count = match self.codec {
+                Codec::Raw => 1,
+                Codec::RunLength => {
+                    u32::decode(&mut input).ok_or(ContainerError::Corrupt)? as usize
+                }
+            };
+            let val = T::decode(&mut input).ok_or(ContainerError::Corrupt)?;
+            if count == 0 || grid.len() + count > len {
+                return Err(ContainerError::Corrupt);
+            }
+            grid.resize(grid.len() + count, val);
+        }
+        if !input.is_empty() {
+            return Err(ContainerError::Corrupt);
+        }
+
+        Ok(Grid::from_parts(grid, self.dimensions.clone()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.payload.len() + 64);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out.extend_from_slice(&(self.schema.len() as u32).to_le_bytes());
+        out.extend_from_slice(self.schema.as_bytes());
+        out.push(self.codec.id());
+        out.extend_from_slice(&(self.dimensions.len() as u32).to_le_bytes());
+        for &d in &self.dimensions {
+            out.extend_from_slice(&(d as u64).to_le_bytes());
+        }
+        out.extend_from_slice(&(self.payload.len() as u64).to_le_bytes());
+        out.extend_from_slice(&self.payload);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ContainerError> {
+        let mut input = bytes;
+        let mut take = |len: usize| -> Result<&[u8], ContainerError> {
+            if input.len() < len {
+                return Err(ContainerError::Corrupt);
+            }
+            let (head, rest) = input.split_at(len);
+            input = rest;
+            Ok(head)
+        };
+
+        match take(4)? {
+            magic if magic == MAGIC => {}
+            magic if magic == SEALED_MAGIC => return Err(ContainerError::Unauthenticated),
+            _ => return Err(ContainerError::BadMagic),
+        }
+        let format = u16::decode(&mut take(2)?).unwrap();
+        if format != FORMAT_VERSION {
+            return Err(ContainerError::UnsupportedFormat(format));
+        }
+        let version = u32::decode(&mut take(4)?).unwrap();
+        let schema_len = u32::decode(&mut take(4)?).unwrap() as usize;
+        let schema =
+            String::from_utf8(take(schema_len)?.to_vec()).map_err(|_| ContainerError::Corrupt)?;
+        let codec_id = take(1)?[0];
+        let codec = Codec::from_id(codec_id).ok_or(ContainerError::UnknownCodec(codec_id))?;
+        let axes = u32::decode(&mut take(4)?).unwrap() as usize;
+        let mut dimensions = Vec::new();
+        for _ in 0..axes {
+            let d = u64::decode(&mut take(8)?).unwrap();
+            dimensions.push(usize::try_from(d).map_err(|_| ContainerError::Corrupt)?);
+        }
+        let payload_len = u64::decode(&mut take(8)?).unwrap();
+        let payload_len = usize::try_from(payload_len).map_err(|_| ContainerError::Corrupt)?;
+        let payload = take(payload_len)?.to_vec();
+        if !input.is_empty() {
+            return Err(ContainerError::Corrupt);
+        }
+
+        Ok(Self {
+            version,
+            schema,
+            codec,
+            dimensions,
+            payload,
+        })
+    }
+}
+
+type Migration = Box<dyn Fn(SavedGrid) -> Result<SavedGrid, ContainerError>>;
+
+/// Reads and writes grids in a versioned binary container.
+///
+/// `version` is the application's own data version, written into every file.
+/// Files from older versions are upgraded on load by the registered
+/// migrations, each taking data of one version to a later one.
+///
+/// ```
+/// use md_grid::{container::{GridFormat, SavedGrid}, Grid};
+///
+/// // Version 1 stored u8 cells, version 2 stores u16 cells.
+/// let old = GridFormat::new(1).save(&Grid::new(7u8, vec![2, 2]));
+///
+/// let format = GridFormat::new(2).migration(1, |saved| {
+///     let grid = saved.decode::<u8>()?;
+///     let mut wide = Grid::new(0u16, grid.dimensions().to_vec());
+///     for (w, &v) in wide.iter_mut().zip(grid.iter()) {
+///         *w = v as u16 * 100;
+///     }
+///     Ok(SavedGrid::encode(&wide, 2, saved.codec))
+/// });
+/// let grid = format.load::<u16>(&old).unwrap();
+/// assert!(grid.iter().all(|&v| v == 700));
+/// ```
+pub struct GridFormat {
+    version: u32,
+    codec: Codec,
+    migrations: BTreeMap<u32, Migration>,
+    #[cfg(feature = "encryption")]
+    key: Option<[u8; 32]>,
+}
+
+impl GridFormat {
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            codec: Codec::default(),
+            migrations: BTreeMap::new(),
+            #[cfg(feature = "encryption")]
+            key: None,
+        }
+    }
+
+    /// Sets the payload codec used when saving. Loading accepts any codec.
+    pub fn codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Registers a migration upgrading data saved as `from_version`.
+    pub fn migration<F>(mut self, from_version: u32, migrate: F) -> Self
+    where
+        F: Fn(SavedGrid) -> Result<SavedGrid, ContainerError> + 'static,
+    {
+        self.migrations.insert(from_version, Box::new(migrate));
+        self
+    }
+
+    /// Seals saved containers with ChaCha20-Poly1305 under `key`, so they
+    /// can't be read or edited without it. Loading then only accepts data
+    /// sealed with the same key.
+    #[cfg(feature = "encryption")]
+    pub fn key(mut self, key: [u8; 32]) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn save<T: ElementCodec>(&self, grid: &Grid<T>) -> Vec<u8> {
+        self.seal_output(SavedGrid::encode(grid, self.version, self.codec).to_bytes())
+    }
+
+    pub fn load<T: Clone + ElementCodec>(&self, bytes: &[u8]) -> Result<Grid<T>, ContainerError> {
+        let bytes = self.open_input(bytes)?;
+        self.upgrade(SavedGrid::from_bytes(&bytes)?)?.decode()
+    }
+
+    /// Saves every grid of `set` in one file. Each grid is stored as its own
+    /// container, so migrations apply to them one at a time on load.
+    pub fn save_set(&self, set: &GridSet) -> Vec<u8> {
+        self.seal_output(set.to_bytes(self.version, self.codec))
+    }
+
+    pub fn load_set(&self, bytes: &[u8]) -> Result<GridSet, ContainerError> {
+        let bytes = self.open_input(bytes)?;
+        GridSet::from_bytes(&bytes, |saved| self.upgrade(saved))
+    }
+
+    fn seal_output(&self, bytes: Vec<u8>) -> Vec<u8> {
+        #[cfg(feature = "encryption")]
+        if let Some(key) = &self.key {
+            return seal(key, &bytes);
+        }
+        bytes
+    }
+
+    fn open_input<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, [u8]>, ContainerError> {
+        #[cfg(feature = "encryption")]
+        if let Some(key) = &self.key {
+            return open(key, bytes).map(Cow::Owned);
+        }
+        if bytes.starts_with(SEALED_MAGIC) {
+            return Err(ContainerError::Unauthenticated);
+        }
+        Ok(Cow::Borrowed(bytes))
+    }
+
+    pub fn write<T: ElementCodec, W: Write>(
+        &self,
+        grid: &Grid<T>,
+        mut writer: W,
+    ) -> Result<(), ContainerError> {
+        writer.write_all(&self.save(grid))?;
+        Ok(())
+    }
+
+    pub fn read<T: Clone + ElementCodec, R: Read>(
+        &self,
+        mut reader: R,
+    ) -> Result<Grid<T>, ContainerError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        self.load(&bytes)
+    }
+
+    /// Applies migrations until `saved` reaches the current version.
+    pub fn upgrade(&self, mut saved: SavedGrid) -> Result<SavedGrid, ContainerError> {
+        if saved.version > self.version {
+            return Err(ContainerError::VersionTooNew {
+                found: saved.version,
+                supported: self.version,
+            });
+        }
+
+        while saved.version < self.version {
+            let from = saved.version;
+            let migrate = self
+                .migrations
+                .get(&from)
+                .ok_or(ContainerError::MissingMigration { from })?;
+            saved = migrate(saved)?;
+            if saved.version <= from {
+                return Err(ContainerError::MigrationStalled { from });
+            }
+        }
+        Ok(saved)
+    }
+}
+
+/// Encrypts a whole container behind [`SEALED_MAGIC`] and a fresh random
+/// nonce. The magic is authenticated too, so it can't be swapped out.
+#[cfg(feature = "encryption")]
+fn seal(key: &[u8; 32], bytes: &[u8]) -> Vec<u8> {
+    use chacha20poly1305::{
+        aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+        ChaCha20Poly1305,
+    };
+
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let payload = Payload {
+        msg: bytes,
+        aad: SEALED_MAGIC,
+    };
+    let sealed = cipher
+        .encrypt(&nonce, payload)
+        .expect("container is too large to seal");
+
+    let mut out = Vec::with_capacity(SEALED_MAGIC.len() + nonce.len() + sealed.len());
+    out.extend_from_slice(SEALED_MAGIC);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    out
+}
+
+#[cfg(feature = "encryption")]
+fn open(key: &[u8; 32], bytes: &[u8]) -> Result<Vec<u8>, ContainerError> {
+    use chacha20poly1305::{
+        aead::{Aead, KeyInit, Payload},
+        ChaCha20Poly1305, Nonce,
+    };
+
+    let Some(rest) = bytes.strip_prefix(SEALED_MAGIC) else {
+        return Err(ContainerError::Unauthenticated);
+    };
+    if rest.len() < 12 {
+        return Err(ContainerError::Corrupt);
+    }
+    let (nonce, sealed) = rest.split_at(12);
+    let payload = Payload {
+        msg: sealed,
+        aad: SEALED_MAGIC,
+    };
+    ChaCha20Poly1305::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| ContainerError::Unauthenticated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut grid = Grid::new(1.5f32, vec![3, 4]);
+        grid.set(&[2, 1], -3.0).unwrap();
+
+        for codec in [Codec::Raw, Codec::RunLength] {
+            let format = GridFormat::new(3).codec(codec);
+            let bytes = format.save(&grid);
+            let loaded = format.load::<f32>(&bytes).unwrap();
+            assert_eq!(loaded.dimensions(), grid.dimensions());
+            assert!(loaded.iter().eq(grid.iter()));
+        }
+
+        let bytes = GridFormat::new(3).codec(Codec::RunLength).save(&grid);
+        assert_eq!(SavedGrid::from_bytes(&bytes).unwrap().payload.len(), 3 * 8);
+    }
+
+    #[test]
+    fn load_errors() {
+        let bytes = GridFormat::new(2).save(&Grid::new(0u8, vec![2]));
+
+        assert!(matches!(
+            GridFormat::new(2).load::<u16>(&bytes),
+            Err(ContainerError::SchemaMismatch { .. })
+        ));
+        assert!(matches!(
+            GridFormat::new(1).load::<u8>(&bytes),
+            Err(ContainerError::VersionTooNew {
+                found: 2,
+                supported: 1
+            })
+        ));
+        assert!(matches!(
+            GridFormat::new(3).load::<u8>(&bytes),
+            Err(ContainerError::MissingMigration { from: 2 })
+        ));
+        assert!(matches!(
+            GridFormat::new(2).load::<u8>(&bytes[..bytes.len() - 1]),
+            Err(ContainerError::Corrupt)
+        ));
+        assert!(matches!(
+            GridFormat::new(2).load::<u8>(b"nope"),
+            Err(ContainerError::BadMagic)
+        ));
+
+        let mut huge = SavedGrid::from_bytes(&bytes).unwrap();
+        huge.dimensions = vec![usize::MAX, 2];
+        assert!(matches!(huge.decode::<u8>(), Err(ContainerError::Corrupt)));
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn sealed_round_trip() {
+        let grid = Grid::new(3u16, vec![4, 4]);
+        let format = GridFormat::new(1).key([7; 32]);
+        let mut bytes = format.save(&grid);
+        assert!(format.load::<u16>(&bytes).unwrap().iter().eq(grid.iter()));
+
+        assert!(matches!(
+            GridFormat::new(1).load::<u16>(&bytes),
+            Err(ContainerError::Unauthenticated)
+        ));
+        assert!(matches!(
+            GridFormat::new(1).key([8; 32]).load::<u16>(&bytes),
+            Err(ContainerError::Unauthenticated)
+        ));
+        assert!(matches!(
+            format.load::<u16>(&GridFormat::new(1).save(&grid)),
+            Err(ContainerError::Unauthenticated)
+        ));
+
+        let last = bytes.len() - 20;
+        bytes[last] ^= 1;
+        assert!(matches!(
+            format.load::<u16>(&bytes),
+            Err(ContainerError::Unauthenticated)
+        ));
+    }
+}
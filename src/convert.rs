@@ -0,0 +1,67 @@
+use std::{error::Error, fmt};
+
+use crate::{false_index, Coord, Grid};
+
+/// Every cell that failed to convert in [`Grid::try_convert`], in row-major
+/// order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertError<E> {
+    pub failures: Vec<(Coord, E)>,
+}
+
+impl<E: fmt::Display> fmt::Display for ConvertError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ERROR: {} cell(s) failed to convert",
+            self.failures.len()
+        )?;
+        if let Some((coords, err)) = self.failures.first() {
+            write!(f, ", first at {:?}: {}", coords, err)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> Error for ConvertError<E> {}
+
+impl<T> Grid<T> {
+    /// Converts every cell with `U::try_from`, failing with the coordinates
+    /// and error of every cell that couldn't be converted.
+    pub fn try_convert<U>(&self) -> Result<Grid<U>, ConvertError<U::Error>>
+    where
+        T: Clone,
+        U: TryFrom<T>,
+    {
+        let mut grid = Vec::with_capacity(self.grid.len());
+        let mut failures = Vec::new();
+        for (i, val) in self.grid.iter().enumerate() {
+            match U::try_from(val.clone()) {
+                Ok(converted) => grid.push(converted),
+                Err(err) => failures.push((false_index(i, &self.dimensions), err)),
+            }
+        }
+
+        if !failures.is_empty() {
+            return Err(ConvertError { failures });
+        }
+        Ok(Grid::from_parts(grid, self.dimensions.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_convert() {
+        let mut grid = Grid::new(12i64, vec![2, 3]);
+        assert_eq!(grid.try_convert::<u8>().unwrap().iter().sum::<u8>(), 72);
+
+        grid.set(&[0, 2], -1).unwrap();
+        grid.set(&[1, 0], 300).unwrap();
+        let err = grid.try_convert::<u8>().unwrap_err();
+        let coords: Vec<_> = err.failures.iter().map(|(c, _)| c.to_vec()).collect();
+        assert_eq!(coords, vec![vec![0, 2], vec![1, 0]]);
+    }
+}
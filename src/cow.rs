@@ -0,0 +1,135 @@
+use crate::{Grid, GridError, GridView};
+
+/// A grid that is either borrowed, as a view of part or all of another
+/// grid, or owned. Functions taking `impl Into<CowGrid<T>>` accept `&Grid`,
+/// views and owned grids alike, and only copy cells when they need to
+/// modify borrowed ones.
+///
+/// ```
+/// use md_grid::{CowGrid, Grid};
+///
+/// fn total<'a>(field: impl Into<CowGrid<'a, f64>>) -> f64 {
+///     field.into().iter().sum()
+/// }
+///
+/// let field = Grid::new(1.0, vec![4, 4]);
+/// assert_eq!(total(&field), 16.0);
+/// assert_eq!(total(field.view(&[1, 1], &[2, 2]).unwrap()), 4.0);
+/// assert_eq!(total(field), 16.0);
+/// ```
+#[derive(Debug)]
+pub enum CowGrid<'a, T> {
+    Borrowed(GridView<'a, T>),
+    Owned(Grid<T>),
+}
+
+impl<'a, T> CowGrid<'a, T> {
+    pub fn is_owned(&self) -> bool {
+        matches!(self, CowGrid::Owned(_))
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        match self {
+            CowGrid::Borrowed(view) => view.dimensions(),
+            CowGrid::Owned(grid) => grid.dimensions(),
+        }
+    }
+
+    pub fn get(&self, target: &[usize]) -> Result<&T, GridError> {
+        match self {
+            CowGrid::Borrowed(view) => view.get(target),
+            CowGrid::Owned(grid) => grid.get(target),
+        }
+    }
+
+    pub fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        match self {
+            CowGrid::Borrowed(view) => view.get_opt(target),
+            CowGrid::Owned(grid) => grid.get_opt(target),
+        }
+    }
+
+    /// Iterates over all cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let (borrowed, owned) = match self {
+            CowGrid::Borrowed(view) => (Some(view.iter()), None),
+            CowGrid::Owned(grid) => (None, Some(grid.iter())),
+        };
+        borrowed
+            .into_iter()
+            .flatten()
+            .chain(owned.into_iter().flatten())
+    }
+
+    /// Borrows the `shape` cells starting at `origin`.
+    pub fn view(&self, origin: &[usize], shape: &[usize]) -> Result<GridView<'_, T>, GridError> {
+        match self {
+            CowGrid::Borrowed(view) => view.view(origin, shape),
+            CowGrid::Owned(grid) => grid.view(origin, shape),
+        }
+    }
+}
+
+impl<T: Clone> CowGrid<'_, T> {
+    /// The owned grid, copying the borrowed cells if needed.
+    pub fn into_owned(self) -> Grid<T> {
+        match self {
+            CowGrid::Borrowed(view) => view.to_grid(),
+            CowGrid::Owned(grid) => grid,
+        }
+    }
+
+    /// Mutable access to the grid, copying the borrowed cells first if
+    /// needed.
+    pub fn to_mut(&mut self) -> &mut Grid<T> {
+        if let CowGrid::Borrowed(view) = self {
+            *self = CowGrid::Owned(view.to_grid());
+        }
+        match self {
+            CowGrid::Owned(grid) => grid,
+            CowGrid::Borrowed(_) => unreachable!(),
+        }
+    }
+}
+
+impl<T> From<Grid<T>> for CowGrid<'_, T> {
+    fn from(grid: Grid<T>) -> Self {
+        CowGrid::Owned(grid)
+    }
+}
+
+impl<'a, T> From<&'a Grid<T>> for CowGrid<'a, T> {
+    fn from(grid: &'a Grid<T>) -> Self {
+        let origin = vec![0; grid.axes];
+        CowGrid::Borrowed(grid.view(&origin, &grid.dimensions).unwrap())
+    }
+}
+
+impl<'a, T> From<GridView<'a, T>> for CowGrid<'a, T> {
+    fn from(view: GridView<'a, T>) -> Self {
+        CowGrid::Borrowed(view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_on_write() {
+        let source = Grid::from_fn(vec![3, 3], |c| c[0] * 3 + c[1]);
+        let mut cow = CowGrid::from(source.view(&[1, 1], &[2, 2]).unwrap());
+        assert!(!cow.is_owned());
+        assert_eq!(cow.get(&[1, 0]), Ok(&7));
+        assert_eq!(
+            cow.view(&[0, 1], &[2, 1]).unwrap().get_opt(&[1, 0]),
+            Some(&8)
+        );
+
+        cow.to_mut().set(&[0, 0], 0).unwrap();
+        assert!(cow.is_owned());
+        assert!(cow.iter().copied().eq([0, 5, 7, 8]));
+        assert_eq!(source.get_opt(&[1, 1]), Some(&4));
+        assert_eq!(CowGrid::from(&source).into_owned().dimensions(), &[3, 3]);
+    }
+}
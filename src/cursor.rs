@@ -0,0 +1,193 @@
+use crate::{
+    direction::{offset_coords, Direction4, Direction8},
+    Coord, Grid, GridError,
+};
+
+/// A facing that a [`GridCursor`] can hold.
+pub trait Heading: Copy {
+    fn offset(self) -> [isize; 2];
+    fn turn_left(self) -> Self;
+    fn turn_right(self) -> Self;
+}
+
+impl Heading for Direction4 {
+    fn offset(self) -> [isize; 2] {
+        Direction4::offset(self)
+    }
+
+    fn turn_left(self) -> Self {
+        Direction4::turn_left(self)
+    }
+
+    fn turn_right(self) -> Self {
+        Direction4::turn_right(self)
+    }
+}
+
+impl Heading for Direction8 {
+    fn offset(self) -> [isize; 2] {
+        Direction8::offset(self)
+    }
+
+    fn turn_left(self) -> Self {
+        Direction8::turn_left(self)
+    }
+
+    fn turn_right(self) -> Self {
+        Direction8::turn_right(self)
+    }
+}
+
+/// What a [`GridCursor`] does when it would step off the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgePolicy {
+    /// Stay in place at the edge.
+    #[default]
+    Clamp,
+    /// Reappear on the opposite edge.
+    Wrap,
+}
+
+/// A position and facing on a 2D grid, for simulating agents that walk it.
+#[derive(Debug)]
+pub struct GridCursor<'a, T, D: Heading = Direction4> {
+    grid: &'a mut Grid<T>,
+    position: Coord,
+    facing: D,
+    policy: EdgePolicy,
+}
+
+impl<'a, T, D: Heading> GridCursor<'a, T, D> {
+    pub fn new(
+        grid: &'a mut Grid<T>,
+        position: &[usize],
+        facing: D,
+        policy: EdgePolicy,
+    ) -> Result<Self, GridError> {
+        if grid.dimensions().len() != 2 {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: grid.dimensions().to_vec(),
+            });
+        }
+        grid.translate_index(position)?;
+
+        Ok(Self {
+            grid,
+            position: Coord::from_slice(position),
+            facing,
+            policy,
+        })
+    }
+
+    pub fn position(&self) -> &[usize] {
+        &self.position
+    }
+
+    pub fn facing(&self) -> D {
+        self.facing
+    }
+
+    pub fn face(&mut self, facing: D) {
+        self.facing = facing;
+    }
+
+    pub fn turn_left(&mut self) {
+        self.facing = self.facing.turn_left();
+    }
+
+    pub fn turn_right(&mut self) {
+        self.facing = self.facing.turn_right();
+    }
+
+    /// Coordinates of the cell in front of the cursor, or `None` if that
+    /// would leave the grid under [`EdgePolicy::Clamp`].
+    pub fn ahead(&self) -> Option<Coord> {
+        let offset = self.facing.offset();
+        match self.policy {
+            EdgePolicy::Clamp => offset_coords(&self.position, &offset, self.grid.dimensions()),
+            EdgePolicy::Wrap => Some(
+                self.position
+                    .iter()
+                    .zip(offset)
+                    .zip(self.grid.dimensions())
+                    .map(|((&c, o), &len)| (c as isize + o).rem_euclid(len as isize) as usize)
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Steps one cell forward, returning whether the cursor moved.
+    pub fn forward(&mut self) -> bool {
+        match self.ahead() {
+            Some(next) => {
+                self.position = next;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The value in front of the cursor, without moving.
+    pub fn peek(&self) -> Option<&T> {
+        self.grid.get_opt(&self.ahead()?)
+    }
+
+    /// The value under the cursor.
+    pub fn read(&self) -> &T {
+        self.grid
+            .get(&self.position)
+            .expect("cursor position is always in bounds")
+    }
+
+    /// Overwrites the value under the cursor.
+    pub fn write(&mut self, val: T) {
+        *self
+            .grid
+            .get_mut(&self.position)
+            .expect("cursor position is always in bounds") = val;
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn cursor<D: Heading>(
+        &mut self,
+        position: &[usize],
+        facing: D,
+        policy: EdgePolicy,
+    ) -> Result<GridCursor<'_, T, D>, GridError> {
+        GridCursor::new(self, position, facing, policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walk_and_write() {
+        let mut grid = Grid::new('.', vec![3, 4]);
+        let mut cursor = grid
+            .cursor(&[0, 0], Direction4::East, EdgePolicy::Clamp)
+            .unwrap();
+
+        while cursor.forward() {
+            cursor.write('#');
+        }
+        assert_eq!(cursor.position(), &[0, 3]);
+
+        cursor.turn_right();
+        assert_eq!(cursor.peek(), Some(&'.'));
+        cursor.turn_left();
+        assert_eq!(cursor.peek(), None);
+
+        let mut cursor = grid
+            .cursor(&[0, 0], Direction8::NorthWest, EdgePolicy::Wrap)
+            .unwrap();
+        assert!(cursor.forward());
+        assert_eq!(cursor.position(), &[2, 3]);
+        assert_eq!(*cursor.read(), '.');
+
+        assert_eq!(grid.iter().filter(|&&c| c == '#').count(), 3);
+    }
+}
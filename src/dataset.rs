@@ -0,0 +1,311 @@
+use std::{any::Any, collections::BTreeMap};
+
+use crate::{
+    container::{Codec, ContainerError, ElementCodec, SavedGrid},
+    Grid, GridError, GridViewMut, Shape,
+};
+
+const MAGIC: &[u8; 4] = b"MDGD";
+const FORMAT_VERSION: u16 = 1;
+
+/// A grid of any cell type held by a [`GridSet`].
+trait Layer {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn schema(&self) -> &str;
+    fn save(&self, version: u32, codec: Codec) -> SavedGrid;
+}
+
+impl<T: ElementCodec + 'static> Layer for Grid<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn schema(&self) -> &str {
+        T::SCHEMA
+    }
+
+    fn save(&self, version: u32, codec: Codec) -> SavedGrid {
+        SavedGrid::encode(self, version, codec)
+    }
+}
+
+/// A loaded grid whose cell type isn't built in, kept encoded until
+/// [`GridSet::decode`] is called with its type.
+impl Layer for SavedGrid {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    fn save(&self, _version: u32, _codec: Codec) -> SavedGrid {
+        self.clone()
+    }
+}
+
+/// Decodes grids of the built-in cell types, keeping others encoded.
+fn decode_known(saved: SavedGrid) -> Result<Box<dyn Layer>, ContainerError> {
+    macro_rules! known {
+        ($($ty:ty),*) => {
+            match saved.schema.as_str() {
+                $(schema if schema == <$ty>::SCHEMA => Ok(Box::new(saved.decode::<$ty>()?)),)*
+                _ => Ok(Box::new(saved)),
+            }
+        };
+    }
+    known!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64, bool)
+}
+
+/// Named grids of one shape, each with its own cell type, saved to and
+/// loaded from a single file with [`GridFormat::save_set`] and
+/// [`GridFormat::load_set`].
+///
+/// [`GridFormat::save_set`]: crate::GridFormat::save_set
+/// [`GridFormat::load_set`]: crate::GridFormat::load_set
+///
+/// ```
+/// use md_grid::{Grid, GridFormat, GridSet};
+///
+/// let mut level = GridSet::new(vec![16, 16]);
+/// level.insert("terrain", Grid::new(0.5f32, vec![16, 16])).unwrap();
+/// level.insert("objects", Grid::new(0u16, vec![16, 16])).unwrap();
+/// assert!(level.insert("fog", Grid::new(true, vec![8, 8])).is_err());
+///
+/// let format = GridFormat::new(1);
+/// let loaded = format.load_set(&format.save_set(&level)).unwrap();
+/// assert_eq!(loaded.get::<u16>("objects").unwrap().dimensions(), &[16, 16]);
+/// assert!(loaded.get::<u8>("objects").is_none());
+/// ```
+pub struct GridSet {
+    dimensions: Vec<usize>,
+    layers: BTreeMap<String, Box<dyn Layer>>,
+}
+
+impl GridSet {
+    /// An empty set whose grids must all have `dimensions`.
+    pub fn new(dimensions: Vec<usize>) -> Self {
+        Self {
+            dimensions,
+            layers: BTreeMap::new(),
+        }
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// The names of the grids, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layers.keys().map(String::as_str)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.layers.contains_key(name)
+    }
+
+    /// The [`ElementCodec::SCHEMA`] of the named grid's cells.
+    pub fn schema(&self, name: &str) -> Option<&str> {
+        self.layers.get(name).map(|layer| layer.schema())
+    }
+
+    /// Adds `grid` under `name`, replacing any grid already there. Fails
+    /// with [`GridError::ShapeMismatch`] if its shape differs from the set's.
+    pub fn insert<T>(&mut self, name: impl Into<String>, grid: Grid<T>) -> Result<(), GridError>
+    where
+        T: ElementCodec + 'static,
+    {
+        Shape::from(&self.dimensions[..]).check_eq(&grid.dimensions)?;
+        self.layers.insert(name.into(), Box::new(grid));
+        Ok(())
+    }
+
+    /// The named grid, or `None` if there is none or its cells aren't `T`.
+    pub fn get<T: 'static>(&self, name: &str) -> Option<&Grid<T>> {
+        self.layers.get(name)?.as_any().downcast_ref()
+    }
+
+    /// Mutable counterpart of [`GridSet::get`]. It is a view of the whole
+    /// grid rather than the grid itself, so the grid can't be resized or
+    /// reshaped and the set keeps a single shape.
+    pub fn get_mut<T: 'static>(&mut self, name: &str) -> Option<GridViewMut<'_, T>> {
+        let grid = self.layers.get_mut(name)?.as_any_mut().downcast_mut();
+        grid.map(Grid::whole_view_mut)
+    }
+
+    /// Removes the named grid, returning whether there was one.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.layers.remove(name).is_some()
+    }
+
+    /// Decodes a loaded grid whose cell type isn't one of the built-in
+    /// [`ElementCodec`] types, after which [`GridSet::get`] finds it. Grids
+    /// already decoded as `T` are returned as they are, viewed as by
+    /// [`GridSet::get_mut`].
+    pub fn decode<T>(&mut self, name: &str) -> Result<Option<GridViewMut<'_, T>>, ContainerError>
+    where
+        T: Clone + ElementCodec + 'static,
+    {
+        let Some(layer) = self.layers.get_mut(name) else {
+            return Ok(None);
+        };
+        if let Some(saved) = layer.as_any().downcast_ref::<SavedGrid>() {
+            *layer = Box::new(saved.decode::<T>()?);
+        }
+        let schema = layer.schema().to_string();
+        match layer.as_any_mut().downcast_mut::<Grid<T>>() {
+            Some(grid) => Ok(Some(grid.whole_view_mut())),
+            None => Err(ContainerError::SchemaMismatch {
+                expected: T::SCHEMA.to_string(),
+                found: schema,
+            }),
+        }
+    }
+
+    pub(crate) fn to_bytes(&self, version: u32, codec: Codec) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&(self.dimensions.len() as u32).to_le_bytes());
+        for &d in &self.dimensions {
+            out.extend_from_slice(&(d as u64).to_le_bytes());
+        }
+        out.extend_from_slice(&(self.layers.len() as u32).to_le_bytes());
+        for (name, layer) in &self.layers {
+            let saved = layer.save(version, codec).to_bytes();
+            out.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(&(saved.len() as u64).to_le_bytes());
+            out.extend_from_slice(&saved);
+        }
+        out
+    }
+
+    /// Reads a set written by [`GridSet::to_bytes`], passing each grid's
+    /// container through `upgrade` before decoding it.
+    pub(crate) fn from_bytes<F>(bytes: &[u8], upgrade: F) -> Result<Self, ContainerError>
+    where
+        F: Fn(SavedGrid) -> Result<SavedGrid, ContainerError>,
+    {
+        let mut input = bytes;
+        let mut take = |len: usize| -> Result<&[u8], ContainerError> {
+            if input.len() < len {
+                return Err(ContainerError::Corrupt);
+            }
+            let (head, rest) = input.split_at(len);
+            input = rest;
+            Ok(head)
+        };
+
+        if take(4)? != MAGIC {
+            return Err(ContainerError::BadMagic);
+        }
+        let format = u16::decode(&mut take(2)?).unwrap();
+        if format != FORMAT_VERSION {
+            return Err(ContainerError::UnsupportedFormat(format));
+        }
+        let axes = u32::decode(&mut take(4)?).unwrap() as usize;
+        let mut dimensions = Vec::new();
+        for _ in 0..axes {
+            let d = u64::decode(&mut take(8)?).unwrap();
+            dimensions.push(usize::try_from(d).map_err(|_| ContainerError::Corrupt)?);
+        }
+
+        let mut set = GridSet::new(dimensions);
+        let count = u32::decode(&mut take(4)?).unwrap();
+        for _ in 0..count {
+            let name_len = u32::decode(&mut take(4)?).unwrap() as usize;
+            let name =
+                String::from_utf8(take(name_len)?.to_vec()).map_err(|_| ContainerError::Corrupt)?;
+            let len = u64::decode(&mut take(8)?).unwrap();
+            let len = usize::try_from(len).map_err(|_| ContainerError::Corrupt)?;
+            let saved = upgrade(SavedGrid::from_bytes(take(len)?)?)?;
+            if saved.dimensions != set.dimensions {
+                return Err(ContainerError::Corrupt);
+            }
+            set.layers.insert(name, decode_known(saved)?);
+        }
+        if !input.is_empty() {
+            return Err(ContainerError::Corrupt);
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GridFormat;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Tile(u8);
+
+    impl ElementCodec for Tile {
+        const SCHEMA: &'static str = "tile";
+
+        fn encode(&self, out: &mut Vec<u8>) {
+            out.push(self.0);
+        }
+
+        fn decode(input: &mut &[u8]) -> Option<Self> {
+            u8::decode(input).map(Tile)
+        }
+    }
+
+    #[test]
+    fn round_trip_layers() {
+        let mut set = GridSet::new(vec![3, 2]);
+        set.insert("height", Grid::from_fn(vec![3, 2], |c| c[0] as f64))
+            .unwrap();
+        set.insert("tiles", Grid::new(Tile(4), vec![3, 2])).unwrap();
+        set.get_mut::<f64>("height")
+            .unwrap()
+            .set(&[0, 1], -1.0)
+            .unwrap();
+
+        let format = GridFormat::new(2).codec(Codec::RunLength);
+        let mut loaded = format.load_set(&format.save_set(&set)).unwrap();
+        assert_eq!(loaded.names().collect::<Vec<_>>(), ["height", "tiles"]);
+        let height = loaded.get::<f64>("height").unwrap();
+        assert!(height.iter().eq(set.get::<f64>("height").unwrap().iter()));
+
+        assert!(loaded.get::<Tile>("tiles").is_none());
+        assert!(loaded.decode::<u8>("tiles").is_err());
+        assert_eq!(
+            loaded
+                .decode::<Tile>("tiles")
+                .unwrap()
+                .unwrap()
+                .get_opt(&[2, 1]),
+            Some(&Tile(4))
+        );
+        assert!(loaded.get::<Tile>("tiles").is_some());
+        assert!(matches!(
+            loaded.decode::<u8>("height"),
+            Err(ContainerError::SchemaMismatch { .. })
+        ));
+
+        assert!(matches!(
+            GridFormat::new(1).load_set(&format.save_set(&set)),
+            Err(ContainerError::VersionTooNew { .. })
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{Grid, GridError};
+
+impl<T: Copy + Into<f64>> Grid<T> {
+    fn check_spacing(&self, spacing: &[f64]) -> Result<(), GridError> {
+        if spacing.len() != self.axes {
+            return Err(GridError::UnsupportedAxes {
+                expected: spacing.len(),
+                shape: self.dimensions.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Calls `f` with each cell's flat index, its position along `axis`, and
+    /// the stride of that axis.
+    fn for_each_along(&self, axis: usize, mut f: impl FnMut(usize, usize, usize)) {
+        let stride: usize = self.dimensions[axis + 1..].iter().product();
+        let len = self.dimensions[axis];
+        for i in 0..self.grid.len() {
+            f(i, (i / stride) % len, stride);
+        }
+    }
+
+    /// The rate of change along each axis, one grid per axis, where cells
+    /// are `spacing[axis]` apart. Uses central differences inside the grid
+    /// and one-sided differences at its edges.
+    pub fn gradient(&self, spacing: &[f64]) -> Result<Vec<Grid<f64>>, GridError> {
+        self.check_spacing(spacing)?;
+        let v = |i: usize| -> f64 { self.grid[i].into() };
+
+        Ok((0..self.axes)
+            .map(|axis| {
+                let (len, h) = (self.dimensions[axis], spacing[axis]);
+                let mut out = Grid::new(0.0, self.dimensions.clone());
+                self.for_each_along(axis, |i, c, stride| {
+                    out.grid[i] = match c {
+                        _ if len < 2 => 0.0,
+                        0 => (v(i + stride) - v(i)) / h,
+                        c if c == len - 1 => (v(i) - v(i - stride)) / h,
+                        _ => (v(i + stride) - v(i - stride)) / (2.0 * h),
+                    };
+                });
+                out
+            })
+            .collect())
+    }
+
+    /// The sum of second derivatives along every axis, where cells are
+    /// `spacing[axis]` apart. Cells past the edges repeat the edge value.
+    pub fn laplacian(&self, spacing: &[f64]) -> Result<Grid<f64>, GridError> {
+        self.check_spacing(spacing)?;
+        let v = |i: usize| -> f64 { self.grid[i].into() };
+
+        let mut out = Grid::new(0.0, self.dimensions.clone());
+        for (axis, &h) in spacing.iter().enumerate() {
+            let len = self.dimensions[axis];
+            self.for_each_along(axis, |i, c, stride| {
+                let lower = if c > 0 { v(i - stride) } else { v(i) };
+                let upper = if c + 1 < len { v(i + stride) } else { v(i) };
+                out.grid[i] += (lower - 2.0 * v(i) + upper) / (h * h);
+            });
+        }
+        Ok(out)
+    }
+
+    /// The steepest slope at each cell in degrees, treating values as
+    /// heights in the same unit as `spacing`.
+    pub fn slope(&self, spacing: &[f64]) -> Result<Grid<f64>, GridError> {
+        let gradient = self.gradient(spacing)?;
+        let mut out = Grid::new(0.0, self.dimensions.clone());
+        for (i, cell) in out.grid.iter_mut().enumerate() {
+            let rise = gradient.iter().map(|g| g.grid[i].powi(2)).sum::<f64>();
+            *cell = rise.sqrt().atan().to_degrees();
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anisotropic_spacing() {
+        // f = 2 * row + 3 * col^2 on rows 0.5 apart and columns 2 apart.
+        let mut grid = Grid::new(0.0, vec![3, 4]);
+        for (i, v) in grid.iter_mut().enumerate() {
+            let (y, x) = ((i / 4) as f64 * 0.5, (i % 4) as f64 * 2.0);
+            *v = 2.0 * y + 3.0 * x * x;
+        }
+
+        let gradient = grid.gradient(&[0.5, 2.0]).unwrap();
+        assert!(gradient[0].iter().all(|&d| (d - 2.0).abs() < 1e-9));
+        assert_eq!(gradient[1].get_opt(&[1, 1]), Some(&12.0));
+        assert_eq!(gradient[1].get_opt(&[1, 0]), Some(&6.0));
+
+        let laplacian = grid.laplacian(&[0.5, 2.0]).unwrap();
+        assert_eq!(laplacian.get_opt(&[1, 2]), Some(&6.0));
+
+        let flat = Grid::new(1.0, vec![2, 2]).slope(&[1.0, 1.0]).unwrap();
+        assert!(flat.iter().all(|&s| s == 0.0));
+        let mut ramp = Grid::new(0.0, vec![1, 2]);
+        ramp.set(&[0, 1], 1.0).unwrap();
+        let slope = ramp.slope(&[1.0, 1.0]).unwrap();
+        assert!((slope.get_opt(&[0, 0]).unwrap() - 45.0).abs() < 1e-9);
+
+        assert!(grid.gradient(&[1.0]).is_err());
+    }
+}
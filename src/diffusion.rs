@@ -0,0 +1,135 @@
+use crate::{
+    direction::offset_coords, error::check_coords, false_index, Connectivity, Grid, GridError,
+    GridLike, Shape,
+};
+
+/// Parameters for [`GridLike::diffuse`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffusionOptions {
+    /// How far each cell moves toward the mean of its neighbors per step,
+    /// from 0 (not at all) to 1 (all the way).
+    pub rate: f64,
+    /// Fraction of its value a cell keeps per step, before absorption.
+    pub decay: f64,
+    /// Stop once no cell changes by more than this in a step.
+    pub tolerance: f64,
+    /// Stop after this many steps even if still changing.
+    pub max_iterations: usize,
+}
+
+impl DiffusionOptions {
+    pub fn new(rate: f64, decay: f64) -> Self {
+        Self {
+            rate,
+            decay,
+            tolerance: 1e-3,
+            max_iterations: 1000,
+        }
+    }
+
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+}
+
+/// Backs [`GridLike::diffuse`].
+pub(crate) fn diffuse<G, P, F>(
+    grid: &G,
+    sources: &[(P, f64)],
+    options: &DiffusionOptions,
+    mut absorption: F,
+) -> Result<Grid<f64>, GridError>
+where
+    G: GridLike,
+    P: AsRef<[usize]>,
+    F: FnMut(&G::Item) -> f64,
+{
+    let dimensions = grid.dimensions();
+    let shape = Shape::from(dimensions);
+    let sources = sources
+        .iter()
+        .map(|(coords, v)| {
+            check_coords(coords.as_ref(), dimensions)?;
+            Ok((shape.flat_index(coords.as_ref()).unwrap(), *v))
+        })
+        .collect::<Result<Vec<_>, GridError>>()?;
+    let keep: Vec<f64> = grid
+        .iter()
+        .map(|v| options.decay * (1.0 - absorption(v)).clamp(0.0, 1.0))
+        .collect();
+    let offsets = Connectivity::Orthogonal.offsets(dimensions.len());
+    let neighbors: Vec<Vec<usize>> = (0..keep.len())
+        .map(|i| {
+            let coords = false_index(i, dimensions);
+            offsets
+                .iter()
+                .filter_map(|o| offset_coords(&coords, o, dimensions))
+                .map(|n| shape.flat_index(&n).unwrap())
+                .collect()
+        })
+        .collect();
+
+    let mut current = Grid::new(0.0, dimensions.to_vec());
+    for &(i, v) in &sources {
+        current.grid[i] = v;
+    }
+    let mut next = current.clone();
+    for _ in 0..options.max_iterations {
+        for (i, cell) in next.grid.iter_mut().enumerate() {
+            let v = current.grid[i];
+            let around = &neighbors[i];
+            let spread = match around.len() {
+                0 => 0.0,
+                n => around.iter().map(|&n| current.grid[n]).sum::<f64>() / n as f64 - v,
+            };
+            *cell = (v + options.rate * spread) * keep[i];
+        }
+        for &(i, v) in &sources {
+            next.grid[i] = v;
+        }
+        let change = (next.grid.iter().zip(&current.grid))
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        std::mem::swap(&mut current, &mut next);
+        if change <= options.tolerance {
+            break;
+        }
+    }
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sound_through_a_doorway() {
+        let mut map = Grid::new('.', vec![5, 5]);
+        for row in [0, 1, 3, 4] {
+            map.set(&[row, 2], '#').unwrap();
+        }
+
+        let options = DiffusionOptions::new(0.8, 0.95).tolerance(1e-6);
+        let absorption = |&c: &char| if c == '#' { 1.0 } else { 0.0 };
+        let sound = map
+            .diffuse(&[([2, 0], 10.0)], &options, absorption)
+            .unwrap();
+
+        let at = |c: &[usize]| *sound.get_opt(c).unwrap();
+        assert_eq!(at(&[2, 0]), 10.0);
+        assert_eq!(at(&[0, 2]), 0.0);
+        assert!(at(&[2, 1]) > at(&[2, 3]) && at(&[2, 3]) > at(&[2, 4]));
+        assert!(at(&[2, 4]) > at(&[0, 4]) && at(&[0, 4]) > 0.0);
+
+        let quick = DiffusionOptions::new(0.8, 0.95).max_iterations(1);
+        let one_step = map.diffuse(&[([2, 0], 10.0)], &quick, absorption).unwrap();
+        assert_eq!(one_step.get_opt(&[2, 3]), Some(&0.0));
+        assert!(map.diffuse(&[([5, 0], 1.0)], &options, absorption).is_err());
+    }
+}
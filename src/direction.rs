@@ -0,0 +1,289 @@
+use crate::{Coord, Grid, GridError};
+
+/// A step of one cell along a single axis, in either direction.
+///
+/// For 2D grids indexed as `[row, column]`, the named constants follow screen
+/// conventions: north is towards row 0 and west is towards column 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Negative(usize),
+    Positive(usize),
+}
+
+impl Direction {
+    pub const NORTH: Direction = Direction::Negative(0);
+    pub const SOUTH: Direction = Direction::Positive(0);
+    pub const WEST: Direction = Direction::Negative(1);
+    pub const EAST: Direction = Direction::Positive(1);
+
+    pub fn axis(self) -> usize {
+        match self {
+            Direction::Negative(axis) | Direction::Positive(axis) => axis,
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Negative(axis) => Direction::Positive(axis),
+            Direction::Positive(axis) => Direction::Negative(axis),
+        }
+    }
+
+    /// The offset of this step in a grid with `axes` dimensions.
+    pub fn offset(self, axes: usize) -> Vec<isize> {
+        let mut offset = vec![0; axes];
+        if let Some(v) = offset.get_mut(self.axis()) {
+            *v = match self {
+                Direction::Negative(_) => -1,
+                Direction::Positive(_) => 1,
+            };
+        }
+        offset
+    }
+
+    /// Applies the step to `coords`, returning `None` if the result would
+    /// leave a grid with the given dimensions.
+    pub fn step(self, coords: &[usize], dimensions: &[usize]) -> Option<Coord> {
+        let axis = self.axis();
+        if coords.len() != dimensions.len() || axis >= coords.len() {
+            return None;
+        }
+
+        let mut next = Coord::from_slice(coords);
+        match self {
+            Direction::Negative(_) => next[axis] = next[axis].checked_sub(1)?,
+            Direction::Positive(_) => next[axis] += 1,
+        }
+        if next[axis] >= dimensions[axis] {
+            return None;
+        }
+        Some(next)
+    }
+}
+
+/// The four orthogonal compass directions on a 2D grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction4 {
+    /// All directions in clockwise order, starting from north.
+    pub const ALL: [Direction4; 4] = [
+        Direction4::North,
+        Direction4::East,
+        Direction4::South,
+        Direction4::West,
+    ];
+
+    pub fn opposite(self) -> Direction4 {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(self) -> Direction4 {
+        self.rotate(3)
+    }
+
+    pub fn turn_right(self) -> Direction4 {
+        self.rotate(1)
+    }
+
+    /// The `[row, column]` offset of one step in this direction.
+    pub fn offset(self) -> [isize; 2] {
+        match self {
+            Direction4::North => [-1, 0],
+            Direction4::East => [0, 1],
+            Direction4::South => [1, 0],
+            Direction4::West => [0, -1],
+        }
+    }
+
+    fn rotate(self, quarter_turns: usize) -> Direction4 {
+        Self::ALL[(self as usize + quarter_turns) % 4]
+    }
+}
+
+impl From<Direction4> for Direction {
+    fn from(dir: Direction4) -> Self {
+        match dir {
+            Direction4::North => Direction::NORTH,
+            Direction4::East => Direction::EAST,
+            Direction4::South => Direction::SOUTH,
+            Direction4::West => Direction::WEST,
+        }
+    }
+}
+
+/// The eight compass directions on a 2D grid, including diagonals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// All directions in clockwise order, starting from north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    pub fn opposite(self) -> Direction8 {
+        self.rotate(4)
+    }
+
+    /// Turns 45 degrees counter-clockwise.
+    pub fn turn_left(self) -> Direction8 {
+        self.rotate(7)
+    }
+
+    /// Turns 45 degrees clockwise.
+    pub fn turn_right(self) -> Direction8 {
+        self.rotate(1)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    /// The `[row, column]` offset of one step in this direction.
+    pub fn offset(self) -> [isize; 2] {
+        match self {
+            Direction8::North => [-1, 0],
+            Direction8::NorthEast => [-1, 1],
+            Direction8::East => [0, 1],
+            Direction8::SouthEast => [1, 1],
+            Direction8::South => [1, 0],
+            Direction8::SouthWest => [1, -1],
+            Direction8::West => [0, -1],
+            Direction8::NorthWest => [-1, -1],
+        }
+    }
+
+    fn rotate(self, eighth_turns: usize) -> Direction8 {
+        Self::ALL[(self as usize + eighth_turns) % 8]
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(dir: Direction4) -> Self {
+        Self::ALL[dir as usize * 2]
+    }
+}
+
+/// Adds `offset` to `coords`, returning `None` if the result would leave a
+/// grid with the given dimensions.
+pub fn offset_coords(coords: &[usize], offset: &[isize], dimensions: &[usize]) -> Option<Coord> {
+    if coords.len() != dimensions.len() || offset.len() != dimensions.len() {
+        return None;
+    }
+
+    coords
+        .iter()
+        .zip(offset)
+        .zip(dimensions)
+        .map(|((&c, &o), &len)| c.checked_add_signed(o).filter(|&v| v < len))
+        .collect()
+}
+
+impl<T> Grid<T> {
+    /// Gets the cell at `target` moved by `offset`, e.g. a
+    /// [`Direction4::offset`].
+    pub fn get_offset(&self, target: &[usize], offset: &[isize]) -> Result<&T, GridError> {
+        let coords = offset_coords(target, offset, &self.dimensions).ok_or_else(|| {
+            GridError::OffsetOutOfBounds {
+                coords: Coord::from_slice(target),
+                offset: offset.to_vec(),
+                shape: self.dimensions.clone(),
+            }
+        })?;
+        self.get(&coords)
+    }
+
+    /// Yields the orthogonal neighbors of `target` along with the direction
+    /// they lie in, ordered by axis and then negative before positive.
+    /// Neighbors outside the grid are skipped.
+    pub fn neighbors_with_direction<'a>(
+        &'a self,
+        target: &'a [usize],
+    ) -> impl Iterator<Item = (Direction, Coord, &'a T)> + 'a {
+        (0..self.axes)
+            .flat_map(|axis| [Direction::Negative(axis), Direction::Positive(axis)])
+            .filter_map(move |dir| {
+                let coords = dir.step(target, &self.dimensions)?;
+                let val = self.get_opt(&coords)?;
+                Some((dir, coords, val))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn neighbors_with_direction() {
+        let mut grid = Grid::new('.', vec![3, 3]);
+        grid.set(&[0, 1], 'n').unwrap();
+        grid.set(&[1, 2], 'e').unwrap();
+
+        let neighbors: Vec<_> = grid.neighbors_with_direction(&[1, 1]).collect();
+        assert_eq!(
+            neighbors,
+            vec![
+                (Direction::NORTH, smallvec![0, 1], &'n'),
+                (Direction::SOUTH, smallvec![2, 1], &'.'),
+                (Direction::WEST, smallvec![1, 0], &'.'),
+                (Direction::EAST, smallvec![1, 2], &'e'),
+            ]
+        );
+
+        let corner: Vec<_> = grid
+            .neighbors_with_direction(&[0, 0])
+            .map(|(dir, _, _)| dir)
+            .collect();
+        assert_eq!(corner, vec![Direction::SOUTH, Direction::EAST]);
+    }
+
+    #[test]
+    fn compass_directions() {
+        assert_eq!(Direction4::North.turn_right(), Direction4::East);
+        assert_eq!(Direction4::North.turn_left(), Direction4::West);
+        assert_eq!(Direction4::West.opposite(), Direction4::East);
+        assert_eq!(Direction8::NorthWest.turn_right(), Direction8::North);
+        assert_eq!(Direction8::SouthEast.opposite(), Direction8::NorthWest);
+        assert_eq!(
+            Direction::from(Direction4::South).opposite(),
+            Direction::NORTH
+        );
+        assert_eq!(Direction::EAST.offset(2), vec![0, 1]);
+
+        let mut grid = Grid::new(0, vec![3, 3]);
+        grid.set(&[0, 2], 9).unwrap();
+        assert_eq!(
+            *grid
+                .get_offset(&[1, 1], &Direction8::NorthEast.offset())
+                .unwrap(),
+            9
+        );
+        assert!(grid
+            .get_offset(&[0, 0], &Direction4::North.offset())
+            .is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use std::{cmp::Ordering, cmp::Reverse, collections::BinaryHeap};
+
+use crate::{direction::offset_coords, false_index, Connectivity, Coord, Grid, GridError, Metric};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Key(f64, f64);
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.total_cmp(&other.1))
+    }
+}
+
+/// Incremental shortest-path search (D* Lite) over orthogonal moves.
+///
+/// Each cell has a cost to enter it, with `f64::INFINITY` marking
+/// impassable cells; costs below 1 are raised to 1 so the Manhattan
+/// heuristic stays admissible. After [`DStarLite::set_cost`] or
+/// [`DStarLite::move_start`], the next [`DStarLite::path`] call only repairs
+/// the part of the search affected by the change.
+#[derive(Debug, Clone)]
+pub struct DStarLite {
+    costs: Grid<f64>,
+    g: Vec<f64>,
+    rhs: Vec<f64>,
+    queued: Vec<Option<Key>>,
+    open: BinaryHeap<Reverse<(Key, usize)>>,
+    offsets: Vec<Vec<isize>>,
+    start: usize,
+    goal: usize,
+    last_start: usize,
+    km: f64,
+}
+
+impl DStarLite {
+    pub fn new<T, F>(
+        grid: &Grid<T>,
+        start: &[usize],
+        goal: &[usize],
+        mut cost: F,
+    ) -> Result<Self, GridError>
+    where
+        F: FnMut(&T) -> f64,
+    {
+        let costs = Grid::from_parts(
+            grid.iter().map(|v| cost(v).max(1.0)).collect(),
+            grid.dimensions.clone(),
+        );
+        let start = costs.translate_index(start)?;
+        let goal = costs.translate_index(goal)?;
+        let len = costs.grid.len();
+
+        let mut search = Self {
+            g: vec![f64::INFINITY; len],
+            rhs: vec![f64::INFINITY; len],
+            queued: vec![None; len],
+            open: BinaryHeap::new(),
+            offsets: Connectivity::Orthogonal.offsets(costs.axes),
+            costs,
+            start,
+            goal,
+            last_start: start,
+            km: 0.0,
+        };
+        search.rhs[goal] = 0.0;
+        let key = search.key(goal);
+        search.push(goal, key);
+
+        Ok(search)
+    }
+
+    pub fn start(&self) -> Coord {
+        false_index(self.start, &self.costs.dimensions)
+    }
+
+    pub fn goal(&self) -> Coord {
+        false_index(self.goal, &self.costs.dimensions)
+    }
+
+    /// Moves the start, e.g. after the agent takes a step along the path.
+    pub fn move_start(&mut self, start: &[usize]) -> Result<(), GridError> {
+        let start = self.costs.translate_index(start)?;
+        self.km += self.heuristic(self.last_start, start);
+        self.last_start = start;
+        self.start = start;
+        Ok(())
+    }
+
+    /// Changes the cost of entering a cell.
+    pub fn set_cost(&mut self, target: &[usize], cost: f64) -> Result<(), GridError> {
+        let index = self.costs.translate_index(target)?;
+        let cost = cost.max(1.0);
+        if self.costs.grid[index] == cost {
+            return Ok(());
+        }
+        self.costs.grid[index] = cost;
+
+        // Only the edges leading into the changed cell are affected.
+        for neighbor in self.neighbors(index) {
+            self.update_vertex(neighbor);
+        }
+        Ok(())
+    }
+
+    /// The cheapest path from the start to the goal, including both ends, or
+    /// `None` if the goal is unreachable.
+    pub fn path(&mut self) -> Option<Vec<Coord>> {
+        self.compute_shortest_path();
+        if self.rhs[self.start].is_infinite() {
+            return None;
+        }
+
+        let mut path = vec![false_index(self.start, &self.costs.dimensions)];
+        let mut current = self.start;
+        while current != self.goal {
+            let next = self
+                .neighbors(current)
+                .into_iter()
+                .map(|n| (n, self.costs.grid[n] + self.g[n]))
+                .min_by(|a, b| a.1.total_cmp(&b.1))?;
+            if next.1.is_infinite() || path.len() > self.g.len() {
+                return None;
+            }
+            current = next.0;
+            path.push(false_index(current, &self.costs.dimensions));
+        }
+        Some(path)
+    }
+
+    /// Total cost of the path returned by [`DStarLite::path`].
+    pub fn path_cost(&mut self) -> f64 {
+        self.compute_shortest_path();
+        self.rhs[self.start]
+    }
+
+    fn heuristic(&self, a: usize, b: usize) -> f64 {
+        let a = false_index(a, &self.costs.dimensions);
+        let b = false_index(b, &self.costs.dimensions);
+        Metric::Manhattan.distance(&a, &b)
+    }
+
+    fn key(&self, index: usize) -> Key {
+        let best = self.g[index].min(self.rhs[index]);
+        Key(best + self.heuristic(self.start, index) + self.km, best)
+    }
+
+    fn neighbors(&self, index: usize) -> Vec<usize> {
+        let coords = false_index(index, &self.costs.dimensions);
+        self.offsets
+            .iter()
+            .filter_map(|offset| offset_coords(&coords, offset, &self.costs.dimensions))
+            .filter_map(|c| self.costs.translate_index(&c).ok())
+            .collect()
+    }
+
+    fn push(&mut self, index: usize, key: Key) {
+        self.queued[index] = Some(key);
+        self.open.push(Reverse((key, index)));
+    }
+
+    /// The smallest key still in the queue, discarding stale entries.
+    fn top(&mut self) -> Option<(Key, usize)> {
+        while let Some(&Reverse((key, index))) = self.open.peek() {
+            if self.queued[index] == Some(key) {
+                return Some((key, index));
+            }
+            self.open.pop();
+        }
+        None
+    }
+
+    fn update_vertex(&mut self, index: usize) {
+        if index != self.goal {
+            self.rhs[index] = self
+                .neighbors(index)
+                .into_iter()
+                .map(|n| self.costs.grid[n] + self.g[n])
+                .fold(f64::INFINITY, f64::min);
+        }
+
+        self.queued[index] = None;
+        if self.g[index] != self.rhs[index] {
+            let key = self.key(index);
+            self.push(index, key);
+        }
+    }
+
+    fn compute_shortest_path(&mut self) {
+        while let Some((old_key, index)) = self.top() {
+            let start_key = self.key(self.start);
+            if old_key >= start_key && self.rhs[self.start] <= self.g[self.start] {
+                break;
+            }
+
+            let new_key = self.key(index);
+            if old_key < new_key {
+                self.push(index, new_key);
+            } else if self.g[index] > self.rhs[index] {
+                self.g[index] = self.rhs[index];
+                self.queued[index] = None;
+                for neighbor in self.neighbors(index) {
+                    self.update_vertex(neighbor);
+                }
+            } else {
+                self.g[index] = f64::INFINITY;
+                self.update_vertex(index);
+                for neighbor in self.neighbors(index) {
+                    self.update_vertex(neighbor);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn replans_around_new_obstacles() {
+        let grid = Grid::new(1.0, vec![5, 5]);
+        let mut search = DStarLite::new(&grid, &[0, 0], &[4, 4], |&c| c).unwrap();
+
+        let path = search.path().unwrap();
+        assert_eq!(path.len(), 9);
+        assert_eq!(search.path_cost(), 8.0);
+
+        // Wall off row 2 except the last column.
+        for col in 0..4 {
+            search.set_cost(&[2, col], f64::INFINITY).unwrap();
+        }
+        let path = search.path().unwrap();
+        assert!(path.contains(&smallvec![2, 4]));
+        assert_eq!(search.path_cost(), 8.0);
+
+        search.move_start(&path[3]).unwrap();
+        search.set_cost(&[2, 4], f64::INFINITY).unwrap();
+        assert_eq!(search.path(), None);
+
+        search.set_cost(&[2, 0], 3.0).unwrap();
+        let path = search.path().unwrap();
+        assert_eq!(path.first().unwrap(), &search.start());
+        assert_eq!(path.last().unwrap(), &search.goal());
+        assert!(path.contains(&smallvec![2, 0]));
+    }
+}
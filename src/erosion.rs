@@ -0,0 +1,408 @@
+use crate::{rng::Rng, Grid, GridError};
+
+/// Parameters for [`Grid::erode_hydraulic`]. The defaults suit heights in
+/// the range of about 0 to 100 on a grid of a few hundred cells per side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydraulicErosion {
+    /// Number of droplets to simulate.
+    pub droplets: usize,
+    /// Seed for where droplets start. The same seed and parameters always
+    /// carve the same terrain.
+    pub seed: u64,
+    /// Steps each droplet lives for at most.
+    pub lifetime: usize,
+    /// How much a droplet keeps its direction instead of following the
+    /// slope, from 0 to 1.
+    pub inertia: f64,
+    /// Sediment a droplet can carry per unit of slope, speed and water.
+    pub capacity: f64,
+    /// Slope used for capacity on flat ground, so droplets still erode it.
+    pub min_slope: f64,
+    /// Fraction of excess sediment deposited per step.
+    pub deposition: f64,
+    /// Fraction of spare capacity filled by eroding per step.
+    pub erosion: f64,
+    /// Fraction of water lost per step.
+    pub evaporation: f64,
+    pub gravity: f64,
+    /// Droplets `par_erode_hydraulic` rolls at once against the same
+    /// heights.
+    pub batch: usize,
+}
+
+impl HydraulicErosion {
+    pub fn new(droplets: usize, seed: u64) -> Self {
+        Self {
+            droplets,
+            seed,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for HydraulicErosion {
+    fn default() -> Self {
+        Self {
+            droplets: 10_000,
+            seed: 0,
+            lifetime: 30,
+            inertia: 0.05,
+            capacity: 4.0,
+            min_slope: 0.01,
+            deposition: 0.3,
+            erosion: 0.3,
+            evaporation: 0.01,
+            gravity: 4.0,
+            batch: 256,
+        }
+    }
+}
+
+fn check_2d(grid: &Grid<f64>) -> Result<(usize, usize), GridError> {
+    match grid.dimensions[..] {
+        [rows, cols] => Ok((rows, cols)),
+        _ => Err(GridError::UnsupportedAxes {
+            expected: 2,
+            shape: grid.dimensions.clone(),
+        }),
+    }
+}
+
+/// Bilinear weights of the four cells around a fractional position, as
+/// `(flat index, weight)`.
+fn corners(cols: usize, y: f64, x: f64) -> [(usize, f64); 4] {
+    let (row, col) = (y as usize, x as usize);
+    let (v, u) = (y - row as f64, x - col as f64);
+    let i = row * cols + col;
+    [
+        (i, (1.0 - u) * (1.0 - v)),
+        (i + 1, u * (1.0 - v)),
+        (i + cols, (1.0 - u) * v),
+        (i + cols + 1, u * v),
+    ]
+}
+
+/// Heights a droplet reads and carves, so the same simulation can run on
+/// the grid itself or against a shared snapshot.
+trait Terrain {
+    fn height(&self, i: usize) -> f64;
+    fn add(&mut self, i: usize, amount: f64);
+}
+
+impl Terrain for [f64] {
+    fn height(&self, i: usize) -> f64 {
+        self[i]
+    }
+
+    fn add(&mut self, i: usize, amount: f64) {
+        self[i] += amount;
+    }
+}
+
+/// A droplet's own changes on top of heights it can't write to, so that it
+/// still sees the channel it is carving.
+#[cfg(feature = "rayon")]
+struct Overlay<'a> {
+    base: &'a [f64],
+    changes: Vec<(usize, f64)>,
+}
+
+#[cfg(feature = "rayon")]
+impl Terrain for Overlay<'_> {
+    fn height(&self, i: usize) -> f64 {
+        let changed: f64 = (self.changes.iter())
+            .filter(|&&(j, _)| j == i)
+            .map(|&(_, d)| d)
+            .sum();
+        self.base[i] + changed
+    }
+
+    fn add(&mut self, i: usize, amount: f64) {
+        self.changes.push((i, amount));
+    }
+}
+
+/// The orthogonal neighbors of flat index `i`.
+fn neighbors(i: usize, rows: usize, cols: usize) -> [Option<usize>; 4] {
+    let (row, col) = (i / cols, i % cols);
+    [
+        (row > 0).then(|| i - cols),
+        (row + 1 < rows).then(|| i + cols),
+        (col > 0).then(|| i - 1),
+        (col + 1 < cols).then(|| i + 1),
+    ]
+}
+
+/// How much of cell `i` slides off in one thermal step, and the summed drop
+/// to the neighbors it slides onto, which share it in proportion to their
+/// drop.
+fn shed(heights: &[f64], i: usize, rows: usize, cols: usize, talus: f64, rate: f64) -> (f64, f64) {
+    let drops = neighbors(i, rows, cols)
+        .into_iter()
+        .flatten()
+        .map(|n| heights[i] - heights[n])
+        .filter(|&drop| drop > talus);
+    let (max, total) = drops.fold((f64::NEG_INFINITY, 0.0), |(max, total), drop| {
+        (f64::max(max, drop), total + drop)
+    });
+    if total == 0.0 {
+        return (0.0, 0.0);
+    }
+    // Moving half the excess at most keeps the cell from ending up lower
+    // than the neighbor it slid onto.
+    (rate.clamp(0.0, 1.0) * (max - talus) / 2.0, total)
+}
+
+/// Cell `i`'s height after one thermal step, given what every cell sheds.
+fn settle(
+    heights: &[f64],
+    flow: &[(f64, f64)],
+    i: usize,
+    rows: usize,
+    cols: usize,
+    talus: f64,
+) -> f64 {
+    let received: f64 = (neighbors(i, rows, cols).into_iter().flatten())
+        .map(|n| (n, heights[n] - heights[i]))
+        .filter(|&(_, drop)| drop > talus)
+        .map(|(n, drop)| flow[n].0 * drop / flow[n].1)
+        .sum();
+    heights[i] - flow[i].0 + received
+}
+
+/// Rolls droplet number `droplet` over `terrain`, which is `rows` by `cols`
+/// with both at least 2.
+fn roll<H: Terrain + ?Sized>(
+    terrain: &mut H,
+    params: &HydraulicErosion,
+    rows: usize,
+    cols: usize,
+    droplet: usize,
+) {
+    let (max_y, max_x) = ((rows - 1) as f64, (cols - 1) as f64);
+
+    // Height and gradient (d/dy, d/dx) at a fractional position.
+    let sample = |terrain: &H, y: f64, x: f64| {
+        let [a, b, c, d] = corners(cols, y, x).map(|(i, _)| terrain.height(i));
+        let (v, u) = (y.fract(), x.fract());
+        let height = a * (1.0 - u) * (1.0 - v) + b * u * (1.0 - v) + c * (1.0 - u) * v + d * u * v;
+        let gy = (c - a) * (1.0 - u) + (d - b) * u;
+        let gx = (b - a) * (1.0 - v) + (d - c) * v;
+        (height, gy, gx)
+    };
+
+    let mut rng = Rng::for_stream(params.seed, droplet as u64);
+    let (mut y, mut x) = (rng.next_f64() * max_y, rng.next_f64() * max_x);
+    let (mut dy, mut dx) = (0.0, 0.0);
+    let (mut speed, mut water, mut sediment) = (1.0, 1.0, 0.0);
+
+    for _ in 0..params.lifetime {
+        let (height, gy, gx) = sample(terrain, y, x);
+        dy = dy * params.inertia - gy * (1.0 - params.inertia);
+        dx = dx * params.inertia - gx * (1.0 - params.inertia);
+        let len = f64::hypot(dy, dx);
+        if len == 0.0 {
+            break;
+        }
+        (dy, dx) = (dy / len, dx / len);
+
+        let (new_y, new_x) = (y + dy, x + dx);
+        if !(0.0..max_y).contains(&new_y) || !(0.0..max_x).contains(&new_x) {
+            break;
+        }
+        let rise = sample(terrain, new_y, new_x).0 - height;
+
+        let capacity = (-rise).max(params.min_slope) * speed * water * params.capacity;
+        if rise > 0.0 || sediment > capacity {
+            let deposit = if rise > 0.0 {
+                rise.min(sediment)
+            } else {
+                (sediment - capacity) * params.deposition
+            };
+            sediment -= deposit;
+            for (i, w) in corners(cols, y, x) {
+                terrain.add(i, deposit * w);
+            }
+        } else {
+            let eroded = ((capacity - sediment) * params.erosion).min(-rise);
+            sediment += eroded;
+            for (i, w) in corners(cols, y, x) {
+                terrain.add(i, -eroded * w);
+            }
+        }
+
+        speed = (speed * speed - rise * params.gravity).max(0.0).sqrt();
+        water *= 1.0 - params.evaporation;
+        (y, x) = (new_y, new_x);
+    }
+}
+
+impl Grid<f64> {
+    /// Thermal erosion of a 2D heightmap: each iteration, wherever a cell is
+    /// more than `talus` higher than its orthogonal neighbors, `rate` of the
+    /// excess slides down to them. Every cell is updated from the same
+    /// snapshot, so the result doesn't depend on visiting order.
+    pub fn erode_thermal(
+        &mut self,
+        talus: f64,
+        rate: f64,
+        iterations: usize,
+    ) -> Result<(), GridError> {
+        let (rows, cols) = check_2d(self)?;
+        let mut flow = vec![(0.0, 0.0); self.grid.len()];
+        let mut next = vec![0.0; self.grid.len()];
+        for _ in 0..iterations {
+            let heights = &self.grid;
+            for (i, f) in flow.iter_mut().enumerate() {
+                *f = shed(heights, i, rows, cols, talus, rate);
+            }
+            for (i, h) in next.iter_mut().enumerate() {
+                *h = settle(heights, &flow, i, rows, cols, talus);
+            }
+            std::mem::swap(&mut self.grid, &mut next);
+        }
+        Ok(())
+    }
+
+    /// Droplet-based hydraulic erosion of a 2D heightmap: droplets roll
+    /// downhill from random cells, picking up sediment where they speed up
+    /// and dropping it where they slow down, carving channels and
+    /// depositing fans. Each droplet draws from its own random stream, so
+    /// batches of droplets are independent of each other's randomness.
+    pub fn erode_hydraulic(&mut self, params: &HydraulicErosion) -> Result<(), GridError> {
+        let (rows, cols) = check_2d(self)?;
+        if rows < 2 || cols < 2 {
+            return Ok(());
+        }
+        for droplet in 0..params.droplets {
+            roll(&mut self.grid[..], params, rows, cols, droplet);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl Grid<f64> {
+    /// [`Grid::erode_thermal`] with each iteration spread over threads. The
+    /// result is the same as the serial version's, bit for bit.
+    pub fn par_erode_thermal(
+        &mut self,
+        talus: f64,
+        rate: f64,
+        iterations: usize,
+    ) -> Result<(), GridError> {
+        use rayon::prelude::*;
+
+        let (rows, cols) = check_2d(self)?;
+        let mut flow = vec![(0.0, 0.0); self.grid.len()];
+        let mut next = vec![0.0; self.grid.len()];
+        for _ in 0..iterations {
+            let heights = &self.grid;
+            (flow.par_iter_mut().enumerate())
+                .for_each(|(i, f)| *f = shed(heights, i, rows, cols, talus, rate));
+            let flow = &flow;
+            (next.par_iter_mut().enumerate())
+                .for_each(|(i, h)| *h = settle(heights, flow, i, rows, cols, talus));
+            std::mem::swap(&mut self.grid, &mut next);
+        }
+        Ok(())
+    }
+
+    /// [`Grid::erode_hydraulic`] with droplets rolled in parallel batches of
+    /// [`HydraulicErosion::batch`]. Droplets in a batch all start from the
+    /// heights left by the previous batch, so the result depends on the batch
+    /// size but not on the number of threads, and differs from the serial
+    /// version's.
+    pub fn par_erode_hydraulic(&mut self, params: &HydraulicErosion) -> Result<(), GridError> {
+        use rayon::prelude::*;
+
+        let (rows, cols) = check_2d(self)?;
+        if rows < 2 || cols < 2 {
+            return Ok(());
+        }
+        let batch = params.batch.max(1);
+        for start in (0..params.droplets).step_by(batch) {
+            let end = (start + batch).min(params.droplets);
+            let base = &self.grid;
+            let changes: Vec<_> = (start..end)
+                .into_par_iter()
+                .map(|droplet| {
+                    let mut overlay = Overlay {
+                        base,
+                        changes: Vec::new(),
+                    };
+                    roll(&mut overlay, params, rows, cols, droplet);
+                    overlay.changes
+                })
+                .collect();
+            for (i, amount) in changes.into_iter().flatten() {
+                self.grid[i] += amount;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thermal_flattens_cliffs() {
+        let mut terrain = Grid::new(0.0, vec![1, 3]);
+        terrain.set(&[0, 1], 10.0).unwrap();
+        terrain.erode_thermal(1.0, 0.5, 200).unwrap();
+
+        let heights: Vec<f64> = terrain.iter().copied().collect();
+        assert!((heights.iter().sum::<f64>() - 10.0).abs() < 1e-9);
+        assert!(heights[1] - heights[0] <= 1.0 + 1e-6);
+        assert_eq!(heights[0], heights[2]);
+        assert!(Grid::new(0.0, vec![3]).erode_thermal(1.0, 0.5, 1).is_err());
+    }
+
+    #[test]
+    fn hydraulic_is_seeded() {
+        let hill = Grid::from_fn(vec![32, 32], |c| {
+            let (y, x) = (c[0] as f64 - 16.0, c[1] as f64 - 16.0);
+            50.0 - (y * y + x * x).sqrt() * 2.0
+        });
+
+        let params = HydraulicErosion::new(500, 7);
+        let mut a = hill.clone();
+        a.erode_hydraulic(&params).unwrap();
+        let mut b = hill.clone();
+        b.erode_hydraulic(&params).unwrap();
+        assert!(a.iter().eq(b.iter()));
+        assert!(a.iter().zip(hill.iter()).any(|(x, y)| x != y));
+
+        let mut c = hill.clone();
+        c.erode_hydraulic(&HydraulicErosion::new(500, 8)).unwrap();
+        assert!(!a.iter().eq(c.iter()));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_erosion() {
+        let hill = Grid::from_fn(vec![24, 40], |c| {
+            let (y, x) = (c[0] as f64 - 12.0, c[1] as f64 - 20.0);
+            30.0 - (y * y + x * x).sqrt() + (c[1] % 3) as f64
+        });
+
+        let mut serial = hill.clone();
+        serial.erode_thermal(0.5, 0.4, 25).unwrap();
+        let mut parallel = hill.clone();
+        parallel.par_erode_thermal(0.5, 0.4, 25).unwrap();
+        assert!(serial.iter().eq(parallel.iter()));
+
+        let params = HydraulicErosion::new(300, 3);
+        let mut a = hill.clone();
+        a.par_erode_hydraulic(&params).unwrap();
+        let mut b = hill.clone();
+        b.par_erode_hydraulic(&params).unwrap();
+        assert!(a.iter().eq(b.iter()));
+        assert!(a.iter().zip(hill.iter()).any(|(x, y)| x != y));
+        assert!(Grid::new(0.0, vec![3])
+            .par_erode_hydraulic(&params)
+            .is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use std::{error::Error, fmt, ops::Range};
+
+use crate::Coord;
+
+/// Errors from grid operations. Every variant carries the shape of the grid
+/// involved, plus the coordinates, axis or region that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// Coordinates had a different number of axes than the grid.
+    DimensionMismatch { coords: Coord, shape: Vec<usize> },
+    /// A coordinate was past the end of its axis.
+    OutOfBounds {
+        coords: Coord,
+        axis: usize,
+        shape: Vec<usize>,
+    },
+    /// Moving coordinates by an offset left the grid.
+    OffsetOutOfBounds {
+        coords: Coord,
+        offset: Vec<isize>,
+        shape: Vec<usize>,
+    },
+    /// A region wasn't entirely inside the grid, or had a different number
+    /// of axes.
+    RegionOutOfBounds {
+        ranges: Vec<Range<usize>>,
+        shape: Vec<usize>,
+    },
+    /// The operation only supports grids with `expected` axes.
+    UnsupportedAxes { expected: usize, shape: Vec<usize> },
+    /// An axis number was not less than the grid's number of axes.
+    InvalidAxis { axis: usize, shape: Vec<usize> },
+    /// Two grids that must have the same dimensions did not.
+    ShapeMismatch {
+        expected: Vec<usize>,
+        got: Vec<usize>,
+    },
+    /// A flat buffer didn't hold exactly one value per cell.
+    LengthMismatch { len: usize, shape: Vec<usize> },
+    /// No region was registered under a name.
+    UnknownRegion { name: String, shape: Vec<usize> },
+    /// A numeric argument was outside the range it must be in, described by
+    /// `expected`.
+    InvalidParameter {
+        name: &'static str,
+        expected: &'static str,
+        shape: Vec<usize>,
+    },
+}
+
+/// Checks that `coords` address a cell of a grid with the given shape.
+pub(crate) fn check_coords(coords: &[usize], shape: &[usize]) -> Result<(), GridError> {
+    if coords.len() != shape.len() {
+        return Err(GridError::DimensionMismatch {
+            coords: Coord::from_slice(coords),
+            shape: shape.to_vec(),
+        });
+    }
+    match coords.iter().zip(shape).position(|(c, len)| c >= len) {
+        Some(axis) => Err(GridError::OutOfBounds {
+            coords: Coord::from_slice(coords),
+            axis,
+            shape: shape.to_vec(),
+        }),
+        None => Ok(()),
+    }
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::DimensionMismatch { coords, shape } => write!(
+                f,
+                "ERROR: Coordinates {:?} have {} axes but the grid of shape {:?} has {}",
+                coords,
+                coords.len(),
+                shape,
+                shape.len()
+            ),
+            GridError::OutOfBounds {
+                coords,
+                axis,
+                shape,
+            } => write!(
+                f,
+                "ERROR: Coordinates {:?} out of bounds on axis {} ({} >= {}) of grid with shape {:?}",
+                coords, axis, coords[*axis], shape[*axis], shape
+            ),
+            GridError::OffsetOutOfBounds {
+                coords,
+                offset,
+                shape,
+            } => write!(
+                f,
+                "ERROR: Offset {:?} from {:?} leaves grid with shape {:?}",
+                offset, coords, shape
+            ),
+            GridError::RegionOutOfBounds { ranges, shape } => write!(
+                f,
+                "ERROR: Region {:?} is not within grid with shape {:?}",
+                ranges, shape
+            ),
+            GridError::UnsupportedAxes { expected, shape } => write!(
+                f,
+                "ERROR: Operation requires a grid with {} axes, got shape {:?}",
+                expected, shape
+            ),
+            GridError::InvalidAxis { axis, shape } => write!(
+                f,
+                "ERROR: Axis {} does not exist in grid with shape {:?}",
+                axis, shape
+            ),
+            GridError::ShapeMismatch { expected, got } => write!(
+                f,
+                "ERROR: Expected a grid with dimensions {:?}, got {:?}",
+                expected, got
+            ),
+            GridError::LengthMismatch { len, shape } => write!(
+                f,
+                "ERROR: {} values cannot fill a grid with shape {:?}",
+                len, shape
+            ),
+            GridError::UnknownRegion { name, shape } => write!(
+                f,
+                "ERROR: No region named {:?} in grid with shape {:?}",
+                name, shape
+            ),
+            GridError::InvalidParameter {
+                name,
+                expected,
+                shape,
+            } => write!(
+                f,
+                "ERROR: Parameter {} must be {} for grid with shape {:?}",
+                name, expected, shape
+            ),
+        }
+    }
+}
+
+impl Error for GridError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn messages_echo_coordinates() {
+        let err = check_coords(&[1, 2, 7, 0], &[4, 4, 4, 4]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ERROR: Coordinates [1, 2, 7, 0] out of bounds on axis 2 (7 >= 4) of grid with shape [4, 4, 4, 4]"
+        );
+
+        let err = check_coords(&[1, 2], &[4, 4, 4]).unwrap_err();
+        assert_eq!(
+            err,
+            GridError::DimensionMismatch {
+                coords: smallvec![1, 2],
+                shape: vec![4, 4, 4]
+            }
+        );
+    }
+}
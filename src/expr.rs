@@ -0,0 +1,421 @@
+use std::{error::Error, fmt};
+
+use crate::{false_index, Coord, Direction4, EdgePolicy, Grid, GridError};
+
+/// A parse error, with the byte offset in the source where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ERROR: {} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for ExprError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Var {
+    Value,
+    Row,
+    Col,
+    Neighbor(Direction4),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Abs,
+    Sqrt,
+    Floor,
+    Ceil,
+    Min,
+    Max,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<(Func, usize)> {
+        Some(match name {
+            "abs" => (Func::Abs, 1),
+            "sqrt" => (Func::Sqrt, 1),
+            "floor" => (Func::Floor, 1),
+            "ceil" => (Func::Ceil, 1),
+            "min" => (Func::Min, 2),
+            "max" => (Func::Max, 2),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Num(f64),
+    Var(Var),
+    Neg(Box<Node>),
+    Binary(u8, Box<Node>, Box<Node>),
+    Call(Func, Vec<Node>),
+}
+
+/// How deeply expressions may nest, which bounds the recursion both when
+/// parsing and when evaluating.
+const MAX_DEPTH: usize = 200;
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    /// Parentheses, calls and operators currently being parsed.
+    depth: usize,
+    /// Height of the node last parsed.
+    height: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ExprError> {
+        Err(ExprError {
+            position: self.pos,
+            message: message.into(),
+        })
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        while self.src.as_bytes().get(self.pos) == Some(&b' ') {
+            self.pos += 1;
+        }
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Runs `parse` one level of nesting deeper.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Node, ExprError>,
+    ) -> Result<Node, ExprError> {
+        if self.depth == MAX_DEPTH {
+            return self.error("Expression nests too deeply");
+        }
+        self.depth += 1;
+        let node = parse(self);
+        self.depth -= 1;
+        node
+    }
+
+    /// Records the height of a node just built from parsed children.
+    fn built(&mut self, node: Node, height: usize) -> Result<Node, ExprError> {
+        if height > MAX_DEPTH {
+            return self.error("Expression nests too deeply");
+        }
+        self.height = height;
+        Ok(node)
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.src.as_bytes().get(self.pos).is_some_and(|&c| f(c)) {
+            self.pos += 1;
+        }
+        &self.src[start..self.pos]
+    }
+
+    /// `sum := product (("+" | "-") product)*`
+    fn sum(&mut self) -> Result<Node, ExprError> {
+        let mut node = self.product()?;
+        while let Some(op @ (b'+' | b'-')) = self.peek() {
+            self.pos += 1;
+            let height = self.height;
+            let rhs = self.product()?;
+            let height = 1 + height.max(self.height);
+            node = self.built(Node::Binary(op, Box::new(node), Box::new(rhs)), height)?;
+        }
+        Ok(node)
+    }
+
+    /// `product := unary (("*" | "/" | "%") unary)*`
+    fn product(&mut self) -> Result<Node, ExprError> {
+        let mut node = self.unary()?;
+        while let Some(op @ (b'*' | b'/' | b'%')) = self.peek() {
+            self.pos += 1;
+            let height = self.height;
+            let rhs = self.unary()?;
+            let height = 1 + height.max(self.height);
+            node = self.built(Node::Binary(op, Box::new(node), Box::new(rhs)), height)?;
+        }
+        Ok(node)
+    }
+
+    /// `unary := "-" unary | atom ("^" unary)?`
+    fn unary(&mut self) -> Result<Node, ExprError> {
+        if self.eat(b'-') {
+            let node = self.nested(Self::unary)?;
+            let height = self.height + 1;
+            return self.built(Node::Neg(Box::new(node)), height);
+        }
+        let base = self.atom()?;
+        if self.eat(b'^') {
+            let height = self.height;
+            let exponent = self.nested(Self::unary)?;
+            let height = 1 + height.max(self.height);
+            let node = Node::Binary(b'^', Box::new(base), Box::new(exponent));
+            return self.built(node, height);
+        }
+        Ok(base)
+    }
+
+    /// `atom := number | variable | function "(" args ")" | "(" sum ")"`
+    fn atom(&mut self) -> Result<Node, ExprError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let node = self.nested(Self::sum)?;
+                if !self.eat(b')') {
+                    return self.error("Expected ')'");
+                }
+                Ok(node)
+            }
+            Some(c) if c.is_ascii_digit() || c == b'.' => {
+                let text = self.take_while(|c| c.is_ascii_digit() || c == b'.');
+                match text.parse() {
+                    Ok(n) => self.built(Node::Num(n), 1),
+                    Err(_) => {
+                        self.pos = start;
+                        self.error(format!("Invalid number {:?}", text))
+                    }
+                }
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == b'_');
+                let var = match name {
+                    "v" => Some(Var::Value),
+                    "row" => Some(Var::Row),
+                    "col" => Some(Var::Col),
+                    "north" => Some(Var::Neighbor(Direction4::North)),
+                    "east" => Some(Var::Neighbor(Direction4::East)),
+                    "south" => Some(Var::Neighbor(Direction4::South)),
+                    "west" => Some(Var::Neighbor(Direction4::West)),
+                    _ => None,
+                };
+                if let Some(var) = var {
+                    return self.built(Node::Var(var), 1);
+                }
+
+                let Some((func, arity)) = Func::from_name(name) else {
+                    self.pos = start;
+                    return self.error(format!("Unknown name {:?}", name));
+                };
+                if !self.eat(b'(') {
+                    return self.error("Expected '('");
+                }
+                let mut args = vec![self.nested(Self::sum)?];
+                let mut height = self.height;
+                while self.eat(b',') {
+                    args.push(self.nested(Self::sum)?);
+                    height = height.max(self.height);
+                }
+                if !self.eat(b')') {
+                    return self.error("Expected ')'");
+                }
+                if args.len() != arity {
+                    self.pos = start;
+                    return self.error(format!("{:?} takes {} arguments", name, arity));
+                }
+                self.built(Node::Call(func, args), height + 1)
+            }
+            Some(c) => self.error(format!("Unexpected {:?}", c as char)),
+            None => self.error("Unexpected end of expression"),
+        }
+    }
+}
+
+/// A per-cell formula parsed at runtime, for [`Grid::eval_expr`].
+///
+/// Expressions combine numbers with `+ - * / % ^`, parentheses, and the
+/// functions `abs`, `sqrt`, `floor`, `ceil`, `min` and `max`. They can read
+/// these variables:
+///
+/// - `v`: the cell's value
+/// - `row`, `col`: the cell's coordinates (2D grids only)
+/// - `north`, `east`, `south`, `west`: neighboring values (2D grids only)
+///
+/// ```
+/// use md_grid::{expr::Expr, EdgePolicy, Grid};
+///
+/// let mut grid = Grid::new(1.0, vec![3, 3]);
+/// grid.set(&[0, 1], 5.0).unwrap();
+///
+/// let expr = Expr::parse("v * 2 + north").unwrap();
+/// let out = grid.eval_expr(&expr, EdgePolicy::Clamp).unwrap();
+/// assert_eq!(*out.get(&[1, 1]).unwrap(), 7.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    root: Node,
+    spatial: bool,
+}
+
+impl Expr {
+    /// Parses `src`, failing on invalid syntax or on nesting more than 200
+    /// levels deep.
+    pub fn parse(src: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser {
+            src,
+            pos: 0,
+            depth: 0,
+            height: 0,
+        };
+        let root = parser.sum()?;
+        if parser.peek().is_some() {
+            return parser.error("Unexpected trailing input");
+        }
+        let spatial = root.uses_coords();
+        Ok(Self { root, spatial })
+    }
+
+    /// Evaluates the expression for a cell value alone, without coordinates
+    /// or neighbors.
+    pub fn eval(&self, v: f64) -> Result<f64, GridError> {
+        if self.spatial {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: Vec::new(),
+            });
+        }
+        Ok(self.root.eval(&|var| match var {
+            Var::Value => v,
+            _ => unreachable!(),
+        }))
+    }
+}
+
+impl Node {
+    fn uses_coords(&self) -> bool {
+        match self {
+            Node::Num(_) | Node::Var(Var::Value) => false,
+            Node::Var(_) => true,
+            Node::Neg(a) => a.uses_coords(),
+            Node::Binary(_, a, b) => a.uses_coords() || b.uses_coords(),
+            Node::Call(_, args) => args.iter().any(Node::uses_coords),
+        }
+    }
+
+    fn eval(&self, lookup: &impl Fn(Var) -> f64) -> f64 {
+        match self {
+            Node::Num(n) => *n,
+            Node::Var(var) => lookup(*var),
+            Node::Neg(a) => -a.eval(lookup),
+            Node::Binary(op, a, b) => {
+                let (a, b) = (a.eval(lookup), b.eval(lookup));
+                match op {
+                    b'+' => a + b,
+                    b'-' => a - b,
+                    b'*' => a * b,
+                    b'/' => a / b,
+                    b'%' => a % b,
+                    _ => a.powf(b),
+                }
+            }
+            Node::Call(func, args) => {
+                let a = args[0].eval(lookup);
+                match func {
+                    Func::Abs => a.abs(),
+                    Func::Sqrt => a.sqrt(),
+                    Func::Floor => a.floor(),
+                    Func::Ceil => a.ceil(),
+                    Func::Min => a.min(args[1].eval(lookup)),
+                    Func::Max => a.max(args[1].eval(lookup)),
+                }
+            }
+        }
+    }
+}
+
+impl<T: Copy + Into<f64>> Grid<T> {
+    /// Evaluates `expr` for every cell. Neighbors past the edge of the grid
+    /// are clamped to the nearest cell or wrapped around, per `edges`.
+    pub fn eval_expr(&self, expr: &Expr, edges: EdgePolicy) -> Result<Grid<f64>, GridError> {
+        if expr.spatial && self.axes != 2 {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            });
+        }
+
+        let step = |i: usize, delta: isize, len: usize| match edges {
+            EdgePolicy::Clamp => i.saturating_add_signed(delta).min(len - 1),
+            EdgePolicy::Wrap => (i as isize + delta).rem_euclid(len as isize) as usize,
+        };
+        let grid = (0..self.grid.len())
+            .map(|i| {
+                let coords = if expr.spatial {
+                    false_index(i, &self.dimensions)
+                } else {
+                    Coord::new()
+                };
+                expr.root.eval(&|var| match var {
+                    Var::Value => self.grid[i].into(),
+                    Var::Row => coords[0] as f64,
+                    Var::Col => coords[1] as f64,
+                    Var::Neighbor(dir) => {
+                        let [dr, dc] = dir.offset();
+                        let r = step(coords[0], dr, self.dimensions[0]);
+                        let c = step(coords[1], dc, self.dimensions[1]);
+                        self.grid[r * self.dimensions[1] + c].into()
+                    }
+                })
+            })
+            .collect();
+
+        Ok(Grid::from_parts(grid, self.dimensions.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_eval() {
+        let expr = Expr::parse("-2 ^ 2 + max(v, 3) * (1 + 1) % 5").unwrap();
+        assert_eq!(expr.eval(4.0).unwrap(), -4.0 + 8.0 % 5.0);
+        assert_eq!(Expr::parse(" sqrt(abs(v)) ").unwrap().eval(-9.0), Ok(3.0));
+
+        let err = Expr::parse("v + foo").unwrap_err();
+        assert_eq!(err.position, 4);
+        assert!(Expr::parse("min(v)").is_err());
+        assert!(Expr::parse("(v + 1").is_err());
+        assert!(Expr::parse("v v").is_err());
+        assert!(Expr::parse("north").unwrap().eval(1.0).is_err());
+
+        let too_deep = Expr::parse(&"(".repeat(100_000)).unwrap_err();
+        assert_eq!(too_deep.message, "Expression nests too deeply");
+        assert!(Expr::parse(&"-".repeat(100_000)).is_err());
+        assert!(Expr::parse(&["v"; 100_000].join("+")).is_err());
+        let nested = format!("{}v{}", "abs(".repeat(50), ")".repeat(50));
+        assert_eq!(Expr::parse(&nested).unwrap().eval(-1.0), Ok(1.0));
+    }
+
+    #[test]
+    fn neighbors_and_edges() {
+        let mut grid = Grid::new(0u8, vec![2, 3]);
+        grid.set(&[0, 0], 1).unwrap();
+        grid.set(&[1, 2], 9).unwrap();
+
+        let expr = Expr::parse("west + row * 10 + col").unwrap();
+        let clamped = grid.eval_expr(&expr, EdgePolicy::Clamp).unwrap();
+        assert!(clamped.iter().eq(&[1.0, 2.0, 2.0, 10.0, 11.0, 12.0]));
+        let wrapped = grid.eval_expr(&expr, EdgePolicy::Wrap).unwrap();
+        assert!(wrapped.iter().eq(&[0.0, 2.0, 2.0, 19.0, 11.0, 12.0]));
+
+        assert!(Grid::new(0u8, vec![4])
+            .eval_expr(&expr, EdgePolicy::Clamp)
+            .is_err());
+    }
+}
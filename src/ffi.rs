@@ -0,0 +1,322 @@
+//! C ABI for grids of `f64`, declared in `include/md_grid.h`.
+//!
+//! Grids cross the boundary as opaque `MdGrid` handles. Functions taking a
+//! handle return [`MD_GRID_NULL`] (or a null pointer or zero) when given a
+//! null one. To link from C, build a `staticlib` or `cdylib` crate that
+//! depends on this one with the `ffi` feature enabled.
+
+use std::{ffi::c_void, os::raw::c_int, ptr, slice};
+
+use crate::{false_index, Grid};
+
+pub const MD_GRID_OK: c_int = 0;
+pub const MD_GRID_NULL: c_int = -1;
+pub const MD_GRID_OUT_OF_BOUNDS: c_int = -2;
+
+/// An opaque handle to a `Grid<f64>`.
+pub struct MdGrid(Grid<f64>);
+
+impl MdGrid {
+    /// Hands `grid` to C. Free the handle with [`md_grid_free`] or take it
+    /// back with [`MdGrid::from_raw`].
+    pub fn into_raw(grid: Grid<f64>) -> *mut MdGrid {
+        Box::into_raw(Box::new(MdGrid(grid)))
+    }
+
+    /// Takes back ownership of a grid handed to C.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must have come from [`MdGrid::into_raw`] or [`md_grid_new`]
+    /// and not have been freed.
+    pub unsafe fn from_raw(handle: *mut MdGrid) -> Grid<f64> {
+        // SAFETY: the caller guarantees `handle` came from `Box::into_raw` and
+        // is still live, so we may take back the box.
+        unsafe { Box::from_raw(handle).0 }
+    }
+}
+
+/// Borrows the grid behind a handle, or `None` for a null handle.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle not being mutated elsewhere.
+unsafe fn grid_ref<'a>(grid: *const MdGrid) -> Option<&'a Grid<f64>> {
+    // SAFETY: the caller guarantees `grid` is null or points to a live,
+    // unaliased-for-writes `MdGrid`.
+    unsafe { grid.as_ref() }.map(|g| &g.0)
+}
+
+/// Mutably borrows the grid behind a handle, or `None` for a null handle.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle not borrowed elsewhere.
+unsafe fn grid_mut<'a>(grid: *mut MdGrid) -> Option<&'a mut Grid<f64>> {
+    // SAFETY: the caller guarantees `grid` is null or points to a live
+    // `MdGrid` with no other borrows.
+    unsafe { grid.as_mut() }.map(|g| &mut g.0)
+}
+
+/// Creates a grid with `axes` dimensions read from `dimensions`, with every
+/// cell set to `fill`. Returns null if `dimensions` is null or the cells
+/// can't be allocated.
+///
+/// # Safety
+///
+/// `dimensions` must point to `axes` readable values.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_new(
+    dimensions: *const usize,
+    axes: usize,
+    fill: f64,
+) -> *mut MdGrid {
+    if dimensions.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: non-null, and the caller guarantees `axes` readable values.
+    let dimensions = unsafe { slice::from_raw_parts(dimensions, axes) }.to_vec();
+    match Grid::try_new(fill, dimensions) {
+        Some(grid) => MdGrid::into_raw(grid),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees a grid. Does nothing if `grid` is null.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle, which is invalid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_free(grid: *mut MdGrid) {
+    if !grid.is_null() {
+        // SAFETY: the caller guarantees a non-null `grid` is a live handle.
+        drop(unsafe { MdGrid::from_raw(grid) });
+    }
+}
+
+/// Number of axes.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_axes(grid: *const MdGrid) -> usize {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    unsafe { grid_ref(grid) }.map_or(0, |g| g.axes)
+}
+
+/// Pointer to the grid's `md_grid_axes` dimensions, valid while the grid
+/// lives.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_dimensions(grid: *const MdGrid) -> *const usize {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    unsafe { grid_ref(grid) }.map_or(ptr::null(), |g| g.dimensions.as_ptr())
+}
+
+/// Total number of cells.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_len(grid: *const MdGrid) -> usize {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    unsafe { grid_ref(grid) }.map_or(0, |g| g.grid.len())
+}
+
+/// Pointer to the cells in row-major order, valid until the grid is freed.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_data(grid: *const MdGrid) -> *const f64 {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    unsafe { grid_ref(grid) }.map_or(ptr::null(), |g| g.grid.as_ptr())
+}
+
+/// Mutable pointer to the cells in row-major order, valid until the grid is
+/// freed.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_data_mut(grid: *mut MdGrid) -> *mut f64 {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    unsafe { grid_mut(grid) }.map_or(ptr::null_mut(), |g| g.grid.as_mut_ptr())
+}
+
+/// Writes the stride of each axis, in cells, to `strides`: the cell at
+/// `coords` is at `data[sum(coords[i] * strides[i])]`.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle, and `strides` must point to
+/// `md_grid_axes` writable values.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_strides(grid: *const MdGrid, strides: *mut usize) -> c_int {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    let Some(grid) = (unsafe { grid_ref(grid) }) else {
+        return MD_GRID_NULL;
+    };
+    if strides.is_null() {
+        return MD_GRID_NULL;
+    }
+    // SAFETY: non-null, and the caller guarantees `axes` writable values.
+    let out = unsafe { slice::from_raw_parts_mut(strides, grid.axes) };
+    out.copy_from_slice(&grid.shape().strides());
+    MD_GRID_OK
+}
+
+/// Reads the cell at `coords` into `out`.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle, `coords` must point to
+/// `md_grid_axes` readable values and `out` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_get(
+    grid: *const MdGrid,
+    coords: *const usize,
+    out: *mut f64,
+) -> c_int {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    let Some(grid) = (unsafe { grid_ref(grid) }) else {
+        return MD_GRID_NULL;
+    };
+    if coords.is_null() || out.is_null() {
+        return MD_GRID_NULL;
+    }
+    // SAFETY: non-null, and the caller guarantees `axes` readable values.
+    let coords = unsafe { slice::from_raw_parts(coords, grid.axes) };
+    match grid.get(coords) {
+        Ok(&val) => {
+            // SAFETY: non-null, and the caller guarantees `out` is writable.
+            unsafe { *out = val };
+            MD_GRID_OK
+        }
+        Err(_) => MD_GRID_OUT_OF_BOUNDS,
+    }
+}
+
+/// Sets the cell at `coords` to `value`.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle and `coords` must point to
+/// `md_grid_axes` readable values.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_set(grid: *mut MdGrid, coords: *const usize, value: f64) -> c_int {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    let Some(grid) = (unsafe { grid_mut(grid) }) else {
+        return MD_GRID_NULL;
+    };
+    if coords.is_null() {
+        return MD_GRID_NULL;
+    }
+    // SAFETY: non-null, and the caller guarantees `axes` readable values.
+    let coords = unsafe { slice::from_raw_parts(coords, grid.axes) };
+    match grid.set(coords, value) {
+        Ok(()) => MD_GRID_OK,
+        Err(_) => MD_GRID_OUT_OF_BOUNDS,
+    }
+}
+
+/// Calls `callback` with the coordinates and value of every cell in
+/// row-major order, passing `user_data` through. Stops early, returning the
+/// callback's result, if it returns nonzero.
+///
+/// # Safety
+///
+/// `grid` must be null or a live handle. The coordinates pointer passed to
+/// `callback` is only valid during that call.
+#[no_mangle]
+pub unsafe extern "C" fn md_grid_for_each(
+    grid: *const MdGrid,
+    callback: Option<extern "C" fn(*const usize, f64, *mut c_void) -> c_int>,
+    user_data: *mut c_void,
+) -> c_int {
+    // SAFETY: the caller guarantees `grid` is null or a live handle.
+    let (Some(grid), Some(callback)) = (unsafe { grid_ref(grid) }, callback) else {
+        return MD_GRID_NULL;
+    };
+    for (i, &val) in grid.grid.iter().enumerate() {
+        let coords = false_index(i, &grid.dimensions);
+        let status = callback(coords.as_ptr(), val, user_data);
+        if status != 0 {
+            return status;
+        }
+    }
+    MD_GRID_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn sum_weighted(coords: *const usize, val: f64, user: *mut c_void) -> c_int {
+        // SAFETY: `md_grid_for_each` passes the coordinates of a 2D grid and
+        // `round_trip` passes a pointer to an `f64` as user data.
+        unsafe {
+            let coords = slice::from_raw_parts(coords, 2);
+            *(user as *mut f64) += val * (coords[0] * 10 + coords[1]) as f64;
+        }
+        0
+    }
+
+    #[test]
+    fn round_trip() {
+        // SAFETY: every handle passed below is live or null, and every array
+        // is as long as the grid has axes.
+        unsafe {
+            let grid = md_grid_new([2usize, 3].as_ptr(), 2, 1.0);
+            assert_eq!(md_grid_axes(grid), 2);
+            assert_eq!(md_grid_len(grid), 6);
+            assert_eq!(*md_grid_dimensions(grid).add(1), 3);
+
+            assert_eq!(md_grid_set(grid, [1usize, 2].as_ptr(), 4.0), MD_GRID_OK);
+            assert_eq!(
+                md_grid_set(grid, [2usize, 0].as_ptr(), 4.0),
+                MD_GRID_OUT_OF_BOUNDS
+            );
+            let mut out = 0.0;
+            assert_eq!(
+                md_grid_get(grid, [1usize, 2].as_ptr(), &mut out),
+                MD_GRID_OK
+            );
+            assert_eq!(out, 4.0);
+
+            let mut strides = [0usize; 2];
+            assert_eq!(md_grid_strides(grid, strides.as_mut_ptr()), MD_GRID_OK);
+            assert_eq!(strides, [3, 1]);
+            assert_eq!(*md_grid_data(grid).add(strides[0] + 2 * strides[1]), 4.0);
+
+            let mut sum = 0.0;
+            let status = md_grid_for_each(
+                grid,
+                Some(sum_weighted),
+                &mut sum as *mut f64 as *mut c_void,
+            );
+            assert_eq!(status, MD_GRID_OK);
+            assert_eq!(sum, 0.0 + 1.0 + 2.0 + 10.0 + 11.0 + 12.0 * 4.0);
+
+            let grid = MdGrid::from_raw(grid);
+            assert_eq!(*grid.get(&[1, 2]).unwrap(), 4.0);
+
+            assert!(md_grid_new([usize::MAX, 2].as_ptr(), 2, 0.0).is_null());
+            assert!(md_grid_new([1usize << 61].as_ptr(), 1, 0.0).is_null());
+            assert_eq!(md_grid_len(ptr::null()), 0);
+            assert_eq!(
+                md_grid_get(ptr::null(), ptr::null(), &mut out),
+                MD_GRID_NULL
+            );
+            md_grid_free(ptr::null_mut());
+        }
+    }
+}
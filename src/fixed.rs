@@ -0,0 +1,118 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{Grid, GridError};
+
+/// A 2D grid whose size is fixed at compile time, stored inline as `H` rows
+/// of `W` cells without any heap allocation. Index it with `[row, col]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstGrid<T, const W: usize, const H: usize> {
+    cells: [[T; W]; H],
+}
+
+impl<T: Clone, const W: usize, const H: usize> ConstGrid<T, W, H> {
+    pub fn new(default_value: T) -> Self {
+        Self {
+            cells: std::array::from_fn(|_| std::array::from_fn(|_| default_value.clone())),
+        }
+    }
+}
+
+impl<T, const W: usize, const H: usize> ConstGrid<T, W, H> {
+    pub const WIDTH: usize = W;
+    pub const HEIGHT: usize = H;
+
+    pub fn from_rows(cells: [[T; W]; H]) -> Self {
+        Self { cells }
+    }
+
+    pub fn into_rows(self) -> [[T; W]; H] {
+        self.cells
+    }
+
+    pub fn rows(&self) -> &[[T; W]; H] {
+        &self.cells
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.cells.get(row)?.get(col)
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.cells.get_mut(row)?.get_mut(col)
+    }
+
+    /// Iterates over all cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter().flatten()
+    }
+
+    /// Iterates mutably over all cells in row-major order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut().flatten()
+    }
+}
+
+impl<T: Clone + Default, const W: usize, const H: usize> Default for ConstGrid<T, W, H> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, const W: usize, const H: usize> Index<[usize; 2]> for ConstGrid<T, W, H> {
+    type Output = T;
+
+    fn index(&self, [row, col]: [usize; 2]) -> &T {
+        &self.cells[row][col]
+    }
+}
+
+impl<T, const W: usize, const H: usize> IndexMut<[usize; 2]> for ConstGrid<T, W, H> {
+    fn index_mut(&mut self, [row, col]: [usize; 2]) -> &mut T {
+        &mut self.cells[row][col]
+    }
+}
+
+impl<T, const W: usize, const H: usize> From<ConstGrid<T, W, H>> for Grid<T> {
+    fn from(fixed: ConstGrid<T, W, H>) -> Self {
+        Grid::from_parts(fixed.cells.into_iter().flatten().collect(), vec![H, W])
+    }
+}
+
+impl<T: Clone, const W: usize, const H: usize> TryFrom<&Grid<T>> for ConstGrid<T, W, H> {
+    type Error = GridError;
+
+    /// Copies a grid of dimensions `[H, W]`.
+    fn try_from(grid: &Grid<T>) -> Result<Self, GridError> {
+        if grid.dimensions != [H, W] {
+            return Err(GridError::ShapeMismatch {
+                expected: vec![H, W],
+                got: grid.dimensions.clone(),
+            });
+        }
+        Ok(Self {
+            cells: std::array::from_fn(|row| {
+                std::array::from_fn(|col| grid.grid[row * W + col].clone())
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_round_trip() {
+        let mut board: ConstGrid<char, 3, 2> = ConstGrid::new('.');
+        board[[1, 2]] = 'x';
+        assert_eq!(board.get(1, 2), Some(&'x'));
+        assert_eq!(board.get(2, 0), None);
+
+        let grid = Grid::from(board);
+        assert_eq!(grid.dimensions(), &[2, 3]);
+        assert_eq!(grid.get_opt(&[1, 2]), Some(&'x'));
+
+        assert_eq!(ConstGrid::<char, 3, 2>::try_from(&grid), Ok(board));
+        assert!(ConstGrid::<char, 2, 3>::try_from(&grid).is_err());
+    }
+}
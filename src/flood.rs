@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+
+use crate::{direction::offset_coords, Connectivity, Coord, GridError, GridLike, Shape};
+
+/// Backs [`GridLike::flood_region`].
+pub(crate) fn flood_region<G, P>(
+    grid: &G,
+    start: &[usize],
+    mut predicate: P,
+) -> Result<Vec<Coord>, GridError>
+where
+    G: GridLike,
+    P: FnMut(&G::Item) -> bool,
+{
+    if !predicate(grid.get(start)?) {
+        return Ok(Vec::new());
+    }
+
+    let dimensions = grid.dimensions();
+    let shape = Shape::from(dimensions);
+    let offsets = Connectivity::Orthogonal.offsets(dimensions.len());
+    let mut seen = vec![false; shape.cell_count()];
+    let mut filled = Vec::new();
+    let mut queue = VecDeque::from([Coord::from_slice(start)]);
+    seen[shape.flat_index(start).unwrap()] = true;
+    while let Some(coords) = queue.pop_front() {
+        for offset in &offsets {
+            let Some(next) = offset_coords(&coords, offset, dimensions) else {
+                continue;
+            };
+            let index = shape.flat_index(&next).unwrap();
+            if !seen[index] && predicate(grid.get_opt(&next).unwrap()) {
+                seen[index] = true;
+                queue.push_back(next);
+            }
+        }
+        filled.push(coords);
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Grid, GridLike, GridLikeMut};
+
+    #[test]
+    fn fills_connected_cells() {
+        let mut map = Grid::new('.', vec![3, 4]);
+        for row in 0..3 {
+            map.set(&[row, 1], '#').unwrap();
+        }
+
+        let filled = map.flood_fill(&[1, 3], |&c| c == '.', '~').unwrap();
+        assert_eq!(filled.len(), 6);
+        assert_eq!(filled[0][..], [1, 3]);
+        assert_eq!(format!("{:#}", map), ".#~~\n.#~~\n.#~~");
+
+        // Filling with a value the predicate still accepts terminates.
+        assert_eq!(
+            map.flood_fill(&[0, 0], |&c| c != '#', '.').unwrap().len(),
+            3
+        );
+        assert!(map.flood_region(&[0, 1], |&c| c == '.').unwrap().is_empty());
+        assert!(map.flood_region(&[3, 0], |_| true).is_err());
+
+        let cube = Grid::new(0, vec![3, 3, 3]);
+        assert_eq!(
+            cube.flood_region(&[1, 1, 1], |&v| v == 0).unwrap().len(),
+            27
+        );
+    }
+}
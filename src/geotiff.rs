@@ -0,0 +1,165 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{Read, Seek},
+};
+
+use tiff::{
+    decoder::{Decoder, DecodingResult},
+    tags::Tag,
+    ColorType, TiffError,
+};
+
+use crate::{GeoTransform, Grid, SpatialGrid};
+
+const GEO_KEY_DIRECTORY: Tag = Tag::Unknown(34735);
+const GT_RASTER_TYPE: u16 = 1025;
+const RASTER_PIXEL_IS_POINT: u16 = 2;
+
+#[derive(Debug)]
+pub enum GeoTiffError {
+    Tiff(TiffError),
+    /// The image has more than one band.
+    NotSingleBand(ColorType),
+    /// The file has neither a model transformation nor a tiepoint and pixel
+    /// scale.
+    MissingGeoreference,
+}
+
+impl fmt::Display for GeoTiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeoTiffError::Tiff(err) => write!(f, "ERROR: {}", err),
+            GeoTiffError::NotSingleBand(color) => {
+                write!(f, "ERROR: Expected a single-band image, got {:?}", color)
+            }
+            GeoTiffError::MissingGeoreference => {
+                write!(f, "ERROR: Image has no GeoTIFF georeferencing tags")
+            }
+        }
+    }
+}
+
+impl Error for GeoTiffError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GeoTiffError::Tiff(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TiffError> for GeoTiffError {
+    fn from(err: TiffError) -> Self {
+        GeoTiffError::Tiff(err)
+    }
+}
+
+fn read_transform<R: Read + Seek>(decoder: &mut Decoder<R>) -> Result<GeoTransform, GeoTiffError> {
+    let matrix = decoder.find_tag(Tag::ModelTransformationTag)?;
+    let mut transform = if let Some(m) = matrix {
+        let m = m.into_f64_vec()?;
+        if m.len() < 8 {
+            return Err(GeoTiffError::MissingGeoreference);
+        }
+        GeoTransform::from_gdal([m[3], m[0], m[1], m[7], m[4], m[5]])
+    } else {
+        let tiepoint = decoder.find_tag(Tag::ModelTiepointTag)?;
+        let scale = decoder.find_tag(Tag::ModelPixelScaleTag)?;
+        let (Some(tiepoint), Some(scale)) = (tiepoint, scale) else {
+            return Err(GeoTiffError::MissingGeoreference);
+        };
+        let (t, s) = (tiepoint.into_f64_vec()?, scale.into_f64_vec()?);
+        if t.len() < 6 || s.len() < 2 {
+            return Err(GeoTiffError::MissingGeoreference);
+        }
+        // Tiepoint (I, J, K, X, Y, Z) pins pixel (I, J) to world (X, Y).
+        GeoTransform::north_up(t[3] - t[0] * s[0], t[4] + t[1] * s[1], s[0], -s[1])
+    };
+
+    // The geotransform describes cell corners; files marked PixelIsPoint
+    // georeference cell centers instead.
+    let keys = decoder.find_tag_unsigned_vec::<u16>(GEO_KEY_DIRECTORY)?;
+    let pixel_is_point = keys.is_some_and(|keys| {
+        keys.get(4..)
+            .unwrap_or_default()
+            .chunks_exact(4)
+            .any(|k| k[0] == GT_RASTER_TYPE && k[1] == 0 && k[3] == RASTER_PIXEL_IS_POINT)
+    });
+    if pixel_is_point {
+        let (x, y) = transform.apply(-0.5, -0.5);
+        transform.origin_x = x;
+        transform.origin_y = y;
+    }
+    Ok(transform)
+}
+
+impl SpatialGrid<f32> {
+    /// Reads the first image of a single-band GeoTIFF, converting samples of
+    /// any type to `f32` and capturing its georeferencing.
+    pub fn read_geotiff<R: Read + Seek>(reader: R) -> Result<Self, GeoTiffError> {
+        let mut decoder = Decoder::new(reader)?;
+        let color = decoder.colortype()?;
+        if !matches!(color, ColorType::Gray(_)) {
+            return Err(GeoTiffError::NotSingleBand(color));
+        }
+        let (width, height) = decoder.dimensions()?;
+        let transform = read_transform(&mut decoder)?;
+
+        let cells: Vec<f32> = match decoder.read_image()? {
+            DecodingResult::U8(v) => v.into_iter().map(|v| v as f32).collect(),
+            DecodingResult::U16(v) => v.into_iter().map(|v| v as f32).collect(),
+            DecodingResult::U32(v) => v.into_iter().map(|v| v as f32).collect(),
+            DecodingResult::U64(v) => v.into_iter().map(|v| v as f32).collect(),
+            DecodingResult::I8(v) => v.into_iter().map(|v| v as f32).collect(),
+            DecodingResult::I16(v) => v.into_iter().map(|v| v as f32).collect(),
+            DecodingResult::I32(v) => v.into_iter().map(|v| v as f32).collect(),
+            DecodingResult::I64(v) => v.into_iter().map(|v| v as f32).collect(),
+            DecodingResult::F16(v) => v.into_iter().map(|v| v.to_f32()).collect(),
+            DecodingResult::F32(v) => v,
+            DecodingResult::F64(v) => v.into_iter().map(|v| v as f32).collect(),
+        };
+
+        let grid = Grid::from_parts(cells, vec![height as usize, width as usize]);
+        Ok(SpatialGrid::new(grid, transform).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use tiff::encoder::{colortype::Gray32Float, TiffEncoder};
+
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn read_elevation() {
+        let mut bytes = Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut bytes).unwrap();
+        let mut image = encoder.new_image::<Gray32Float>(3, 2).unwrap();
+        let tags = image.encoder();
+        tags.write_tag(Tag::ModelPixelScaleTag, &[30.0f64, 30.0, 0.0][..])
+            .unwrap();
+        tags.write_tag(
+            Tag::ModelTiepointTag,
+            &[0.0f64, 0.0, 0.0, 500_000.0, 4_000_000.0, 0.0][..],
+        )
+        .unwrap();
+        image.write_data(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.5]).unwrap();
+
+        bytes.set_position(0);
+        let spatial = SpatialGrid::read_geotiff(bytes).unwrap();
+        assert_eq!(spatial.grid().dimensions(), &[2, 3]);
+        assert_eq!(*spatial.grid().get(&[1, 2]).unwrap(), 6.5);
+        assert_eq!(
+            spatial.transform(),
+            Some(&GeoTransform::north_up(500_000.0, 4_000_000.0, 30.0, -30.0))
+        );
+        assert_eq!(
+            spatial.cell_at(500_075.0, 3_999_955.0),
+            Some(smallvec![1, 2])
+        );
+    }
+}
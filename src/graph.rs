@@ -0,0 +1,64 @@
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::{false_index, Coord, Grid, GridError};
+
+impl<T> Grid<T> {
+    /// Builds a directed graph with one node per cell and an edge to each
+    /// orthogonal neighbor for which `edge(from, to)` returns a weight.
+    ///
+    /// Nodes hold their cell's coordinates and are added in row-major order,
+    /// so a node's index equals the cell's flat index (see
+    /// [`Grid::graph_node`]).
+    pub fn as_graph<W, F>(&self, mut edge: F) -> DiGraph<Coord, W>
+    where
+        F: FnMut(&T, &T) -> Option<W>,
+    {
+        let mut graph = DiGraph::with_capacity(self.grid.len(), self.grid.len() * self.axes * 2);
+        for i in 0..self.grid.len() {
+            graph.add_node(false_index(i, &self.dimensions));
+        }
+
+        for i in 0..self.grid.len() {
+            let coords = &graph[NodeIndex::new(i)];
+            let targets: Vec<_> = self
+                .neighbors_with_direction(coords)
+                .filter_map(|(_, neighbor, to)| {
+                    let weight = edge(&self.grid[i], to)?;
+                    Some((self.translate_index(&neighbor).ok()?, weight))
+                })
+                .collect();
+            for (j, weight) in targets {
+                graph.add_edge(NodeIndex::new(i), NodeIndex::new(j), weight);
+            }
+        }
+
+        graph
+    }
+
+    /// The node in a graph built by [`Grid::as_graph`] for the cell at `target`.
+    pub fn graph_node(&self, target: &[usize]) -> Result<NodeIndex, GridError> {
+        Ok(NodeIndex::new(self.translate_index(target)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::algo::dijkstra;
+
+    #[test]
+    fn as_graph() {
+        let mut grid = Grid::new(1u32, vec![3, 3]);
+        grid.set(&[1, 1], 0).unwrap();
+
+        // Walls (0) are impassable, other cells cost their value to enter.
+        let graph = grid.as_graph(|&from, &to| (from != 0 && to != 0).then_some(to));
+        assert_eq!(graph.node_count(), 9);
+        assert_eq!(graph[grid.graph_node(&[2, 1]).unwrap()][..], [2, 1]);
+
+        let start = grid.graph_node(&[0, 1]).unwrap();
+        let goal = grid.graph_node(&[2, 1]).unwrap();
+        let costs = dijkstra(&graph, start, Some(goal), |e| *e.weight());
+        assert_eq!(costs[&goal], 4);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::{
+    convolve, diffusion, error::check_coords, flood, indexed::CoordCounter, pathfinding,
+    pathfinding::Path, ArcGrid, ChunkedGrid, Coord, CowGrid, DiffusionOptions, Grid, GridError,
+    GridView, GridViewMut, SparseGrid,
+};
+
+/// Read access shared by every kind of grid, so algorithms written once
+/// against it work on dense grids, views and sparse grids alike.
+///
+/// ```
+/// use md_grid::{GridLike, SparseGrid};
+///
+/// let mut walls = SparseGrid::new('.', vec![3, 3]);
+/// walls.set(&[1, 1], '#').unwrap();
+/// let path = walls.astar(&[0, 0], &[2, 2], |&c| c != '#').unwrap();
+/// assert_eq!(path.unwrap().len(), 5);
+/// ```
+pub trait GridLike {
+    type Item;
+
+    fn dimensions(&self) -> &[usize];
+
+    fn get_opt(&self, target: &[usize]) -> Option<&Self::Item>;
+
+    fn get(&self, target: &[usize]) -> Result<&Self::Item, GridError> {
+        check_coords(target, self.dimensions())?;
+        Ok(self
+            .get_opt(target)
+            .expect("checked against the dimensions"))
+    }
+
+    /// Iterates over all cells in row-major order.
+    fn iter(&self) -> impl Iterator<Item = &Self::Item> {
+        let len: usize = self.dimensions().iter().product();
+        let mut counter = CoordCounter::new(self.dimensions());
+        (0..len).map(move |_| {
+            let cell = self.get_opt(counter.coords()).expect("inside the grid");
+            counter.advance();
+            cell
+        })
+    }
+
+    /// A dense copy of the cells.
+    fn to_grid(&self) -> Grid<Self::Item>
+    where
+        Self::Item: Clone,
+        Self: Sized,
+    {
+        Grid::from_vec(self.iter().cloned().collect(), self.dimensions().to_vec())
+            .expect("one value per cell")
+    }
+
+    /// Convolves a 2D grid with a 2D kernel centered on each cell, treating
+    /// cells outside the grid as zero. The output has the same shape as
+    /// `self`.
+    ///
+    /// This is the direct O(n·k) method; for large kernels see
+    /// [`Grid::convolve_fft`] (requires the `rustfft` feature).
+    fn convolve(&self, kernel: &Grid<f64>) -> Result<Grid<f64>, GridError>
+    where
+        Self: GridLike<Item = f64> + Sized,
+    {
+        convolve::convolve(self, kernel)
+    }
+
+    /// Spreads values such as sound or scent outward from `sources`, each a
+    /// cell and the intensity it keeps emitting. Every step, each cell moves
+    /// toward the mean of its orthogonal neighbors, then keeps `decay` of
+    /// the result, less the fraction `absorption` returns for the cell
+    /// (1 for walls that block it entirely).
+    fn diffuse<P, F>(
+        &self,
+        sources: &[(P, f64)],
+        options: &DiffusionOptions,
+        absorption: F,
+    ) -> Result<Grid<f64>, GridError>
+    where
+        P: AsRef<[usize]>,
+        F: FnMut(&Self::Item) -> f64,
+        Self: Sized,
+    {
+        diffusion::diffuse(self, sources, options, absorption)
+    }
+
+    /// The cells reachable from `start` through orthogonal neighbors that
+    /// all satisfy `predicate`, in breadth-first order. Empty if `start`
+    /// itself doesn't satisfy it.
+    fn flood_region<P>(&self, start: &[usize], predicate: P) -> Result<Vec<Coord>, GridError>
+    where
+        P: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        flood::flood_region(self, start, predicate)
+    }
+
+    /// The fewest-steps path from `start` to `goal` through orthogonal
+    /// neighbors that satisfy `passable`, including both ends, or `None` if
+    /// there is none.
+    fn bfs_shortest_path<P>(
+        &self,
+        start: &[usize],
+        goal: &[usize],
+        passable: P,
+    ) -> Result<Option<Path>, GridError>
+    where
+        P: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        pathfinding::bfs_shortest_path(self, start, goal, passable)
+    }
+
+    /// Like [`GridLike::astar_with_cost`] with every step costing 1,
+    /// returning only the path.
+    fn astar<P>(
+        &self,
+        start: &[usize],
+        goal: &[usize],
+        passable: P,
+    ) -> Result<Option<Path>, GridError>
+    where
+        P: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        Ok(self
+            .astar_with_cost(start, goal, passable, |_| 1)?
+            .map(|(path, _)| path))
+    }
+
+    /// The cheapest path from `start` to `goal` through orthogonal neighbors
+    /// that satisfy `passable`, including both ends, with its total cost, or
+    /// `None` if there is none.
+    ///
+    /// Each step costs `cost` of the cell it enters, at least 1, so the
+    /// Manhattan distance stays an admissible heuristic and the path is
+    /// optimal.
+    fn astar_with_cost<P, C>(
+        &self,
+        start: &[usize],
+        goal: &[usize],
+        passable: P,
+        cost: C,
+    ) -> Result<Option<(Path, usize)>, GridError>
+    where
+        P: FnMut(&Self::Item) -> bool,
+        C: FnMut(&Self::Item) -> usize,
+        Self: Sized,
+    {
+        pathfinding::astar_with_cost(self, start, goal, passable, cost)
+    }
+}
+
+/// Write access to the cells of a [`GridLike`].
+pub trait GridLikeMut: GridLike {
+    fn set(&mut self, target: &[usize], val: Self::Item) -> Result<(), GridError>;
+
+    /// Sets every cell of [`GridLike::flood_region`] to `new_value`,
+    /// returning their coordinates.
+    fn flood_fill<P>(
+        &mut self,
+        start: &[usize],
+        predicate: P,
+        new_value: Self::Item,
+    ) -> Result<Vec<Coord>, GridError>
+    where
+        P: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        let filled = self.flood_region(start, predicate)?;
+        for coords in &filled {
+            self.set(coords, new_value.clone())?;
+        }
+        Ok(filled)
+    }
+}
+
+impl<T> GridLike for Grid<T> {
+    type Item = T;
+
+    fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        Grid::get_opt(self, target)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        self.grid.iter()
+    }
+}
+
+impl<T> GridLikeMut for Grid<T> {
+    fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        Grid::set(self, target, val)
+    }
+}
+
+impl<T> GridLike for GridView<'_, T> {
+    type Item = T;
+
+    fn dimensions(&self) -> &[usize] {
+        GridView::dimensions(self)
+    }
+
+    fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        GridView::get_opt(self, target)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        GridView::iter(self)
+    }
+}
+
+impl<T> GridLike for GridViewMut<'_, T> {
+    type Item = T;
+
+    fn dimensions(&self) -> &[usize] {
+        GridViewMut::dimensions(self)
+    }
+
+    fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        GridViewMut::get_opt(self, target)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        GridViewMut::iter(self)
+    }
+}
+
+impl<T> GridLikeMut for GridViewMut<'_, T> {
+    fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        GridViewMut::set(self, target, val)
+    }
+}
+
+impl<T> GridLike for SparseGrid<T> {
+    type Item = T;
+
+    fn dimensions(&self) -> &[usize] {
+        SparseGrid::dimensions(self)
+    }
+
+    fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        SparseGrid::get(self, target).ok()
+    }
+}
+
+impl<T> GridLikeMut for SparseGrid<T> {
+    fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        SparseGrid::set(self, target, val)
+    }
+}
+
+impl<T> GridLike for ArcGrid<T> {
+    type Item = T;
+
+    fn dimensions(&self) -> &[usize] {
+        ArcGrid::dimensions(self)
+    }
+
+    fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        ArcGrid::get_opt(self, target)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        ArcGrid::iter(self)
+    }
+}
+
+impl<T: Clone> GridLikeMut for ArcGrid<T> {
+    fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        ArcGrid::set(self, target, val)
+    }
+}
+
+impl<T> GridLike for CowGrid<'_, T> {
+    type Item = T;
+
+    fn dimensions(&self) -> &[usize] {
+        CowGrid::dimensions(self)
+    }
+
+    fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        CowGrid::get_opt(self, target)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        CowGrid::iter(self)
+    }
+}
+
+impl<T: Clone> GridLikeMut for CowGrid<'_, T> {
+    fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        self.to_mut().set(target, val)
+    }
+}
+
+impl<T> GridLike for ChunkedGrid<T> {
+    type Item = T;
+
+    fn dimensions(&self) -> &[usize] {
+        ChunkedGrid::dimensions(self)
+    }
+
+    fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        ChunkedGrid::get_opt(self, target)
+    }
+}
+
+impl<T: Clone> GridLikeMut for ChunkedGrid<T> {
+    fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        ChunkedGrid::set(self, target, val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Boundary, HeatSolver};
+
+    fn count<G: GridLike<Item = u8>>(grid: &G, value: u8) -> usize {
+        grid.iter().filter(|&&v| v == value).count()
+    }
+
+    #[test]
+    fn backends_share_algorithms() {
+        let mut dense = Grid::new(0u8, vec![4, 4]);
+        let mut sparse = SparseGrid::new(0u8, vec![4, 4]);
+        for row in 0..3 {
+            GridLikeMut::set(&mut dense, &[row, 2], 1).unwrap();
+            GridLikeMut::set(&mut sparse, &[row, 2], 1).unwrap();
+        }
+        assert_eq!(count(&dense, 1), 3);
+        assert_eq!(count(&sparse, 1), 3);
+        assert!(GridLike::iter(&sparse).eq(GridLike::iter(&dense)));
+
+        let dense_path = dense.bfs_shortest_path(&[0, 0], &[0, 3], |&v| v == 0);
+        let sparse_path = sparse.bfs_shortest_path(&[0, 0], &[0, 3], |&v| v == 0);
+        assert_eq!(dense_path, sparse_path);
+        assert_eq!(sparse_path.unwrap().unwrap().len(), 10);
+
+        let view = dense.view(&[0, 0], &[3, 2]).unwrap();
+        assert_eq!(view.flood_region(&[0, 0], |&v| v == 0).unwrap().len(), 6);
+
+        let filled = sparse.flood_fill(&[0, 3], |&v| v == 0, 2).unwrap();
+        assert_eq!(filled.len(), 13);
+        assert_eq!(count(&sparse, 2), 13);
+        assert!(GridLike::get(&sparse, &[4, 0]).is_err());
+    }
+
+    #[test]
+    fn stencils_on_any_backend() {
+        let dense = Grid::from_fn(vec![6, 8], |c| (c[0] * 8 + c[1]) as f64);
+        let mut chunked = ChunkedGrid::new(0.0, vec![6, 8], vec![4, 3]);
+        for (i, &v) in dense.iter().enumerate() {
+            chunked.set(&[i / 8, i % 8], v).unwrap();
+        }
+        let kernel = Grid::from_fn(vec![3, 3], |c| (c[0] + 2 * c[1]) as f64);
+        let expected = dense.convolve(&kernel).unwrap();
+        assert!(chunked
+            .convolve(&kernel)
+            .unwrap()
+            .iter()
+            .eq(expected.iter()));
+
+        let view = dense.view(&[1, 2], &[4, 5]).unwrap();
+        let expected = view.to_grid().convolve(&kernel).unwrap();
+        assert!(view.convolve(&kernel).unwrap().iter().eq(expected.iter()));
+
+        let options = DiffusionOptions::new(0.5, 1.0).max_iterations(3);
+        let sources = [([0, 0], 1.0)];
+        let from_view = view.diffuse(&sources, &options, |_| 0.0).unwrap();
+        let from_dense = view.to_grid().diffuse(&sources, &options, |_| 0.0).unwrap();
+        assert!(from_view.iter().eq(from_dense.iter()));
+
+        let mut heat = HeatSolver::new(&chunked, 1.0, vec![1.0, 1.0], Boundary::Insulated).unwrap();
+        heat.run(5);
+        let total: f64 = heat.field().iter().sum();
+        assert!((total - dense.iter().sum::<f64>()).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{Grid, GridError, GridLike};
+
+/// What lies past the edges of a [`HeatSolver`]'s grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    /// A reservoir held at a fixed temperature.
+    Fixed(f64),
+    /// Nothing: no heat crosses the edge.
+    Insulated,
+    /// The opposite edge, as on a torus.
+    Periodic,
+}
+
+/// Steps the heat equation `du/dt = diffusivity * ∇²u` forward in time with
+/// explicit Euler steps, which are only stable for small enough time steps.
+#[derive(Debug, Clone)]
+pub struct HeatSolver {
+    field: Grid<f64>,
+    diffusivity: f64,
+    spacing: Vec<f64>,
+    boundary: Boundary,
+    dt: f64,
+    elapsed: f64,
+}
+
+impl HeatSolver {
+    /// Starts from a copy of the temperatures in `field`, whose cells are
+    /// `spacing[axis]` apart. The time step defaults to the largest stable
+    /// one. Fails if there isn't one spacing per axis, or if `diffusivity`
+    /// or any spacing is not positive and finite.
+    pub fn new<G>(
+        field: &G,
+        diffusivity: f64,
+        spacing: Vec<f64>,
+        boundary: Boundary,
+    ) -> Result<Self, GridError>
+    where
+        G: GridLike<Item = f64>,
+    {
+        let field = field.to_grid();
+        if spacing.len() != field.axes {
+            return Err(GridError::LengthMismatch {
+                len: spacing.len(),
+                shape: field.dimensions.clone(),
+            });
+        }
+        let positive = |x: f64| x > 0.0 && x.is_finite();
+        for (name, ok) in [
+            ("diffusivity", positive(diffusivity)),
+            ("spacing", spacing.iter().all(|&h| positive(h))),
+        ] {
+            if !ok {
+                return Err(GridError::InvalidParameter {
+                    name,
+                    expected: "positive and finite",
+                    shape: field.dimensions.clone(),
+                });
+            }
+        }
+
+        let mut solver = Self {
+            field,
+            diffusivity,
+            spacing,
+            boundary,
+            dt: 0.0,
+            elapsed: 0.0,
+        };
+        solver.dt = solver.max_stable_time_step();
+        Ok(solver)
+    }
+
+    /// Uses a time step of `dt` instead of the largest stable one. Fails if
+    /// `dt` is not positive or exceeds [`HeatSolver::max_stable_time_step`].
+    pub fn time_step(mut self, dt: f64) -> Result<Self, GridError> {
+        if !(dt > 0.0 && dt <= self.max_stable_time_step()) {
+            return Err(GridError::InvalidParameter {
+                name: "dt",
+                expected: "positive and at most the largest stable time step",
+                shape: self.field.dimensions.clone(),
+            });
+        }
+        self.dt = dt;
+        Ok(self)
+    }
+
+    /// The largest time step for which explicit steps don't blow up:
+    /// `1 / (2 * diffusivity * Σ 1/h²)`.
+    pub fn max_stable_time_step(&self) -> f64 {
+        let inverse: f64 = self.spacing.iter().map(|h| 1.0 / (h * h)).sum();
+        1.0 / (2.0 * self.diffusivity * inverse)
+    }
+
+    pub fn field(&self) -> &Grid<f64> {
+        &self.field
+    }
+
+    pub fn into_field(self) -> Grid<f64> {
+        self.field
+    }
+
+    /// Simulated time so far.
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Advances one time step.
+    pub fn step(&mut self) {
+        let field = &self.field;
+        let mut next = field.clone();
+        for (axis, &h) in self.spacing.iter().enumerate() {
+            let stride: usize = field.dimensions[axis + 1..].iter().product();
+            let len = field.dimensions[axis];
+            let scale = self.diffusivity * self.dt / (h * h);
+            for (i, cell) in next.grid.iter_mut().enumerate() {
+                let c = (i / stride) % len;
+                let v = field.grid[i];
+                let edge = |wrapped: usize| match self.boundary {
+                    Boundary::Fixed(t) => t,
+                    Boundary::Insulated => v,
+                    Boundary::Periodic => field.grid[wrapped],
+                };
+                let lower = match c {
+                    0 => edge(i + (len - 1) * stride),
+                    _ => field.grid[i - stride],
+                };
+                let upper = if c + 1 == len {
+                    edge(i - (len - 1) * stride)
+                } else {
+                    field.grid[i + stride]
+                };
+                *cell += scale * (lower - 2.0 * v + upper);
+            }
+        }
+        self.field = next;
+        self.elapsed += self.dt;
+    }
+
+    /// Advances `steps` time steps.
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundaries() {
+        let mut rod = Grid::new(0.0, vec![5]);
+        rod.set(&[2], 100.0).unwrap();
+
+        let mut insulated = HeatSolver::new(&rod, 1.0, vec![1.0], Boundary::Insulated).unwrap();
+        assert_eq!(insulated.max_stable_time_step(), 0.5);
+        insulated.run(200);
+        let total: f64 = insulated.field().iter().sum();
+        assert!((total - 100.0).abs() < 1e-9);
+        assert!(insulated.field().iter().all(|&t| (t - 20.0).abs() < 1e-3));
+        assert_eq!(insulated.elapsed(), 100.0);
+
+        let mut cooled = HeatSolver::new(&rod, 1.0, vec![1.0], Boundary::Fixed(0.0))
+            .unwrap()
+            .time_step(0.25)
+            .unwrap();
+        cooled.run(400);
+        assert!(cooled.field().iter().all(|&t| t.abs() < 1e-3));
+
+        let mut ring = HeatSolver::new(&rod, 1.0, vec![1.0], Boundary::Periodic).unwrap();
+        ring.step();
+        assert_eq!(ring.field().get_opt(&[1]), Some(&50.0));
+        ring.run(200);
+        let total: f64 = ring.field().iter().sum();
+        assert!((total - 100.0).abs() < 1e-9);
+
+        let plate = Grid::new(0.0, vec![2, 2]);
+        assert!(matches!(
+            HeatSolver::new(&plate, 1.0, vec![1.0], Boundary::Insulated),
+            Err(GridError::LengthMismatch { len: 1, .. })
+        ));
+        assert!(HeatSolver::new(&plate, 0.0, vec![1.0, 1.0], Boundary::Insulated).is_err());
+        assert!(HeatSolver::new(&plate, 1.0, vec![1.0, f64::NAN], Boundary::Insulated).is_err());
+    }
+
+    #[test]
+    fn unstable_time_step() {
+        let plate = Grid::new(0.0, vec![2, 2]);
+        let solver = HeatSolver::new(&plate, 1.0, vec![1.0, 1.0], Boundary::Insulated).unwrap();
+        assert!(matches!(
+            solver.clone().time_step(0.3),
+            Err(GridError::InvalidParameter { name: "dt", .. })
+        ));
+        assert!(solver.time_step(-0.1).is_err());
+    }
+}
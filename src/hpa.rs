@@ -0,0 +1,473 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use crate::{
+    direction::offset_coords, smallvec, Connectivity, Coord, Grid, GridError, Metric, Region,
+};
+
+type ClusterId = (usize, usize);
+
+/// Hierarchical pathfinding (HPA*) over a 2D grid split into square
+/// clusters.
+///
+/// Entrances between neighboring clusters and the distances between
+/// entrances inside each cluster are precomputed into an abstract graph, so
+/// long queries only search that graph and refine the result cluster by
+/// cluster. Paths use orthogonal unit-cost steps and are near-optimal rather
+/// than guaranteed shortest. [`HierarchicalPathfinder::set_passable`] only
+/// recomputes the clusters around the changed cell.
+#[derive(Debug, Clone)]
+pub struct HierarchicalPathfinder {
+    passable: Grid<bool>,
+    cluster_size: usize,
+    clusters: (usize, usize),
+    transitions: HashMap<(ClusterId, ClusterId), Vec<(Coord, Coord)>>,
+    intra: HashMap<ClusterId, Vec<(Coord, Coord, usize)>>,
+    graph: HashMap<Coord, Vec<(Coord, usize)>>,
+}
+
+impl HierarchicalPathfinder {
+    pub fn new<T, P>(
+        grid: &Grid<T>,
+        cluster_size: usize,
+        mut passable: P,
+    ) -> Result<Self, GridError>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let &[rows, cols] = grid.dimensions() else {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: grid.dimensions().to_vec(),
+            });
+        };
+        let cluster_size = cluster_size.max(1);
+
+        let mut finder = Self {
+            passable: Grid::from_parts(grid.iter().map(&mut passable).collect(), vec![rows, cols]),
+            cluster_size,
+            clusters: (rows.div_ceil(cluster_size), cols.div_ceil(cluster_size)),
+            transitions: HashMap::new(),
+            intra: HashMap::new(),
+            graph: HashMap::new(),
+        };
+
+        for row in 0..finder.clusters.0 {
+            for col in 0..finder.clusters.1 {
+                finder.update_transitions((row, col), (row, col + 1));
+                finder.update_transitions((row, col), (row + 1, col));
+            }
+        }
+        for row in 0..finder.clusters.0 {
+            for col in 0..finder.clusters.1 {
+                finder.update_intra((row, col));
+            }
+        }
+        finder.rebuild_graph();
+
+        Ok(finder)
+    }
+
+    pub fn is_passable(&self, target: &[usize]) -> Result<bool, GridError> {
+        Ok(self.passable.grid[self.passable.translate_index(target)?])
+    }
+
+    /// Changes the passability of one cell, recomputing only the entrances
+    /// and distances of its cluster and the clusters bordering it.
+    pub fn set_passable(&mut self, target: &[usize], passable: bool) -> Result<(), GridError> {
+        let index = self.passable.translate_index(target)?;
+        if self.passable.grid[index] == passable {
+            return Ok(());
+        }
+        self.passable.grid[index] = passable;
+
+        let (row, col) = self.cluster_of(target);
+        let mut borders = vec![((row, col), (row, col + 1)), ((row, col), (row + 1, col))];
+        let mut clusters = vec![(row, col), (row + 1, col), (row, col + 1)];
+        if row > 0 {
+            borders.push(((row - 1, col), (row, col)));
+            clusters.push((row - 1, col));
+        }
+        if col > 0 {
+            borders.push(((row, col - 1), (row, col)));
+            clusters.push((row, col - 1));
+        }
+
+        let stale = self.edges(&borders, &clusters);
+        self.unlink(&stale);
+        for &(a, b) in &borders {
+            self.update_transitions(a, b);
+        }
+        for &cluster in &clusters {
+            self.update_intra(cluster);
+        }
+        let fresh = self.edges(&borders, &clusters);
+        self.link(&fresh);
+
+        Ok(())
+    }
+
+    /// Finds a path from `start` to `goal`, including both ends, or `None`
+    /// if the goal can't be reached.
+    pub fn find_path(
+        &self,
+        start: &[usize],
+        goal: &[usize],
+    ) -> Result<Option<Vec<Coord>>, GridError> {
+        if !self.is_passable(start)? || !self.is_passable(goal)? {
+            return Ok(None);
+        }
+        if start == goal {
+            return Ok(Some(vec![Coord::from_slice(start)]));
+        }
+
+        let start_cluster = self.cluster_of(start);
+        let goal_cluster = self.cluster_of(goal);
+        let start_search = self.bfs(start, &self.cluster_region(start_cluster));
+        let goal_search = self.bfs(goal, &self.cluster_region(goal_cluster));
+
+        // A path that never leaves the shared cluster, if there is one.
+        let mut best: Option<(usize, Vec<Coord>)> = None;
+        if start_cluster == goal_cluster {
+            if let Some(&(cost, _)) = start_search.get(goal) {
+                best = Some((
+                    cost,
+                    vec![Coord::from_slice(start), Coord::from_slice(goal)],
+                ));
+            }
+        }
+
+        let heuristic = |coords: &[usize]| Metric::Manhattan.distance(coords, goal) as usize;
+        let mut costs: HashMap<Coord, usize> = HashMap::new();
+        let mut previous: HashMap<Coord, Option<Coord>> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        for entrance in self.entrances(start_cluster) {
+            if let Some(&(cost, _)) = start_search.get(&entrance) {
+                costs.insert(entrance.clone(), cost);
+                previous.insert(entrance.clone(), None);
+                open.push(Reverse((cost + heuristic(&entrance), cost, entrance)));
+            }
+        }
+
+        let mut best_exit: Option<Coord> = None;
+        while let Some(Reverse((estimate, cost, node))) = open.pop() {
+            if best
+                .as_ref()
+                .is_some_and(|(best_cost, _)| estimate >= *best_cost)
+            {
+                break;
+            }
+            if costs.get(&node).is_some_and(|&c| c < cost) {
+                continue;
+            }
+
+            if self.cluster_of(&node) == goal_cluster {
+                if let Some(&(to_goal, _)) = goal_search.get(&node) {
+                    if best.as_ref().is_none_or(|(b, _)| cost + to_goal < *b) {
+                        best = Some((cost + to_goal, Vec::new()));
+                        best_exit = Some(node.clone());
+                    }
+                }
+            }
+
+            for (next, step) in self.graph.get(&node).into_iter().flatten() {
+                let next_cost = cost + step;
+                if costs.get(next).is_none_or(|&c| next_cost < c) {
+                    costs.insert(next.clone(), next_cost);
+                    previous.insert(next.clone(), Some(node.clone()));
+                    open.push(Reverse((
+                        next_cost + heuristic(next),
+                        next_cost,
+                        next.clone(),
+                    )));
+                }
+            }
+        }
+
+        let Some((_, mut waypoints)) = best else {
+            return Ok(None);
+        };
+        if let Some(exit) = best_exit {
+            let mut chain = vec![Coord::from_slice(goal)];
+            let mut node = Some(exit);
+            while let Some(n) = node {
+                node = previous[&n].clone();
+                chain.push(n);
+            }
+            chain.push(Coord::from_slice(start));
+            chain.reverse();
+            waypoints = chain;
+        }
+
+        Ok(Some(self.refine(&waypoints)))
+    }
+
+    /// Expands abstract waypoints into a cell-by-cell path.
+    fn refine(&self, waypoints: &[Coord]) -> Vec<Coord> {
+        let mut path = vec![waypoints[0].clone()];
+        for pair in waypoints.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            let cluster = self.cluster_of(from);
+            if cluster != self.cluster_of(to) {
+                path.push(to.clone());
+                continue;
+            }
+
+            let search = self.bfs(from, &self.cluster_region(cluster));
+            let mut segment = Vec::new();
+            let mut node = to.clone();
+            while node != *from {
+                let prev = search[&node].1.clone();
+                segment.push(node);
+                node = prev;
+            }
+            path.extend(segment.into_iter().rev());
+        }
+        path
+    }
+
+    fn cluster_of(&self, coords: &[usize]) -> ClusterId {
+        (coords[0] / self.cluster_size, coords[1] / self.cluster_size)
+    }
+
+    fn cluster_region(&self, (row, col): ClusterId) -> Region {
+        let size = self.cluster_size;
+        Region::new(vec![
+            row * size..(row + 1) * size,
+            col * size..(col + 1) * size,
+        ])
+        .clamp_to(&self.passable.dimensions)
+    }
+
+    /// Breadth-first search from `from` without leaving `region`, mapping each
+    /// reached cell to its distance and predecessor.
+    fn bfs(&self, from: &[usize], region: &Region) -> HashMap<Coord, (usize, Coord)> {
+        let offsets = Connectivity::Orthogonal.offsets(2);
+        let mut reached = HashMap::from([(Coord::from_slice(from), (0, Coord::from_slice(from)))]);
+        let mut queue = VecDeque::from([Coord::from_slice(from)]);
+
+        while let Some(cell) = queue.pop_front() {
+            let distance = reached[&cell].0;
+            for offset in &offsets {
+                let Some(next) = offset_coords(&cell, offset, &self.passable.dimensions) else {
+                    continue;
+                };
+                if !region.contains(&next)
+                    || reached.contains_key(&next)
+                    || !self.is_passable(&next).unwrap_or(false)
+                {
+                    continue;
+                }
+                reached.insert(next.clone(), (distance + 1, cell.clone()));
+                queue.push_back(next);
+            }
+        }
+
+        reached
+    }
+
+    /// Recomputes the entrances on the border between cluster `a` and the
+    /// cluster `b` directly right of or below it.
+    fn update_transitions(&mut self, a: ClusterId, b: ClusterId) {
+        if b.0 >= self.clusters.0 || b.1 >= self.clusters.1 {
+            return;
+        }
+
+        let size = self.cluster_size;
+        let (rows, cols) = (self.passable.dimensions[0], self.passable.dimensions[1]);
+        let border: Vec<(Coord, Coord)> = if a.0 == b.0 {
+            let col = b.1 * size;
+            (a.0 * size..((a.0 + 1) * size).min(rows))
+                .map(|row| (smallvec![row, col - 1], smallvec![row, col]))
+                .collect()
+        } else {
+            let row = b.0 * size;
+            (a.1 * size..((a.1 + 1) * size).min(cols))
+                .map(|col| (smallvec![row - 1, col], smallvec![row, col]))
+                .collect()
+        };
+
+        // One transition in the middle of each run of open border cells.
+        let mut transitions = Vec::new();
+        let mut run: Vec<&(Coord, Coord)> = Vec::new();
+        for pair in border.iter().map(Some).chain([None]) {
+            let open = pair.is_some_and(|(x, y)| {
+                self.is_passable(x).unwrap_or(false) && self.is_passable(y).unwrap_or(false)
+            });
+            if open {
+                run.push(pair.unwrap());
+            } else if !run.is_empty() {
+                transitions.push(run[run.len() / 2].clone());
+                run.clear();
+            }
+        }
+
+        self.transitions.insert((a, b), transitions);
+    }
+
+    fn entrances(&self, cluster: ClusterId) -> Vec<Coord> {
+        let (row, col) = cluster;
+        let after = [(cluster, (row, col + 1)), (cluster, (row + 1, col))];
+        let before = [
+            row.checked_sub(1).map(|up| ((up, col), cluster)),
+            col.checked_sub(1).map(|left| ((row, left), cluster)),
+        ];
+        let own = (after.iter())
+            .filter_map(|key| self.transitions.get(key))
+            .flatten()
+            .map(|(x, _)| x.clone());
+        let theirs = (before.iter().flatten())
+            .filter_map(|key| self.transitions.get(key))
+            .flatten()
+            .map(|(_, y)| y.clone());
+        let mut entrances: Vec<Coord> = own.chain(theirs).collect();
+        entrances.sort_unstable();
+        entrances.dedup();
+        entrances
+    }
+
+    fn update_intra(&mut self, cluster: ClusterId) {
+        if cluster.0 >= self.clusters.0 || cluster.1 >= self.clusters.1 {
+            return;
+        }
+
+        let region = self.cluster_region(cluster);
+        let entrances = self.entrances(cluster);
+        let mut edges = Vec::new();
+        for (i, from) in entrances.iter().enumerate() {
+            let search = self.bfs(from, &region);
+            for to in &entrances[i + 1..] {
+                if let Some(&(distance, _)) = search.get(to) {
+                    edges.push((from.clone(), to.clone(), distance));
+                }
+            }
+        }
+        self.intra.insert(cluster, edges);
+    }
+
+    fn rebuild_graph(&mut self) {
+        self.graph.clear();
+        let borders: Vec<_> = self.transitions.keys().copied().collect();
+        let clusters: Vec<_> = self.intra.keys().copied().collect();
+        let edges = self.edges(&borders, &clusters);
+        self.link(&edges);
+    }
+
+    /// The edges of the abstract graph that come from the entrances on
+    /// `borders` and the distances inside `clusters`.
+    fn edges(
+        &self,
+        borders: &[(ClusterId, ClusterId)],
+        clusters: &[ClusterId],
+    ) -> Vec<(Coord, Coord, usize)> {
+        let inter = (borders.iter())
+            .filter_map(|key| self.transitions.get(key))
+            .flatten()
+            .map(|(a, b)| (a.clone(), b.clone(), 1));
+        let intra = (clusters.iter())
+            .filter_map(|cluster| self.intra.get(cluster))
+            .flatten()
+            .cloned();
+        inter.chain(intra).collect()
+    }
+
+    fn link(&mut self, edges: &[(Coord, Coord, usize)]) {
+        for (a, b, cost) in edges {
+            self.graph
+                .entry(a.clone())
+                .or_default()
+                .push((b.clone(), *cost));
+            self.graph
+                .entry(b.clone())
+                .or_default()
+                .push((a.clone(), *cost));
+        }
+        // Keep neighbor order independent of hash map iteration order.
+        for (a, b, _) in edges {
+            for end in [a, b] {
+                if let Some(neighbors) = self.graph.get_mut(end) {
+                    neighbors.sort_unstable();
+                }
+            }
+        }
+    }
+
+    /// Removes one copy of each of `edges` from the abstract graph, which
+    /// must hold them.
+    fn unlink(&mut self, edges: &[(Coord, Coord, usize)]) {
+        for (a, b, cost) in edges {
+            for (from, to) in [(a, b), (b, a)] {
+                let neighbors = self.graph.get_mut(from).expect("edge is in the graph");
+                let at = (neighbors.iter())
+                    .position(|(n, c)| n == to && c == cost)
+                    .expect("edge is in the graph");
+                neighbors.remove(at);
+                if neighbors.is_empty() {
+                    self.graph.remove(from);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    fn assert_valid_path(
+        finder: &HierarchicalPathfinder,
+        path: &[Coord],
+        start: &[usize],
+        goal: &[usize],
+    ) {
+        assert_eq!(path.first().unwrap()[..], *start);
+        assert_eq!(path.last().unwrap()[..], *goal);
+        for step in path.windows(2) {
+            assert_eq!(Metric::Manhattan.distance(&step[0], &step[1]), 1.0);
+            assert!(finder.is_passable(&step[1]).unwrap());
+        }
+    }
+
+    #[test]
+    fn routes_through_gap_and_replans() {
+        // A wall across row 10 with a single gap at column 17.
+        let mut grid = Grid::new('.', vec![20, 20]);
+        for col in 0..20 {
+            if col != 17 {
+                grid.set(&[10, col], '#').unwrap();
+            }
+        }
+
+        let mut finder = HierarchicalPathfinder::new(&grid, 5, |&c| c != '#').unwrap();
+        let path = finder.find_path(&[2, 2], &[18, 3]).unwrap().unwrap();
+        assert_valid_path(&finder, &path, &[2, 2], &[18, 3]);
+        assert!(path.contains(&smallvec![10, 17]));
+        // The shortest route is 46 steps; HPA* may detour slightly.
+        assert!(path.len() - 1 <= 52, "path too long: {}", path.len() - 1);
+
+        finder.set_passable(&[10, 17], false).unwrap();
+        assert_eq!(finder.find_path(&[2, 2], &[18, 3]).unwrap(), None);
+        let rebuilt = HierarchicalPathfinder::new(&finder.passable, 5, |&p| p).unwrap();
+        assert_eq!(finder.graph, rebuilt.graph);
+
+        finder.set_passable(&[10, 4], true).unwrap();
+        let path = finder.find_path(&[2, 2], &[18, 3]).unwrap().unwrap();
+        assert_valid_path(&finder, &path, &[2, 2], &[18, 3]);
+        assert!(path.contains(&smallvec![10, 4]));
+        let rebuilt = HierarchicalPathfinder::new(&finder.passable, 5, |&p| p).unwrap();
+        assert_eq!(finder.graph, rebuilt.graph);
+        assert!(path.len() - 1 <= 23, "path too long: {}", path.len() - 1);
+    }
+
+    #[test]
+    fn same_cluster() {
+        let grid = Grid::new(true, vec![8, 8]);
+        let finder = HierarchicalPathfinder::new(&grid, 4, |&c| c).unwrap();
+        let path = finder.find_path(&[0, 0], &[3, 2]).unwrap().unwrap();
+        assert_valid_path(&finder, &path, &[0, 0], &[3, 2]);
+        assert_eq!(path.len(), 6);
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+use crate::{direction::offset_coords, false_index, Connectivity, Direction8, Grid, GridError};
+
+/// A height ordered with `total_cmp`, so it can key a heap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Height(f64);
+
+impl Eq for Height {}
+
+impl PartialOrd for Height {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Height {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl<T: Copy + Into<f64>> Grid<T> {
+    /// Raises every pit in a heightmap to the level of its lowest outlet, so
+    /// water from any cell can flow downhill or across flats to the grid's
+    /// edge. Uses the priority-flood algorithm, growing inward from the
+    /// edges lowest cell first; `connectivity` decides which cells water
+    /// can flow between.
+    pub fn fill_depressions(&self, connectivity: Connectivity) -> Grid<f64> {
+        let mut filled = Grid::from_parts(
+            self.grid.iter().map(|&v| v.into()).collect(),
+            self.dimensions.clone(),
+        );
+        let mut closed = vec![false; filled.grid.len()];
+        let mut open = BinaryHeap::new();
+
+        for (i, done) in closed.iter_mut().enumerate() {
+            let coords = false_index(i, &self.dimensions);
+            let on_edge = coords
+                .iter()
+                .zip(&self.dimensions)
+                .any(|(&c, &len)| c == 0 || c + 1 == len);
+            if on_edge {
+                *done = true;
+                open.push(Reverse((Height(filled.grid[i]), i)));
+            }
+        }
+
+        let offsets = connectivity.offsets(self.axes);
+        while let Some(Reverse((Height(level), i))) = open.pop() {
+            let coords = false_index(i, &self.dimensions);
+            for offset in &offsets {
+                let Some(n) = offset_coords(&coords, offset, &self.dimensions)
+                    .and_then(|n| filled.checked_index(&n))
+                else {
+                    continue;
+                };
+                if closed[n] {
+                    continue;
+                }
+                closed[n] = true;
+                filled.grid[n] = filled.grid[n].max(level);
+                open.push(Reverse((Height(filled.grid[n]), n)));
+            }
+        }
+        filled
+    }
+
+    /// The D8 flow direction of each cell of a 2D heightmap: toward the
+    /// neighbor with the steepest drop, with diagonal drops divided by √2.
+    /// Cells with no lower neighbor are `None`; run
+    /// [`Grid::fill_depressions`] first so only the grid's outlets are.
+    pub fn flow_direction(&self) -> Result<Grid<Option<Direction8>>, GridError> {
+        if self.axes != 2 {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            });
+        }
+
+        let mut out = Grid::new(None, self.dimensions.clone());
+        for (i, dir) in out.grid.iter_mut().enumerate() {
+            let coords = false_index(i, &self.dimensions);
+            let height: f64 = self.grid[i].into();
+            let mut steepest = 0.0;
+            for d in Direction8::ALL {
+                let Some(n) = offset_coords(&coords, &d.offset(), &self.dimensions) else {
+                    continue;
+                };
+                let neighbor: f64 = self.grid[self.checked_index(&n).unwrap()].into();
+                let distance = if d.is_diagonal() { 2f64.sqrt() } else { 1.0 };
+                let drop = (height - neighbor) / distance;
+                if drop > steepest {
+                    (steepest, *dir) = (drop, Some(d));
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// How many cells of a 2D heightmap drain through each cell, counting
+    /// the cell itself, following [`Grid::flow_direction`]. Rivers are the
+    /// cells with high counts.
+    pub fn flow_accumulation(&self) -> Result<Grid<usize>, GridError> {
+        let directions = self.flow_direction()?;
+        let downstream: Vec<Option<usize>> = (directions.grid.iter().enumerate())
+            .map(|(i, dir)| {
+                let coords = false_index(i, &self.dimensions);
+                let n = offset_coords(&coords, &dir.as_ref()?.offset(), &self.dimensions)?;
+                self.checked_index(&n)
+            })
+            .collect();
+
+        // Visit cells only once everything upstream of them has been added.
+        let mut upstream = vec![0usize; downstream.len()];
+        for &d in downstream.iter().flatten() {
+            upstream[d] += 1;
+        }
+        let mut ready: Vec<usize> = (0..upstream.len()).filter(|&i| upstream[i] == 0).collect();
+        let mut out = Grid::new(1, self.dimensions.clone());
+        while let Some(i) = ready.pop() {
+            if let Some(d) = downstream[i] {
+                out.grid[d] += out.grid[i];
+                upstream[d] -= 1;
+                if upstream[d] == 0 {
+                    ready.push(d);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_pits_to_their_outlet() {
+        #[rustfmt::skip]
+        let heights = [
+            5, 5, 5, 5, 5,
+            5, 1, 2, 5, 5,
+            5, 2, 1, 3, 3,
+            5, 5, 5, 5, 5,
+        ];
+        let mut grid = Grid::new(0u8, vec![4, 5]);
+        for (cell, h) in grid.iter_mut().zip(heights) {
+            *cell = h;
+        }
+
+        let filled = grid.fill_depressions(Connectivity::Orthogonal);
+        let cells: Vec<f64> = filled.iter().copied().collect();
+        assert_eq!(cells[6..9], [3.0, 3.0, 5.0]);
+        assert_eq!(cells[11..15], [3.0, 3.0, 3.0, 3.0]);
+        assert!(filled.iter().zip(grid.iter()).all(|(&f, &h)| f >= h as f64));
+
+        // Diagonal flow finds a lower outlet through the corner.
+        let mut bowl = Grid::new(9.0, vec![3, 3]);
+        bowl.set(&[1, 1], 0.0).unwrap();
+        bowl.set(&[0, 0], 2.0).unwrap();
+        let filled = bowl.fill_depressions(Connectivity::Diagonal);
+        assert_eq!(filled.get_opt(&[1, 1]), Some(&2.0));
+        let filled = bowl.fill_depressions(Connectivity::Orthogonal);
+        assert_eq!(filled.get_opt(&[1, 1]), Some(&9.0));
+    }
+
+    #[test]
+    fn drainage() {
+        // A valley sloping down to the middle of the bottom edge.
+        let mut terrain = Grid::new(0.0, vec![3, 3]);
+        for (i, v) in terrain.iter_mut().enumerate() {
+            let (row, col) = ((i / 3) as f64, (i % 3) as f64);
+            *v = (2.0 - row) * 2.0 + (col - 1.0).abs();
+        }
+
+        let directions = terrain.flow_direction().unwrap();
+        assert_eq!(directions.get_opt(&[0, 1]), Some(&Some(Direction8::South)));
+        assert_eq!(directions.get_opt(&[2, 0]), Some(&Some(Direction8::East)));
+        assert_eq!(directions.get_opt(&[2, 1]), Some(&None));
+
+        let accumulation = terrain.flow_accumulation().unwrap();
+        assert_eq!(accumulation.get_opt(&[2, 1]), Some(&9));
+        assert_eq!(accumulation.get_opt(&[0, 0]), Some(&1));
+        assert!(Grid::new(0.0, vec![2, 2, 2]).flow_direction().is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use core::slice::{Iter, IterMut};
+
+use crate::{smallvec, Coord, Grid};
+
+/// Row-major coordinates that advance one cell at a time, carrying into
+/// earlier axes, so walking a grid never converts flat indices back.
+pub(crate) struct CoordCounter<'a> {
+    coords: Coord,
+    dimensions: &'a [usize],
+}
+
+impl<'a> CoordCounter<'a> {
+    pub(crate) fn new(dimensions: &'a [usize]) -> Self {
+        Self {
+            coords: smallvec![0; dimensions.len()],
+            dimensions,
+        }
+    }
+
+    pub(crate) fn coords(&self) -> &[usize] {
+        &self.coords
+    }
+
+    /// Moves to the next cell. Past the last cell the coordinates wrap to
+    /// all zeros.
+    pub(crate) fn advance(&mut self) {
+        for (c, &len) in self.coords.iter_mut().zip(self.dimensions).rev() {
+            *c += 1;
+            if *c < len {
+                return;
+            }
+            *c = 0;
+        }
+    }
+}
+
+/// Iterator over each cell with its coordinates, in row-major order.
+/// Created by [`Grid::iter_indexed`].
+pub struct IndexedIter<'a, T> {
+    cells: Iter<'a, T>,
+    counter: CoordCounter<'a>,
+}
+
+impl<'a, T> Iterator for IndexedIter<'a, T> {
+    type Item = (Coord, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.cells.next()?;
+        let coords = Coord::from_slice(self.counter.coords());
+        self.counter.advance();
+        Some((coords, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IndexedIter<'_, T> {}
+
+/// Mutable counterpart of [`IndexedIter`]. Created by
+/// [`Grid::iter_mut_indexed`].
+pub struct IndexedIterMut<'a, T> {
+    cells: IterMut<'a, T>,
+    counter: CoordCounter<'a>,
+}
+
+impl<'a, T> Iterator for IndexedIterMut<'a, T> {
+    type Item = (Coord, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.cells.next()?;
+        let coords = Coord::from_slice(self.counter.coords());
+        self.counter.advance();
+        Some((coords, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IndexedIterMut<'_, T> {}
+
+impl<T> Grid<T> {
+    /// Iterates over every cell with its coordinates, in row-major order.
+    pub fn iter_indexed(&self) -> IndexedIter<'_, T> {
+        IndexedIter {
+            cells: self.grid.iter(),
+            counter: CoordCounter::new(&self.dimensions),
+        }
+    }
+
+    /// Iterates mutably over every cell with its coordinates, in row-major
+    /// order.
+    pub fn iter_mut_indexed(&mut self) -> IndexedIterMut<'_, T> {
+        IndexedIterMut {
+            cells: self.grid.iter_mut(),
+            counter: CoordCounter::new(&self.dimensions),
+        }
+    }
+
+    /// Folds over every cell in row-major order, passing its coordinates.
+    /// The coordinates are tracked incrementally and only allocate for
+    /// grids of more than four axes.
+    pub fn fold_indexed<B, F>(&self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, &[usize], &T) -> B,
+    {
+        let mut counter = CoordCounter::new(&self.dimensions);
+        let mut acc = init;
+        for v in &self.grid {
+            acc = f(acc, counter.coords(), v);
+            counter.advance();
+        }
+        acc
+    }
+
+    /// Calls `f` with every cell and its coordinates in row-major order.
+    pub fn for_each_indexed<F>(&self, mut f: F)
+    where
+        F: FnMut(&[usize], &T),
+    {
+        self.fold_indexed((), |(), coords, v| f(coords, v));
+    }
+
+    /// Mutable counterpart of [`Grid::for_each_indexed`].
+    pub fn for_each_indexed_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[usize], &mut T),
+    {
+        let mut counter = CoordCounter::new(&self.dimensions);
+        for v in &mut self.grid {
+            f(counter.coords(), v);
+            counter.advance();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexed_passes() {
+        let mut grid = Grid::new(0, vec![2, 3, 2]);
+        grid.for_each_indexed_mut(|coords, v| *v = coords[0] * 100 + coords[1] * 10 + coords[2]);
+        assert_eq!(grid.get_opt(&[1, 2, 1]), Some(&121));
+
+        let mismatches = grid.fold_indexed(0, |n, coords, &v| {
+            n + usize::from(v != coords[0] * 100 + coords[1] * 10 + coords[2])
+        });
+        assert_eq!(mismatches, 0);
+
+        let mut last = Vec::new();
+        grid.for_each_indexed(|coords, _| last = coords.to_vec());
+        assert_eq!(last, vec![1, 2, 1]);
+
+        let mut visited = 0;
+        Grid::new(0, vec![3, 0]).for_each_indexed(|_, _| visited += 1);
+        assert_eq!(visited, 0);
+    }
+
+    #[test]
+    fn lazy_indexed_iterators() {
+        let mut grid = Grid::new(0, vec![2, 3]);
+        for (coords, v) in grid.iter_mut_indexed() {
+            *v = coords[0] * 10 + coords[1];
+        }
+
+        let mut cells = grid.iter_indexed();
+        assert_eq!(cells.len(), 6);
+        let (coords, v) = cells.next().unwrap();
+        assert_eq!((coords[..].to_vec(), *v), (vec![0, 0], 0));
+        let (coords, v) = cells.nth(3).unwrap();
+        assert_eq!((coords[..].to_vec(), *v), (vec![1, 1], 11));
+        assert_eq!(cells.len(), 1);
+    }
+}
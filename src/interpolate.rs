@@ -0,0 +1,147 @@
+use crate::{Grid, GridError};
+
+impl<T: Copy + Into<f64>> Grid<T> {
+    /// The value at a fractional position, linearly interpolated between the
+    /// surrounding cells along every axis (bilinear in 2D, trilinear in 3D).
+    /// Cell `[i, j]` sits at position `[i as f64, j as f64]`.
+    ///
+    /// Returns `None` if the position has the wrong number of axes or lies
+    /// outside the grid's cells.
+    pub fn sample(&self, point: &[f64]) -> Option<f64> {
+        if point.len() != self.axes {
+            return None;
+        }
+
+        // Per axis: the lower cell index and the weight of the upper cell.
+        let mut base = Vec::with_capacity(self.axes);
+        for (&x, &len) in point.iter().zip(&self.dimensions) {
+            if !(0.0..=(len as f64 - 1.0)).contains(&x) {
+                return None;
+            }
+            let lower = (x.floor() as usize).min(len.saturating_sub(2));
+            base.push((lower, x - lower as f64));
+        }
+
+        let mut value = 0.0;
+        for corner in 0..1usize << self.axes {
+            let mut index = 0;
+            let mut weight = 1.0;
+            for (axis, (&(lower, t), &len)) in base.iter().zip(&self.dimensions).enumerate() {
+                let upper = corner >> (self.axes - 1 - axis) & 1 == 1;
+                weight *= if upper { t } else { 1.0 - t };
+                index = index * len + (lower + upper as usize).min(len - 1);
+            }
+            if weight != 0.0 {
+                value += weight * self.grid[index].into();
+            }
+        }
+        Some(value)
+    }
+
+    /// Samples the grid every `spacing` units along a polyline through
+    /// fractional positions, returning `(distance along the path, value)`
+    /// pairs. The last point is always sampled. Values off the grid are NaN.
+    /// Fails if `spacing` is not positive and finite or any point is not
+    /// finite.
+    pub fn sample_path<P: AsRef<[f64]>>(
+        &self,
+        points: &[P],
+        spacing: f64,
+    ) -> Result<Vec<(f64, f64)>, GridError> {
+        if !(spacing > 0.0 && spacing.is_finite()) {
+            return Err(GridError::InvalidParameter {
+                name: "spacing",
+                expected: "positive and finite",
+                shape: self.dimensions.clone(),
+            });
+        }
+        if let Some(p) = points.iter().find(|p| p.as_ref().len() != self.axes) {
+            return Err(GridError::UnsupportedAxes {
+                expected: p.as_ref().len(),
+                shape: self.dimensions.clone(),
+            });
+        }
+        if !points.iter().flat_map(AsRef::as_ref).all(|x| x.is_finite()) {
+            return Err(GridError::InvalidParameter {
+                name: "points",
+                expected: "finite",
+                shape: self.dimensions.clone(),
+            });
+        }
+        let value_at = |point: &[f64]| self.sample(point).unwrap_or(f64::NAN);
+
+        let mut samples = Vec::new();
+        let Some(first) = points.first() else {
+            return Ok(samples);
+        };
+        samples.push((0.0, value_at(first.as_ref())));
+
+        // Distance covered by previous segments, and the next distance to
+        // sample at.
+        let mut travelled = 0.0;
+        let mut next = spacing;
+        let mut point = vec![0.0; self.axes];
+        for segment in points.windows(2) {
+            let (a, b) = (segment[0].as_ref(), segment[1].as_ref());
+            let length = a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| (b - a).powi(2))
+                .sum::<f64>()
+                .sqrt();
+
+            while next < travelled + length {
+                let t = (next - travelled) / length;
+                for (p, (a, b)) in point.iter_mut().zip(a.iter().zip(b)) {
+                    *p = a + (b - a) * t;
+                }
+                samples.push((next, value_at(&point)));
+                next += spacing;
+            }
+            travelled += length;
+        }
+
+        if points.len() > 1 {
+            samples.push((travelled, value_at(points[points.len() - 1].as_ref())));
+        }
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elevation_profile() {
+        let mut terrain = Grid::new(0.0, vec![3, 3]);
+        for (i, v) in terrain.iter_mut().enumerate() {
+            *v = (i % 3) as f64 * 10.0 + (i / 3) as f64;
+        }
+        assert_eq!(terrain.sample(&[0.5, 1.5]), Some(15.5));
+        assert_eq!(terrain.sample(&[2.0, 2.0]), Some(22.0));
+        assert_eq!(terrain.sample(&[2.5, 0.0]), None);
+
+        let profile = terrain
+            .sample_path(&[[0.0, 0.0], [0.0, 2.0], [1.0, 2.0]], 0.75)
+            .unwrap();
+        let expected = [
+            (0.0, 0.0),
+            (0.75, 7.5),
+            (1.5, 15.0),
+            (2.25, 20.25),
+            (3.0, 21.0),
+        ];
+        assert_eq!(profile.len(), expected.len());
+        for ((d, v), (ed, ev)) in profile.into_iter().zip(expected) {
+            assert!((d - ed).abs() < 1e-9 && (v - ev).abs() < 1e-9);
+        }
+
+        assert!(terrain.sample_path(&[[0.0, 0.0, 0.0]], 1.0).is_err());
+        assert!(terrain.sample_path(&[[0.0, 0.0]], 0.0).is_err());
+        assert!(terrain.sample_path(&[[0.0, 0.0]], f64::NAN).is_err());
+        assert!(terrain
+            .sample_path(&[[0.0, 0.0], [f64::INFINITY, 1.0]], 1.0)
+            .is_err());
+    }
+}
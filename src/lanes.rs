@@ -0,0 +1,151 @@
+use std::{iter::StepBy, ops::Index, slice::Iter};
+
+use crate::{Grid, GridError};
+
+/// The cells along one axis with every other coordinate fixed: a row or
+/// column in 2D, a pillar in 3D. Created by [`Grid::lanes`].
+#[derive(Debug, Clone, Copy)]
+pub struct Lane<'a, T> {
+    cells: &'a [T],
+    stride: usize,
+    len: usize,
+}
+
+impl<'a, T> Lane<'a, T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        (index < self.len).then(|| &self.cells[index * self.stride])
+    }
+
+    pub fn iter(&self) -> StepBy<Iter<'a, T>> {
+        self.cells.iter().step_by(self.stride)
+    }
+}
+
+impl<T> Index<usize> for Lane<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index {index} out of bounds for lane of length {}",
+                self.len
+            )
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for Lane<'a, T> {
+    type Item = &'a T;
+    type IntoIter = StepBy<Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over every lane along an axis, in row-major order of their
+/// first cells.
+#[derive(Debug, Clone)]
+pub struct Lanes<'a, T> {
+    cells: &'a [T],
+    stride: usize,
+    len: usize,
+    next: usize,
+    count: usize,
+}
+
+impl<'a, T> Iterator for Lanes<'a, T> {
+    type Item = Lane<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.count {
+            return None;
+        }
+        let (outer, inner) = (self.next / self.stride, self.next % self.stride);
+        let start = outer * self.len * self.stride + inner;
+        self.next += 1;
+
+        // Trim the slice to end at the lane's last cell, so `step_by`
+        // stops there.
+        let cells = match self.len {
+            0 => &[],
+            len => &self.cells[start..start + (len - 1) * self.stride + 1],
+        };
+        Some(Lane {
+            cells,
+            stride: self.stride,
+            len: self.len,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Lanes<'_, T> {}
+
+impl<T> Grid<T> {
+    /// Iterates over the lanes along `axis`: for a 2D grid, axis 1 gives
+    /// the rows and axis 0 the columns.
+    pub fn lanes(&self, axis: usize) -> Result<Lanes<'_, T>, GridError> {
+        if axis >= self.axes {
+            return Err(GridError::InvalidAxis {
+                axis,
+                shape: self.dimensions.clone(),
+            });
+        }
+        let count = (self.dimensions.iter().enumerate())
+            .filter(|&(a, _)| a != axis)
+            .map(|(_, &len)| len)
+            .product();
+        Ok(Lanes {
+            cells: &self.grid,
+            stride: self.dimensions[axis + 1..].iter().product::<usize>().max(1),
+            len: self.dimensions[axis],
+            next: 0,
+            count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_columns_and_pillars() {
+        let mut grid = Grid::new(0, vec![2, 3]);
+        for (i, v) in grid.iter_mut().enumerate() {
+            *v = i;
+        }
+
+        let rows: Vec<Vec<usize>> = grid
+            .lanes(1)
+            .unwrap()
+            .map(|lane| lane.iter().copied().collect())
+            .collect();
+        assert_eq!(rows, vec![vec![0, 1, 2], vec![3, 4, 5]]);
+
+        let columns: Vec<_> = grid.lanes(0).unwrap().collect();
+        assert_eq!(columns.len(), 3);
+        assert_eq!((columns[2][0], columns[2][1]), (2, 5));
+        assert_eq!(columns[1].get(2), None);
+
+        let cube = Grid::new(1, vec![2, 3, 4]);
+        let pillars = cube.lanes(0).unwrap();
+        assert_eq!(pillars.len(), 12);
+        assert!(pillars.map(|p| p.into_iter().sum::<i32>()).all(|s| s == 2));
+        assert!(cube.lanes(3).is_err());
+        assert_eq!(Grid::new(0, vec![0, 3]).lanes(0).unwrap().count(), 3);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{Grid, GridError, Region};
+
+/// Largest rectangle of `true` cells in a row-major `rows × cols` mask,
+/// along with its area.
+fn largest_rect_in_mask(mask: &[bool], rows: usize, cols: usize) -> Option<(usize, Region)> {
+    let mut heights = vec![0; cols];
+    let mut best: Option<(usize, Region)> = None;
+    let mut stack: Vec<usize> = Vec::with_capacity(cols + 1);
+
+    for row in 0..rows {
+        for (col, height) in heights.iter_mut().enumerate() {
+            *height = if mask[row * cols + col] {
+                *height + 1
+            } else {
+                0
+            };
+        }
+
+        // Largest rectangle in the histogram ending at this row. A zero-height
+        // sentinel at `cols` flushes the stack.
+        stack.clear();
+        for col in 0..=cols {
+            let height = heights.get(col).copied().unwrap_or(0);
+            while let Some(&top) = stack.last() {
+                if heights[top] <= height {
+                    break;
+                }
+                stack.pop();
+                let left = stack.last().map_or(0, |&l| l + 1);
+                let area = heights[top] * (col - left);
+                if best.as_ref().is_none_or(|(b, _)| area > *b) {
+                    let top_row = row + 1 - heights[top];
+                    best = Some((area, Region::new(vec![top_row..row + 1, left..col])));
+                }
+            }
+            stack.push(col);
+        }
+    }
+
+    best
+}
+
+impl<T> Grid<T> {
+    /// The largest axis-aligned rectangle of cells matching `predicate` in a
+    /// 2D grid, or `None` if no cell matches. Ties go to the rectangle found
+    /// first in row-major order of its bottom row.
+    pub fn largest_rect<P>(&self, mut predicate: P) -> Result<Option<Region>, GridError>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let &[rows, cols] = &self.dimensions[..] else {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            });
+        };
+
+        let mask: Vec<bool> = self.grid.iter().map(&mut predicate).collect();
+        Ok(largest_rect_in_mask(&mask, rows, cols).map(|(_, region)| region))
+    }
+
+    /// The largest axis-aligned box of cells matching `predicate` in a 3D
+    /// grid, or `None` if no cell matches.
+    ///
+    /// Runs the 2D search on the intersection of every contiguous range of
+    /// layers along axis 0, which is exact but costs O(depth² · rows · cols).
+    pub fn largest_box<P>(&self, mut predicate: P) -> Result<Option<Region>, GridError>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let &[depth, rows, cols] = &self.dimensions[..] else {
+            return Err(GridError::UnsupportedAxes {
+                expected: 3,
+                shape: self.dimensions.clone(),
+            });
+        };
+
+        let layer = rows * cols;
+        let mask: Vec<bool> = self.grid.iter().map(&mut predicate).collect();
+        let mut best: Option<(usize, Region)> = None;
+
+        for first in 0..depth {
+            let mut combined = mask[first * layer..(first + 1) * layer].to_vec();
+            for last in first..depth {
+                if last > first {
+                    let next = &mask[last * layer..(last + 1) * layer];
+                    combined.iter_mut().zip(next).for_each(|(c, &n)| *c &= n);
+                }
+                let layers = last + 1 - first;
+
+                // No rectangle on these layers can beat the best box so far.
+                let upper_bound = combined.iter().filter(|&&c| c).count() * layers;
+                if upper_bound == 0 {
+                    break;
+                }
+                if best.as_ref().is_some_and(|(b, _)| upper_bound <= *b) {
+                    continue;
+                }
+
+                if let Some((area, rect)) = largest_rect_in_mask(&combined, rows, cols) {
+                    let volume = area * layers;
+                    if best.as_ref().is_none_or(|(b, _)| volume > *b) {
+                        let ranges = std::iter::once(first..last + 1)
+                            .chain(rect.ranges().iter().cloned())
+                            .collect();
+                        best = Some((volume, Region::new(ranges)));
+                    }
+                }
+            }
+        }
+
+        Ok(best.map(|(_, region)| region))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn largest_rect() {
+        let rows = ["#..#.", "#....", "..#..", "....."];
+        let mut grid = Grid::new('.', vec![4, 5]);
+        for (r, row) in rows.iter().enumerate() {
+            for (c, ch) in row.chars().enumerate() {
+                grid.set(&[r, c], ch).unwrap();
+            }
+        }
+
+        let rect = grid.largest_rect(|&c| c == '.').unwrap().unwrap();
+        assert_eq!(rect, Region::new(vec![1..4, 3..5]));
+
+        assert_eq!(grid.largest_rect(|&c| c == 'x').unwrap(), None);
+        assert!(Grid::new(0, vec![2]).largest_rect(|_| true).is_err());
+    }
+
+    #[test]
+    fn largest_box() {
+        let mut grid = Grid::new(true, vec![3, 4, 4]);
+        grid.set(&[1, 0, 0], false).unwrap();
+        grid.set(&[2, 3, 3], false).unwrap();
+
+        let found = grid.largest_box(|&c| c).unwrap().unwrap();
+        assert_eq!(found.len(), 3 * 3 * 3);
+        assert!(found.iter().all(|c| *grid.get(&c).unwrap()));
+    }
+}
@@ -0,0 +1,147 @@
+use std::mem::size_of;
+
+use crate::{Grid, Region, Shape};
+
+/// Where the cells of a grid or view sit in memory, for handing them to
+/// code that reads the buffer directly, such as FFI or GPU uploads.
+///
+/// The cell at `coords` is `offset + sum(coords[i] * strides[i])` elements
+/// from the start of the grid's buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub dimensions: Vec<usize>,
+    /// Distance between neighboring cells along each axis, in elements.
+    pub strides: Vec<usize>,
+    /// Elements from the start of the buffer to the first cell.
+    pub offset: usize,
+    /// Size of one element in bytes.
+    pub element_size: usize,
+}
+
+impl Layout {
+    /// Whether the cells form one unbroken run in row-major order, so
+    /// `len` elements can be read starting at the first cell.
+    pub fn is_contiguous(&self) -> bool {
+        if self.dimensions.contains(&0) {
+            return true;
+        }
+        let mut expected = 1;
+        for (&d, &s) in self.dimensions.iter().zip(&self.strides).rev() {
+            if d > 1 && s != expected {
+                return false;
+            }
+            expected *= d;
+        }
+        true
+    }
+
+    /// The number of cells.
+    pub fn len(&self) -> usize {
+        self.dimensions.iter().product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// [`Layout::strides`] in bytes.
+    pub fn byte_strides(&self) -> Vec<usize> {
+        self.strides.iter().map(|s| s * self.element_size).collect()
+    }
+}
+
+/// Layout of the part of `grid` a view covers.
+pub(crate) fn view_layout<T>(grid: &Grid<T>, region: &Region, shape: &[usize]) -> Layout {
+    let strides = Shape::from(&grid.dimensions[..]).strides();
+    let offset = region
+        .origin()
+        .iter()
+        .zip(&strides)
+        .map(|(o, s)| o * s)
+        .sum();
+    Layout {
+        dimensions: shape.to_vec(),
+        strides,
+        offset,
+        element_size: size_of::<T>(),
+    }
+}
+
+impl<T> Grid<T> {
+    /// The stride of each axis in elements. Grids are always row-major, so
+    /// the last axis has stride 1.
+    pub fn strides(&self) -> Vec<usize> {
+        self.strides.clone()
+    }
+
+    /// Always true: a grid's cells are one row-major buffer.
+    pub fn is_contiguous(&self) -> bool {
+        true
+    }
+
+    pub fn layout(&self) -> Layout {
+        Layout {
+            dimensions: self.dimensions.clone(),
+            strides: self.strides(),
+            offset: 0,
+            element_size: size_of::<T>(),
+        }
+    }
+
+    /// Pointer to the first cell. It is valid for reads of every cell for as
+    /// long as the grid isn't modified or dropped.
+    pub fn as_ptr(&self) -> *const T {
+        self.grid.as_ptr()
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.grid.as_mut_ptr()
+    }
+
+    /// Size of one cell in bytes.
+    pub fn element_size(&self) -> usize {
+        size_of::<T>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_layouts() {
+        let grid = Grid::from_fn(vec![4, 5, 6], |c| (c[0] * 100 + c[1] * 10 + c[2]) as u32);
+        let layout = grid.layout();
+        assert_eq!(layout.strides, vec![30, 6, 1]);
+        assert_eq!(layout.byte_strides(), vec![120, 24, 4]);
+        assert!(layout.is_contiguous());
+
+        let view = grid.view(&[1, 2, 3], &[2, 2, 3]).unwrap();
+        let layout = view.layout();
+        assert_eq!(layout.offset, 30 + 12 + 3);
+        assert!(!view.is_contiguous());
+        // SAFETY: the offset is inside the grid's buffer.
+        let cell = unsafe { *view.as_ptr().add(layout.strides[0] + 2) };
+        assert_eq!(cell, 225);
+
+        assert!(grid.view(&[1, 0, 0], &[2, 5, 6]).unwrap().is_contiguous());
+        assert!(grid.view(&[1, 3, 0], &[1, 2, 6]).unwrap().is_contiguous());
+        assert!(!grid.view(&[1, 3, 0], &[2, 2, 6]).unwrap().is_contiguous());
+        assert!(grid.view(&[2, 4, 1], &[1, 1, 4]).unwrap().is_contiguous());
+    }
+
+    #[test]
+    fn strides_follow_reshape_and_resize() {
+        let mut grid = Grid::from_fn(vec![4, 6], |c| c[0] * 6 + c[1]);
+        assert_eq!(grid.strides(), vec![6, 1]);
+
+        grid.reshape(vec![2, 3, 4]).unwrap();
+        assert_eq!(grid.strides(), vec![12, 4, 1]);
+        assert_eq!(grid[[1, 2, 3]], 23);
+
+        grid.resize_axis(2, 5, 0).unwrap();
+        assert_eq!(grid.strides(), vec![15, 5, 1]);
+        assert_eq!(grid[[1, 2, 3]], 23);
+        assert_eq!(grid[[1, 2, 4]], 0);
+    }
+}
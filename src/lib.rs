@@ -1,17 +1,174 @@
+//! Every iterator over a grid visits cells in row-major order: the last axis
+//! varies fastest, so a 2D grid yields `[0, 0], [0, 1], ..., [1, 0], ...`.
+//! This order is part of the API contract, and backends without a natural
+//! order (see [`SparseGrid::iter_sorted`]) offer an iterator that matches it.
+//!
+//! Every `unsafe` block states why it is sound in a `SAFETY:` comment, and
+//! `tests/unsafe_paths.rs` exercises each one outside the `wasm` bindings
+//! under Miri.
+
+#![deny(unsafe_op_in_unsafe_fn, clippy::undocumented_unsafe_blocks)]
+
 use core::slice::{Iter, IterMut};
-use std::{error::Error, fmt::Debug};
+use std::fmt::Debug;
+use std::ops::{Index, IndexMut};
+
+pub mod accessor;
+#[cfg(feature = "gif")]
+pub mod animation;
+pub mod blocked;
+pub mod bsp;
+mod caves;
+pub mod chunked;
+pub mod color;
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod compare;
+pub mod container;
+pub mod convert;
+mod convolve;
+pub mod cow;
+pub mod cursor;
+pub mod dataset;
+mod derivative;
+pub mod diffusion;
+pub mod direction;
+pub mod dstar;
+pub mod erosion;
+pub mod error;
+#[cfg(feature = "expr")]
+pub mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fixed;
+mod flood;
+#[cfg(feature = "geotiff")]
+pub mod geotiff;
+#[cfg(feature = "petgraph")]
+mod graph;
+pub mod grid_like;
+pub mod heat;
+pub mod hpa;
+mod hydrology;
+pub mod indexed;
+mod interpolate;
+pub mod lanes;
+mod largest;
+pub mod layout;
+mod light;
+pub mod merkle;
+pub mod nearest;
+pub mod neighbors;
+pub mod nodata;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod partition;
+mod pathfinding;
+pub mod pool;
+pub mod progressive;
+pub mod pyramid;
+pub mod region;
+pub mod resample;
+mod resize;
+mod rng;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod shape;
+pub mod shared;
+mod sorted;
+pub mod sparse;
+pub mod spatial;
+pub mod static_axes;
+pub mod stats;
+pub mod svg;
+pub mod tagged;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+mod text;
+mod transfer;
+mod transpose;
+pub mod validate;
+pub mod view;
+pub mod viewport;
+mod visibility;
+pub mod walk;
+#[cfg(feature = "wasm-bindgen")]
+mod wasm;
+mod wrapping;
+pub mod zip;
+
+pub use accessor::{Accessor, AccessorMut};
+#[cfg(feature = "gif")]
+pub use animation::AnimationRecorder;
+pub use blocked::BlockView;
+pub use bsp::BspDungeon;
+pub use chunked::ChunkedGrid;
+pub use color::Color;
+#[cfg(feature = "arrow")]
+pub use columnar::ColumnarError;
+pub use compare::{DiffRegion, DiffReport, GridComparison, Mismatch};
+pub use container::{ContainerError, GridFormat};
+pub use convert::ConvertError;
+pub use cow::CowGrid;
+pub use cursor::{EdgePolicy, GridCursor};
+pub use dataset::GridSet;
+pub use diffusion::DiffusionOptions;
+pub use direction::{Direction, Direction4, Direction8};
+pub use dstar::DStarLite;
+pub use erosion::HydraulicErosion;
+pub use error::GridError;
+#[cfg(feature = "expr")]
+pub use expr::Expr;
+pub use fixed::ConstGrid;
+#[cfg(feature = "geotiff")]
+pub use geotiff::GeoTiffError;
+pub use grid_like::{GridLike, GridLikeMut};
+pub use heat::{Boundary, HeatSolver};
+pub use hpa::HierarchicalPathfinder;
+pub use indexed::{IndexedIter, IndexedIterMut};
+pub use lanes::{Lane, Lanes};
+pub use layout::Layout;
+pub use merkle::MerkleTree;
+pub use nearest::Metric;
+pub use neighbors::Connectivity;
+pub use nodata::NoDataGrid;
+pub use partition::Partition;
+pub use pool::GridPool;
+pub use pyramid::GridPyramid;
+pub use region::Region;
+pub use resample::{ResampleMethod, Resolution};
+pub use shape::Shape;
+pub use shared::ArcGrid;
+pub use sparse::SparseGrid;
+pub use spatial::{GeoTransform, SpatialGrid};
+pub use static_axes::{Grid2, Grid3, GridN};
+pub use stats::Stats;
+pub use svg::SvgOptions;
+pub use tagged::{RegionChange, TaggedGrid};
+#[cfg(feature = "terminal")]
+pub use terminal::TerminalGraphics;
+pub use validate::{ValidationReport, Validator, Violation};
+pub use view::{GridView, GridViewMut};
+pub use viewport::{GridViewport, ViewportFrame};
+pub use walk::RandomWalk;
+
+pub use smallvec::smallvec;
+
+/// Coordinates of a cell, one index per axis. Up to four axes are stored
+/// inline, so producing coordinates doesn't allocate for most grids; build
+/// them with [`smallvec!`] or `Coord::from_slice`.
+pub type Coord = smallvec::SmallVec<[usize; 4]>;
 
 #[derive(Debug, Clone)]
-pub struct Grid<T: Clone> {
+pub struct Grid<T> {
     grid: Vec<T>,
     axes: usize,
     dimensions: Vec<usize>,
+    strides: Vec<usize>,
 }
 
 impl<T: Clone> Grid<T> {
     pub fn new(default_value: T, dimensions: Vec<usize>) -> Self {
-        let axes = dimensions.len();
-
         let size = dimensions.iter().product();
 
         let mut grid = Vec::with_capacity(size);
@@ -19,74 +176,339 @@ impl<T: Clone> Grid<T> {
             grid.push(default_value.clone());
         }
 
+        Self::from_parts(grid, dimensions)
+    }
+
+    /// Like [`Grid::new`], but `None` instead of panicking or aborting if
+    /// the cells can't be allocated, for hosts that must not go down with
+    /// an oversized request.
+    pub fn try_new(default_value: T, dimensions: Vec<usize>) -> Option<Self> {
+        let size = Shape::from(&dimensions[..]).checked_cell_count()?;
+        let bytes = size.checked_mul(std::mem::size_of::<T>())?;
+        if bytes > isize::MAX as usize {
+            return None;
+        }
+        let mut grid = Vec::new();
+        grid.try_reserve_exact(size).ok()?;
+        grid.resize(size, default_value);
+
+        Some(Self::from_parts(grid, dimensions))
+    }
+
+    /// Sets every cell to `value`.
+    pub fn fill(&mut self, value: T) {
+        self.grid.fill(value);
+    }
+}
+
+impl<T: Default> Grid<T> {
+    /// Builds a grid with every cell set to `T::default()`, which doesn't
+    /// need `T` to be `Clone`.
+    pub fn from_default(dimensions: Vec<usize>) -> Self {
+        Self::from_fn(dimensions, |_| T::default())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Wraps cells already checked to fill `dimensions`, caching the
+    /// strides that index translation uses.
+    pub(crate) fn from_parts(grid: Vec<T>, dimensions: Vec<usize>) -> Self {
+        let strides = Shape::from(&dimensions[..]).strides();
         Self {
             grid,
-            axes,
+            axes: dimensions.len(),
             dimensions,
+            strides,
+        }
+    }
+
+    /// Builds a grid from cells already laid out in row-major order.
+    pub fn from_vec(data: Vec<T>, dimensions: Vec<usize>) -> Result<Self, GridError> {
+        if Shape::from(&dimensions[..]).checked_cell_count() != Some(data.len()) {
+            return Err(GridError::LengthMismatch {
+                len: data.len(),
+                shape: dimensions,
+            });
+        }
+
+        Ok(Self::from_parts(data, dimensions))
+    }
+
+    /// Reinterprets the cells, in row-major order, as a grid of
+    /// `new_dimensions`. Fails with [`GridError::LengthMismatch`] unless it
+    /// has the same number of cells.
+    pub fn reshape(&mut self, new_dimensions: Vec<usize>) -> Result<(), GridError> {
+        if Shape::from(&new_dimensions[..]).checked_cell_count() != Some(self.grid.len()) {
+            return Err(GridError::LengthMismatch {
+                len: self.grid.len(),
+                shape: new_dimensions,
+            });
+        }
+        self.axes = new_dimensions.len();
+        self.strides = Shape::from(&new_dimensions[..]).strides();
+        self.dimensions = new_dimensions;
+        Ok(())
+    }
+
+    /// The cells in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.grid
+    }
+
+    /// The cells in row-major order, for writing.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.grid
+    }
+
+    /// The cells in row-major order, dropping the dimensions. The inverse of
+    /// [`Grid::from_vec`].
+    pub fn into_flat_vec(self) -> Vec<T> {
+        self.grid
+    }
+
+    /// Builds a grid by calling `f` with the coordinates of each cell, in
+    /// row-major order.
+    pub fn from_fn<F>(dimensions: Vec<usize>, mut f: F) -> Self
+    where
+        F: FnMut(&[usize]) -> T,
+    {
+        let size = dimensions.iter().product();
+        let mut grid = Vec::with_capacity(size);
+        let mut counter = indexed::CoordCounter::new(&dimensions);
+        for _ in 0..size {
+            grid.push(f(counter.coords()));
+            counter.advance();
         }
+
+        Self::from_parts(grid, dimensions)
     }
 
-    pub fn get(&self, target: &[usize]) -> Result<&T, Box<dyn Error>> {
+    pub fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    pub fn get(&self, target: &[usize]) -> Result<&T, GridError> {
         let target = self.translate_index(target)?;
-        let val = &self.grid[target];
-        Ok(val)
+        Ok(self.cell(target))
     }
 
-    pub fn get_mut(&mut self, target: &[usize]) -> Result<&mut T, Box<dyn Error>> {
+    pub fn get_mut(&mut self, target: &[usize]) -> Result<&mut T, GridError> {
         let target = self.translate_index(target)?;
-        let val = &mut self.grid[target];
-        Ok(val)
+        Ok(self.cell_mut(target))
     }
 
-    pub fn set(&mut self, target: &[usize], val: T) -> Result<(), Box<dyn Error>> {
+    pub fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
         let target = self.translate_index(target)?;
-        self.grid[target] = val;
+        *self.cell_mut(target) = val;
         Ok(())
     }
 
+    /// Applies `f` to the cell at `target` in place, returning its result.
+    pub fn update<R, F>(&mut self, target: &[usize], f: F) -> Result<R, GridError>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let target = self.translate_index(target)?;
+        Ok(f(&mut self.grid[target]))
+    }
+
+    /// Like [`Grid::get`], but returns `None` for invalid coordinates
+    /// without allocating an error.
+    pub fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        self.checked_index(target).map(|i| self.cell(i))
+    }
+
+    /// Like [`Grid::get_mut`], but returns `None` for invalid coordinates
+    /// without allocating an error.
+    pub fn get_mut_opt(&mut self, target: &[usize]) -> Option<&mut T> {
+        self.checked_index(target).map(|i| self.cell_mut(i))
+    }
+
+    /// The cell at `target` without any bounds checks.
+    ///
+    /// # Safety
+    ///
+    /// `target` must have one coordinate per axis, each less than that
+    /// axis's length. Anything else is undefined behavior.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, target: &[usize]) -> &T {
+        debug_assert!(self.checked_index(target).is_some());
+        let index = unchecked_index(target, &self.strides);
+        // SAFETY: the caller guarantees `target` is inside the grid.
+        unsafe { self.grid.get_unchecked(index) }
+    }
+
+    /// Mutable counterpart of [`Grid::get_unchecked`].
+    ///
+    /// # Safety
+    ///
+    /// As for [`Grid::get_unchecked`].
+    #[inline]
+    pub unsafe fn get_unchecked_mut(&mut self, target: &[usize]) -> &mut T {
+        debug_assert!(self.checked_index(target).is_some());
+        let index = unchecked_index(target, &self.strides);
+        // SAFETY: the caller guarantees `target` is inside the grid.
+        unsafe { self.grid.get_unchecked_mut(index) }
+    }
+
+    /// Iterates over all cells in row-major order.
     pub fn iter(&self) -> GridIter<'_, T> {
         self.into_iter()
     }
 
+    /// Iterates mutably over all cells in row-major order.
     pub fn iter_mut(&mut self) -> GridIterMut<'_, T> {
         self.into_iter()
     }
 
-    fn translate_index(&self, target: &[usize]) -> Result<usize, Box<dyn Error>> {
+    /// Sets every `(coords, value)` pair, or none of them if any coordinates
+    /// are invalid.
+    pub fn set_many<I>(&mut self, cells: I) -> Result<(), GridError>
+    where
+        I: IntoIterator<Item = (Coord, T)>,
+    {
+        let mut validated = Vec::new();
+        for (coords, val) in cells {
+            validated.push((self.translate_index(&coords)?, val));
+        }
+
+        for (index, val) in validated {
+            self.grid[index] = val;
+        }
+        Ok(())
+    }
+
+    /// Sets every cell to the result of calling `f`, in row-major order.
+    pub fn fill_with<F>(&mut self, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        self.grid.fill_with(f);
+    }
+
+    /// Exchanges the cells at `a` and `b` without cloning either. Nothing
+    /// moves if either coordinate is invalid.
+    pub fn swap(&mut self, a: &[usize], b: &[usize]) -> Result<(), GridError> {
+        let a = self.translate_index(a)?;
+        let b = self.translate_index(b)?;
+        self.grid.swap(a, b);
+        Ok(())
+    }
+
+    /// Coordinates of the cell at `index` in the grid's row-major storage,
+    /// or `None` if the index is past the last cell.
+    pub fn flat_to_coords(&self, index: usize) -> Option<Coord> {
+        (index < self.grid.len()).then(|| false_index(index, &self.dimensions))
+    }
+
+    /// Position of the cell at `target` in the grid's row-major storage, for
+    /// exchanging data with flat buffers.
+    pub fn coords_to_flat(&self, target: &[usize]) -> Result<usize, GridError> {
+        self.translate_index(target)
+    }
+
+    /// The cell at a flat index already checked to be in bounds. With the
+    /// `unchecked-indexing` feature this skips the slice's own bounds check.
+    #[inline]
+    fn cell(&self, index: usize) -> &T {
+        debug_assert!(index < self.grid.len());
+        #[cfg(feature = "unchecked-indexing")]
+        // SAFETY: callers only pass indices from `checked_index` or
+        // `translate_index`, which are less than `self.grid.len()`.
+        unsafe {
+            self.grid.get_unchecked(index)
+        }
+        #[cfg(not(feature = "unchecked-indexing"))]
+        &self.grid[index]
+    }
+
+    /// Mutable counterpart of [`Grid::cell`].
+    #[inline]
+    fn cell_mut(&mut self, index: usize) -> &mut T {
+        debug_assert!(index < self.grid.len());
+        #[cfg(feature = "unchecked-indexing")]
+        // SAFETY: as for `cell`.
+        unsafe {
+            self.grid.get_unchecked_mut(index)
+        }
+        #[cfg(not(feature = "unchecked-indexing"))]
+        &mut self.grid[index]
+    }
+
+    fn translate_index(&self, target: &[usize]) -> Result<usize, GridError> {
+        match self.checked_index(target) {
+            Some(index) => Ok(index),
+            None => Err(error::check_coords(target, &self.dimensions).unwrap_err()),
+        }
+    }
+
+    /// Flat index of `target`, without building an error when it's invalid.
+    fn checked_index(&self, target: &[usize]) -> Option<usize> {
         if target.len() != self.axes {
-            return Err(format!(
-                "ERROR: Tried to index with {} dimensions when grid only has {} dimensions",
-                target.len(),
-                &self.axes
-            )
-            .into());
+            return None;
         }
 
         let mut index = 0;
-        for (i, v) in target.iter().enumerate() {
-            let step: usize = v * self.dimensions.iter().skip(i + 1).product::<usize>();
-            index += step;
+        for ((v, len), stride) in target.iter().zip(&self.dimensions).zip(&self.strides) {
+            if v >= len {
+                return None;
+            }
+            index += v * stride;
         }
 
-        if index >= self.grid.len() {
-            return Err(format!(
-                "ERROR: Index ({}) out of bounds ({})",
-                index,
-                self.grid.len()
-            )
-            .into());
+        Some(index)
+    }
+}
+
+/// Implements `Index` and `IndexMut` for a coordinate type, panicking with
+/// the [`GridError`] message when the coordinates are invalid.
+macro_rules! impl_index {
+    ($([$($generics:tt)*] $target:ty => |$c:ident| $slice:expr;)*) => {$(
+        impl<T, $($generics)*> Index<$target> for Grid<T> {
+            type Output = T;
+
+            fn index(&self, $c: $target) -> &T {
+                self.get($slice).unwrap_or_else(|e| panic!("{e}"))
+            }
         }
 
-        Ok(index)
-    }
+        impl<T, $($generics)*> IndexMut<$target> for Grid<T> {
+            fn index_mut(&mut self, $c: $target) -> &mut T {
+                self.get_mut($slice).unwrap_or_else(|e| panic!("{e}"))
+            }
+        }
+    )*};
+}
+
+impl_index! {
+    [] &[usize] => |c| c;
+    [const N: usize] [usize; N] => |c| &c;
+    [] (usize, usize) => |c| &[c.0, c.1];
+    [] (usize, usize, usize) => |c| &[c.0, c.1, c.2];
+}
+
+/// Row-major flat index of `target`, assuming it is inside the grid whose
+/// `strides` these are.
+#[inline]
+fn unchecked_index(target: &[usize], strides: &[usize]) -> usize {
+    target
+        .iter()
+        .zip(strides)
+        .map(|(v, stride)| v * stride)
+        .sum()
 }
 
-// TODO: impl false_index
-fn false_index(index: usize, dimensions: &[usize]) -> Vec<usize> {
-    todo!()
+fn false_index(index: usize, dimensions: &[usize]) -> Coord {
+    let mut coords = smallvec![0; dimensions.len()];
+    let mut remainder = index;
+    for (coord, len) in coords.iter_mut().zip(dimensions).rev() {
+        *coord = remainder % len;
+        remainder /= len;
+    }
+    coords
 }
 
-impl<'a, T: Clone> IntoIterator for &'a Grid<T> {
+impl<'a, T> IntoIterator for &'a Grid<T> {
     type Item = &'a T;
     type IntoIter = GridIter<'a, T>;
 
@@ -95,7 +517,7 @@ impl<'a, T: Clone> IntoIterator for &'a Grid<T> {
     }
 }
 
-impl<'a, T: Clone> IntoIterator for &'a mut Grid<T> {
+impl<'a, T> IntoIterator for &'a mut Grid<T> {
     type Item = &'a mut T;
     type IntoIter = GridIterMut<'a, T>;
 
@@ -104,19 +526,19 @@ impl<'a, T: Clone> IntoIterator for &'a mut Grid<T> {
     }
 }
 
-pub struct GridIter<'a, T: Clone> {
+pub struct GridIter<'a, T> {
     grid: Iter<'a, T>,
     dimensions: &'a [usize],
 }
 
-impl<'a, T: Clone> GridIter<'a, T> {
+impl<'a, T> GridIter<'a, T> {
     fn new(grid: &'a Grid<T>) -> Self {
         let dimensions = &grid.dimensions[..];
         let grid = grid.grid.iter();
         Self { grid, dimensions }
     }
 
-    pub fn position<P>(&mut self, predicate: P) -> Option<Vec<usize>>
+    pub fn position<P>(&mut self, predicate: P) -> Option<Coord>
     where
         P: FnMut(&'a T) -> bool,
     {
@@ -127,19 +549,9 @@ impl<'a, T: Clone> GridIter<'a, T> {
 
         None
     }
-
-    pub fn enumerate<P>(self) -> std::vec::IntoIter<(Vec<usize>, &'a T)> {
-        let dimensions = self.dimensions;
-        let mut res = Vec::with_capacity(self.grid.len());
-        for (i, val) in Iterator::enumerate(self) {
-            let i = false_index(i, dimensions);
-            res.push((i, val));
-        }
-        res.into_iter()
-    }
 }
 
-impl<'a, T: Clone> Iterator for GridIter<'a, T> {
+impl<'a, T> Iterator for GridIter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -147,21 +559,32 @@ impl<'a, T: Clone> Iterator for GridIter<'a, T> {
     }
 }
 
-// TODO: impl position and enumerate for GridIterMut
-pub struct GridIterMut<'a, T: Clone> {
+pub struct GridIterMut<'a, T> {
     grid: IterMut<'a, T>,
     dimensions: &'a [usize],
 }
 
-impl<'a, T: Clone> GridIterMut<'a, T> {
+impl<'a, T> GridIterMut<'a, T> {
     fn new(grid: &'a mut Grid<T>) -> Self {
         let dimensions = &grid.dimensions[..];
         let grid = grid.grid.iter_mut();
         Self { grid, dimensions }
     }
+
+    pub fn position<P>(&mut self, predicate: P) -> Option<Coord>
+    where
+        P: FnMut(&'a mut T) -> bool,
+    {
+        if let Some(index) = Iterator::position(self, predicate) {
+            let index = false_index(index, self.dimensions);
+            return Some(index);
+        }
+
+        None
+    }
 }
 
-impl<'a, T: Clone> Iterator for GridIterMut<'a, T> {
+impl<'a, T> Iterator for GridIterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -172,6 +595,7 @@ impl<'a, T: Clone> Iterator for GridIterMut<'a, T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::smallvec;
 
     #[test]
     fn new_grid() {
@@ -184,6 +608,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_fn() {
+        let distance = Grid::from_fn(vec![3, 4], |c| c[0].abs_diff(1) + c[1].abs_diff(2));
+        assert_eq!(distance.get_opt(&[1, 2]), Some(&0));
+        assert_eq!(distance.get_opt(&[0, 0]), Some(&3));
+        assert_eq!(distance.get_opt(&[2, 3]), Some(&2));
+        assert_eq!(Grid::from_fn(vec![], |c| c.len()).get_opt(&[]), Some(&0));
+    }
+
+    #[test]
+    fn flat_conversions() {
+        let grid = Grid::new(0, vec![2, 3, 4]);
+        assert_eq!(grid.coords_to_flat(&[1, 2, 3]), Ok(23));
+        assert_eq!(grid.flat_to_coords(23).unwrap()[..], [1, 2, 3]);
+        for i in 0..24 {
+            let coords = grid.flat_to_coords(i).unwrap();
+            assert_eq!(grid.coords_to_flat(&coords), Ok(i));
+        }
+        assert_eq!(grid.flat_to_coords(24), None);
+        assert!(grid.coords_to_flat(&[2, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn fill() {
+        let mut field = Grid::new(1.5, vec![3, 4]);
+        field.fill(0.0);
+        assert!(field.iter().all(|&v| v == 0.0));
+
+        field.fill_region(&[1, 1], &[2, 2], 9.0).unwrap();
+        assert_eq!(field.iter().filter(|&&v| v == 9.0).count(), 4);
+        assert_eq!(field[[2, 2]], 9.0);
+        assert!(field.fill_region(&[2, 2], &[2, 2], 5.0).is_err());
+        assert!(!field.iter().any(|&v| v == 5.0));
+
+        let mut next = 0.0;
+        field.fill_with(|| {
+            next += 1.0;
+            next
+        });
+        assert_eq!(field[[2, 3]], 12.0);
+    }
+
+    #[test]
+    fn reshape_and_flatten() {
+        let mut grid = Grid::from_fn(vec![2, 6], |c| c[0] * 6 + c[1]);
+        grid.reshape(vec![3, 2, 2]).unwrap();
+        assert_eq!(grid[[1, 0, 1]], 5);
+        assert!(matches!(
+            grid.reshape(vec![5, 2]),
+            Err(GridError::LengthMismatch { len: 12, .. })
+        ));
+        assert_eq!(grid.dimensions(), &[3, 2, 2]);
+
+        grid.as_mut_slice().reverse();
+        assert_eq!(grid.as_slice()[0], 11);
+        let flat = grid.into_flat_vec();
+        assert_eq!(Grid::from_vec(flat, vec![12]).unwrap()[[11]], 0);
+    }
+
+    #[test]
+    fn from_vec() {
+        let grid = Grid::from_vec((0..6).collect(), vec![2, 3]).unwrap();
+        assert_eq!(grid.get_opt(&[1, 0]), Some(&3));
+        assert_eq!(
+            Grid::from_vec(vec![0; 5], vec![2, 3]).unwrap_err(),
+            GridError::LengthMismatch {
+                len: 5,
+                shape: vec![2, 3]
+            }
+        );
+        assert!(Grid::from_vec(Vec::<u8>::new(), vec![usize::MAX, 2]).is_err());
+    }
+
+    #[test]
+    fn non_clone_cells() {
+        use std::sync::Mutex;
+
+        let mut locks: Grid<Mutex<u32>> = Grid::from_default(vec![2, 2]);
+        *locks[[1, 0]].lock().unwrap() += 3;
+        locks.set(&[0, 1], Mutex::new(5)).unwrap();
+        let total: u32 = locks.iter().map(|m| *m.lock().unwrap()).sum();
+        assert_eq!(total, 8);
+        locks.swap(&[1, 0], &[0, 0]).unwrap();
+        assert_eq!(*locks[[0, 0]].lock().unwrap(), 3);
+        assert!(locks.swap(&[0, 0], &[2, 0]).is_err());
+        assert_eq!(*locks[[0, 0]].lock().unwrap(), 3);
+
+        let scalers = Grid::from_fn(vec![3], |c| {
+            let k = c[0];
+            Box::new(move |x| x * k) as Box<dyn Fn(usize) -> usize>
+        });
+        assert_eq!(scalers[[2]](10), 20);
+
+        // Mapping, zipping and wrapping only need to clone what they copy.
+        let mapped = locks.map(|m| Mutex::new(*m.lock().unwrap() * 2));
+        let summed = mapped
+            .zip_with(&locks, |a, b| {
+                Mutex::new(*a.lock().unwrap() + *b.lock().unwrap())
+            })
+            .unwrap();
+        assert_eq!(*summed[[0, 0]].lock().unwrap(), 9);
+        let shared = ArcGrid::from(summed);
+        assert_eq!(shared.dimensions(), &[2, 2]);
+        assert_eq!(*shared.get(&[0, 1]).unwrap().lock().unwrap(), 15);
+        let masked = NoDataGrid::new(mapped).map(|m| Some(*m.lock().unwrap()));
+        assert_eq!(masked.get(&[0, 0]).unwrap(), Some(&Some(6)));
+        assert!(HierarchicalPathfinder::new(&locks, 2, |_| true).is_ok());
+        assert!(DStarLite::new(&locks, &[0, 0], &[1, 1], |_| 1.0).is_ok());
+    }
+
     #[test]
     fn translate_index() {
         // 2d grid (10x10)
@@ -234,6 +768,92 @@ mod tests {
         assert_eq!(grid.grid[23], 0);
     }
 
+    #[test]
+    fn typed_errors() {
+        let mut grid = Grid::new(0, vec![2, 3]);
+
+        assert!(matches!(
+            grid.get(&[1, 3]),
+            Err(GridError::OutOfBounds { axis: 1, .. })
+        ));
+        assert!(matches!(
+            grid.set(&[1], 4),
+            Err(GridError::DimensionMismatch { .. })
+        ));
+        assert!(matches!(
+            grid.get_mut(&[2, 0]),
+            Err(GridError::OutOfBounds { axis: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn index_operators() {
+        let mut grid = Grid::new(0, vec![2, 3]);
+        grid[(1, 2)] = 5;
+        grid[[0, 1]] += 2;
+        assert_eq!(grid[&[1, 2][..]], 5);
+        assert_eq!(grid[[0, 1]], 2);
+
+        let mut cube = Grid::new('.', vec![2, 2, 2]);
+        cube[(1, 0, 1)] = '#';
+        assert_eq!(cube.get_opt(&[1, 0, 1]), Some(&'#'));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERROR: ")]
+    fn index_out_of_bounds() {
+        let grid = Grid::new(0, vec![2, 3]);
+        let _ = grid[(2, 0)];
+    }
+
+    #[test]
+    fn get_opt() {
+        let mut grid = Grid::new(0, vec![2, 3]);
+        *grid.get_mut_opt(&[1, 2]).unwrap() = 5;
+
+        assert_eq!(grid.get_opt(&[1, 2]), Some(&5));
+        assert_eq!(grid.get_opt(&[2, 0]), None);
+        assert_eq!(grid.get_opt(&[0, 3]), None);
+        assert_eq!(grid.get_opt(&[0]), None);
+        assert!(grid.get_mut_opt(&[0, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn update() {
+        let mut grid = Grid::new(0, vec![2, 2]);
+        for _ in 0..3 {
+            grid.update(&[1, 0], |count| *count += 1).unwrap();
+        }
+        let previous = grid
+            .update(&[1, 0], |count| std::mem::replace(count, 10))
+            .unwrap();
+
+        assert_eq!(previous, 3);
+        assert_eq!(grid.get_opt(&[1, 0]), Some(&10));
+        assert!(grid.update(&[2, 0], |count| *count += 1).is_err());
+    }
+
+    #[test]
+    fn set_many() {
+        let mut grid = Grid::new(0, vec![3, 3]);
+
+        grid.set_many(vec![(smallvec![0, 1], 4), (smallvec![2, 2], 7)])
+            .unwrap();
+        assert_eq!(grid.grid[1], 4);
+        assert_eq!(grid.grid[8], 7);
+
+        let err = grid.set_many(vec![(smallvec![1, 1], 5), (smallvec![0, 3], 6)]);
+        assert_eq!(
+            err,
+            Err(GridError::OutOfBounds {
+                coords: smallvec![0, 3],
+                axis: 1,
+                shape: vec![3, 3]
+            })
+        );
+        assert_eq!(grid.grid[4], 0);
+    }
+
     #[test]
     fn into_iterator() {
         let mut grid = Grid::new(0, vec![10, 10]);
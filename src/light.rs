@@ -0,0 +1,84 @@
+use crate::{direction::offset_coords, false_index, Connectivity, Grid, GridError};
+
+impl<T> Grid<T> {
+    /// Spreads light from `sources`, each a cell and the level it emits, the
+    /// way block games light their worlds. Each orthogonal step loses
+    /// `falloff` levels plus the `opacity` of the cell entered, so opaque
+    /// cells (opacity 255) stay dark. Every cell ends with the brightest
+    /// level any source reaches it with.
+    pub fn propagate_light<P, F>(
+        &self,
+        sources: &[(P, u8)],
+        falloff: u8,
+        mut opacity: F,
+    ) -> Result<Grid<u8>, GridError>
+    where
+        P: AsRef<[usize]>,
+        F: FnMut(&T) -> u8,
+    {
+        let mut light = Grid::new(0u8, self.dimensions.clone());
+        // Cells waiting to spread their light, bucketed by level so every
+        // cell spreads once, at its final level.
+        let mut buckets = vec![Vec::new(); 256];
+        for (coords, level) in sources {
+            let i = self.translate_index(coords.as_ref())?;
+            if *level > light.grid[i] {
+                light.grid[i] = *level;
+                buckets[*level as usize].push(i);
+            }
+        }
+
+        let offsets = Connectivity::Orthogonal.offsets(self.axes);
+        for level in (1..256).rev() {
+            while let Some(i) = buckets[level].pop() {
+                if light.grid[i] as usize != level {
+                    continue;
+                }
+                let coords = false_index(i, &self.dimensions);
+                for offset in &offsets {
+                    let Some(n) = offset_coords(&coords, offset, &self.dimensions) else {
+                        continue;
+                    };
+                    let n = self.checked_index(&n).unwrap();
+                    let reached = (level as u8)
+                        .saturating_sub(falloff)
+                        .saturating_sub(opacity(&self.grid[n]));
+                    if reached > light.grid[n] {
+                        light.grid[n] = reached;
+                        buckets[reached as usize].push(n);
+                    }
+                }
+            }
+        }
+        Ok(light)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn torch_behind_a_wall() {
+        let mut world = Grid::new('.', vec![3, 7]);
+        for row in 0..2 {
+            world.set(&[row, 3], '#').unwrap();
+        }
+        world.set(&[2, 3], 'g').unwrap();
+
+        let opacity = |&c: &char| match c {
+            '#' => 255,
+            'g' => 2,
+            _ => 0,
+        };
+        let light = world.propagate_light(&[([0, 0], 15)], 1, opacity).unwrap();
+        assert_eq!(light.get_opt(&[0, 0]), Some(&15));
+        assert_eq!(light.get_opt(&[0, 2]), Some(&13));
+        assert_eq!(light.get_opt(&[0, 3]), Some(&0));
+        // Around the wall through the glass: 15 - 4 to reach it, then 3 more.
+        assert_eq!(light.get_opt(&[2, 3]), Some(&8));
+        assert_eq!(light.get_opt(&[0, 4]), Some(&5));
+
+        assert!(world.propagate_light(&[([3, 0], 15)], 1, opacity).is_err());
+    }
+}
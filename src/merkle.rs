@@ -0,0 +1,268 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{Coord, Grid, GridError, Region, Shape};
+
+/// 64-bit FNV-1a, used instead of `DefaultHasher` because its output must
+/// stay the same across Rust releases for hashes to be stored. Integers are
+/// fed as little-endian bytes, with `usize` and `isize` widened to 64 bits,
+/// so every platform computes the same hashes.
+struct Fnv64(u64);
+
+impl Fnv64 {
+    fn new() -> Self {
+        Fnv64(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv64 {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+fn hash_pair(left: u64, right: u64) -> u64 {
+    let mut hasher = Fnv64::new();
+    hasher.write_u64(left);
+    hasher.write_u64(right);
+    hasher.finish()
+}
+
+/// A hash tree over fixed-size chunks of a grid, for finding which chunks
+/// differ between two grids without comparing every cell.
+///
+/// Leaves hash the cells of one chunk each, in row-major chunk order; every
+/// level above hashes adjacent pairs of the level below. Hashes are stable
+/// across runs, platforms and Rust versions as long as `T`'s [`Hash`] impl
+/// is, which holds for the primitive types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    dimensions: Vec<usize>,
+    chunk_shape: Vec<usize>,
+    chunk_counts: Vec<usize>,
+    levels: Vec<Vec<u64>>,
+}
+
+impl MerkleTree {
+    pub fn root(&self) -> u64 {
+        self.levels
+            .last()
+            .and_then(|l| l.first())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn chunk_shape(&self) -> &[usize] {
+        &self.chunk_shape
+    }
+
+    /// Number of chunks along each axis.
+    pub fn chunk_counts(&self) -> &[usize] {
+        &self.chunk_counts
+    }
+
+    /// The cells covered by the chunk with the given row-major index.
+    pub fn chunk_region(&self, chunk: usize) -> Region {
+        let mut remainder = chunk;
+        let mut ranges = vec![0..0; self.chunk_counts.len()];
+        for axis in (0..ranges.len()).rev() {
+            let c = remainder % self.chunk_counts[axis];
+            remainder /= self.chunk_counts[axis];
+            let start = c * self.chunk_shape[axis];
+            ranges[axis] = start..(start + self.chunk_shape[axis]).min(self.dimensions[axis]);
+        }
+        Region::new(ranges)
+    }
+
+    /// Regions of the chunks whose contents differ from `other`'s, visiting
+    /// only the branches of the tree whose hashes differ.
+    pub fn diff(&self, other: &MerkleTree) -> Result<Vec<Region>, GridError> {
+        Shape::from(&self.dimensions[..]).check_eq(&other.dimensions)?;
+        Shape::from(&self.chunk_shape[..]).check_eq(&other.chunk_shape)?;
+
+        let mut changed = Vec::new();
+        let mut pending = vec![(self.levels.len() - 1, 0)];
+        if self.levels[0].is_empty() {
+            pending.clear();
+        }
+        while let Some((level, node)) = pending.pop() {
+            if self.levels[level][node] == other.levels[level][node] {
+                continue;
+            }
+            if level == 0 {
+                changed.push(node);
+                continue;
+            }
+            for child in [2 * node, 2 * node + 1] {
+                if child < self.levels[level - 1].len() {
+                    pending.push((level - 1, child));
+                }
+            }
+        }
+
+        changed.sort_unstable();
+        Ok(changed.into_iter().map(|c| self.chunk_region(c)).collect())
+    }
+
+    /// Rehashes the chunk containing `target` and its ancestors after that
+    /// cell of `grid` changed.
+    pub fn update<T: Hash>(&mut self, grid: &Grid<T>, target: &[usize]) -> Result<(), GridError> {
+        Shape::from(&self.dimensions[..]).check_eq(grid.dimensions())?;
+        grid.translate_index(target)?;
+
+        let mut node = target
+            .iter()
+            .zip(&self.chunk_shape)
+            .zip(&self.chunk_counts)
+            .fold(0, |acc, ((&c, &size), &count)| acc * count + c / size);
+        self.levels[0][node] = hash_chunk(grid, &self.chunk_region(node));
+        for level in 1..self.levels.len() {
+            node /= 2;
+            self.levels[level][node] = self.parent_hash(level - 1, node);
+        }
+        Ok(())
+    }
+
+    fn parent_hash(&self, child_level: usize, node: usize) -> u64 {
+        let children = &self.levels[child_level];
+        match children.get(2 * node + 1) {
+            Some(&right) => hash_pair(children[2 * node], right),
+            None => children[2 * node],
+        }
+    }
+}
+
+fn hash_chunk<T: Hash>(grid: &Grid<T>, region: &Region) -> u64 {
+    let mut hasher = Fnv64::new();
+    let shape = region.shape();
+    hasher.write_u64(shape.len() as u64);
+    for len in shape {
+        hasher.write_u64(len as u64);
+    }
+    for coords in region {
+        grid.grid[grid.translate_index(&coords).unwrap()].hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+impl<T: Hash> Grid<T> {
+    /// Builds a [`MerkleTree`] over chunks of `chunk_shape` cells (smaller at
+    /// the far edges if the shape doesn't divide the grid).
+    pub fn merkle_tree(&self, chunk_shape: &[usize]) -> Result<MerkleTree, GridError> {
+        if chunk_shape.len() != self.axes {
+            return Err(GridError::DimensionMismatch {
+                coords: Coord::from_slice(chunk_shape),
+                shape: self.dimensions.clone(),
+            });
+        }
+
+        let chunk_shape: Vec<usize> = chunk_shape.iter().map(|&s| s.max(1)).collect();
+        let chunk_counts: Vec<usize> = self
+            .dimensions
+            .iter()
+            .zip(&chunk_shape)
+            .map(|(d, s)| d.div_ceil(*s))
+            .collect();
+
+        let mut tree = MerkleTree {
+            dimensions: self.dimensions.clone(),
+            chunk_shape,
+            chunk_counts,
+            levels: Vec::new(),
+        };
+        let chunks: usize = tree.chunk_counts.iter().product();
+        tree.levels.push(
+            (0..chunks)
+                .map(|c| hash_chunk(self, &tree.chunk_region(c)))
+                .collect(),
+        );
+        while tree.levels.last().unwrap().len() > 1 {
+            let level = tree.levels.len() - 1;
+            let parents = tree.levels[level].len().div_ceil(2);
+            let hashes = (0..parents).map(|p| tree.parent_hash(level, p)).collect();
+            tree.levels.push(hashes);
+        }
+
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_finds_changed_chunks() {
+        let mut a = Grid::new(0u32, vec![10, 10]);
+        let b = a.clone();
+        let tree_b = b.merkle_tree(&[4, 4]).unwrap();
+        let mut tree_a = a.merkle_tree(&[4, 4]).unwrap();
+        assert_eq!(tree_a.chunk_counts(), &[3, 3]);
+        assert_eq!(tree_a.root(), tree_b.root());
+        assert!(tree_a.diff(&tree_b).unwrap().is_empty());
+
+        a.set(&[9, 1], 5).unwrap();
+        a.set(&[5, 5], 5).unwrap();
+        tree_a.update(&a, &[9, 1]).unwrap();
+        tree_a.update(&a, &[5, 5]).unwrap();
+        assert_eq!(tree_a, a.merkle_tree(&[4, 4]).unwrap());
+        // Pinned so a change to how chunks are hashed, on any platform, is
+        // caught before stored hashes stop matching.
+        assert_eq!(tree_a.root(), 15_038_126_782_583_670_336);
+        assert_ne!(tree_a.root(), tree_b.root());
+
+        assert_eq!(
+            tree_a.diff(&tree_b).unwrap(),
+            vec![
+                Region::new(vec![4..8, 4..8]),
+                Region::new(vec![8..10, 0..4])
+            ]
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::cmp::Ordering;
+
+use crate::{direction::offset_coords, Coord, Grid, GridError};
+
+/// How distance between two cells is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Metric {
+    /// Sum of the per-axis distances.
+    #[default]
+    Manhattan,
+    /// Largest per-axis distance.
+    Chebyshev,
+    /// Straight-line distance.
+    Euclidean,
+}
+
+impl Metric {
+    pub fn distance(self, a: &[usize], b: &[usize]) -> f64 {
+        let deltas = a.iter().zip(b).map(|(&a, &b)| a.abs_diff(b) as f64);
+        match self {
+            Metric::Manhattan => deltas.sum(),
+            Metric::Chebyshev => deltas.fold(0.0, f64::max),
+            Metric::Euclidean => deltas.map(|d| d * d).sum::<f64>().sqrt(),
+        }
+    }
+}
+
+/// Offsets of every cell at Chebyshev distance exactly `radius`.
+pub(crate) fn ring_offsets(axes: usize, radius: isize) -> Vec<Vec<isize>> {
+    fn walk(
+        axes: usize,
+        radius: isize,
+        on_ring: bool,
+        current: &mut Vec<isize>,
+        out: &mut Vec<Vec<isize>>,
+    ) {
+        if current.len() == axes {
+            if on_ring {
+                out.push(current.clone());
+            }
+            return;
+        }
+
+        let last = current.len() + 1 == axes;
+        for d in -radius..=radius {
+            let edge = d.abs() == radius;
+            if last && !on_ring && !edge {
+                continue;
+            }
+            current.push(d);
+            walk(axes, radius, on_ring || edge, current, out);
+            current.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(axes, radius, false, &mut Vec::with_capacity(axes), &mut out);
+    out
+}
+
+impl<T> Grid<T> {
+    /// Finds the closest cell to `origin` matching `predicate`, searching in
+    /// expanding rings. Ties are broken by row-major order.
+    pub fn nearest_matching<P>(
+        &self,
+        origin: &[usize],
+        predicate: P,
+        metric: Metric,
+    ) -> Result<Option<(Coord, &T)>, GridError>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        Ok(self.k_nearest_matching(origin, 1, predicate, metric)?.pop())
+    }
+
+    /// Finds up to `k` cells closest to `origin` matching `predicate`, sorted
+    /// by distance and then row-major order.
+    pub fn k_nearest_matching<P>(
+        &self,
+        origin: &[usize],
+        k: usize,
+        mut predicate: P,
+        metric: Metric,
+    ) -> Result<Vec<(Coord, &T)>, GridError>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.translate_index(origin)?;
+
+        let max_radius = origin
+            .iter()
+            .zip(&self.dimensions)
+            .map(|(&o, &len)| o.max(len - 1 - o))
+            .max()
+            .unwrap_or(0);
+
+        let by_distance = |a: &(f64, Coord), b: &(f64, Coord)| {
+            a.0.partial_cmp(&b.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.1.cmp(&b.1))
+        };
+
+        let mut found: Vec<(f64, Coord)> = Vec::new();
+        for radius in 0..=max_radius {
+            // Every cell on this ring is at least `radius` away under all metrics.
+            if k == 0 || (found.len() >= k && radius as f64 > found[k - 1].0) {
+                break;
+            }
+
+            for offset in ring_offsets(self.axes, radius as isize) {
+                let Some(coords) = offset_coords(origin, &offset, &self.dimensions) else {
+                    continue;
+                };
+                if predicate(&self.grid[self.translate_index(&coords)?]) {
+                    found.push((metric.distance(origin, &coords), coords));
+                }
+            }
+            found.sort_by(by_distance);
+        }
+
+        found.truncate(k);
+        found
+            .into_iter()
+            .map(|(_, coords)| {
+                let index = self.translate_index(&coords)?;
+                Ok((coords, &self.grid[index]))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn ring_offsets_cover_shell() {
+        assert_eq!(ring_offsets(2, 0), vec![vec![0, 0]]);
+        assert_eq!(ring_offsets(2, 1).len(), 8);
+        assert_eq!(ring_offsets(2, 2).len(), 16);
+        assert_eq!(ring_offsets(3, 1).len(), 26);
+    }
+
+    #[test]
+    fn nearest_matching() {
+        let mut grid = Grid::new(0, vec![10, 10]);
+        grid.set(&[0, 0], 1).unwrap();
+        grid.set(&[6, 7], 2).unwrap();
+        grid.set(&[8, 5], 3).unwrap();
+
+        let (coords, val) = grid
+            .nearest_matching(&[6, 4], |&v| v != 0, Metric::Manhattan)
+            .unwrap()
+            .unwrap();
+        // Both are 3 steps away, so row-major order decides.
+        assert_eq!((coords, *val), (smallvec![6, 7], 2));
+
+        let (coords, _) = grid
+            .nearest_matching(&[6, 4], |&v| v != 0, Metric::Chebyshev)
+            .unwrap()
+            .unwrap();
+        assert_eq!(coords[..], [8, 5]);
+
+        let all = grid
+            .k_nearest_matching(&[6, 4], 5, |&v| v != 0, Metric::Euclidean)
+            .unwrap();
+        let values: Vec<_> = all.into_iter().map(|(_, v)| *v).collect();
+        assert_eq!(values, vec![3, 2, 1]);
+
+        assert!(grid
+            .nearest_matching(&[6, 4], |&v| v == 9, Metric::Manhattan)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    direction::{offset_coords, Direction},
+    nearest::ring_offsets,
+    Coord, Grid,
+};
+
+/// Which cells count as adjacent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    /// Cells sharing a face (von Neumann neighborhood): 4 in 2D, 6 in 3D.
+    #[default]
+    Orthogonal,
+    /// Cells sharing a face, edge, or corner (Moore neighborhood): 8 in 2D,
+    /// 26 in 3D.
+    Diagonal,
+}
+
+impl Connectivity {
+    /// Offsets from a cell to each of its neighbors in a grid with `axes`
+    /// dimensions.
+    pub fn offsets(self, axes: usize) -> Vec<Vec<isize>> {
+        match self {
+            Connectivity::Orthogonal => (0..axes)
+                .flat_map(|axis| [Direction::Negative(axis), Direction::Positive(axis)])
+                .map(|dir| dir.offset(axes))
+                .collect(),
+            Connectivity::Diagonal => ring_offsets(axes, 1),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// The cells adjacent to `target` under `connectivity`, with their
+    /// coordinates, clipped at the grid's edges. Yields nothing if `target`
+    /// is out of bounds.
+    pub fn neighbors<'a>(
+        &'a self,
+        target: &'a [usize],
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (Coord, &'a T)> + 'a {
+        let offsets = match self.checked_index(target) {
+            Some(_) => connectivity.offsets(self.axes),
+            None => Vec::new(),
+        };
+        offsets.into_iter().filter_map(move |offset| {
+            let coords = offset_coords(target, &offset, &self.dimensions)?;
+            let index = self.checked_index(&coords)?;
+            Some((coords, self.cell(index)))
+        })
+    }
+
+    /// The passable neighbors of `target`, each with a step cost of 1. Empty
+    /// if `target` is out of bounds.
+    ///
+    /// The return shape matches the successor functions used by the
+    /// `pathfinding` crate, e.g.
+    /// `dijkstra(&start, |c| grid.successors(c, Connectivity::Orthogonal, |v| *v != '#'), |c| *c == goal)`.
+    pub fn successors<P>(
+        &self,
+        target: &[usize],
+        connectivity: Connectivity,
+        mut passable: P,
+    ) -> Vec<(Coord, usize)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        if self.checked_index(target).is_none() {
+            return Vec::new();
+        }
+        connectivity
+            .offsets(self.axes)
+            .iter()
+            .filter_map(|offset| offset_coords(target, offset, &self.dimensions))
+            .filter(|coords| {
+                self.translate_index(coords)
+                    .is_ok_and(|i| passable(self.cell(i)))
+            })
+            .map(|coords| (coords, 1))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn successors() {
+        let mut grid = Grid::new('.', vec![3, 3]);
+        grid.set(&[0, 1], '#').unwrap();
+
+        let orthogonal = grid.successors(&[0, 0], Connectivity::Orthogonal, |&c| c != '#');
+        assert_eq!(orthogonal, vec![(smallvec![1, 0], 1)]);
+
+        let diagonal = grid.successors(&[0, 0], Connectivity::Diagonal, |&c| c != '#');
+        assert_eq!(diagonal, vec![(smallvec![1, 0], 1), (smallvec![1, 1], 1)]);
+
+        assert!(grid
+            .successors(&[3, 1], Connectivity::Diagonal, |_| true)
+            .is_empty());
+        assert!(grid
+            .successors(&[0, 0, 0], Connectivity::Orthogonal, |_| true)
+            .is_empty());
+        assert_eq!(Connectivity::Diagonal.offsets(3).len(), 26);
+    }
+
+    #[test]
+    fn neighbors_clip_at_edges() {
+        let mut grid = Grid::new(0, vec![3, 3, 3]);
+        for (i, v) in grid.iter_mut().enumerate() {
+            *v = i;
+        }
+
+        assert_eq!(
+            grid.neighbors(&[1, 1, 1], Connectivity::Orthogonal).count(),
+            6
+        );
+        assert_eq!(
+            grid.neighbors(&[1, 1, 1], Connectivity::Diagonal).count(),
+            26
+        );
+        assert_eq!(
+            grid.neighbors(&[0, 0, 0], Connectivity::Diagonal).count(),
+            7
+        );
+
+        let corner: Vec<_> = grid
+            .neighbors(&[0, 0, 2], Connectivity::Orthogonal)
+            .map(|(coords, &v)| (coords.to_vec(), v))
+            .collect();
+        assert_eq!(
+            corner,
+            vec![(vec![1, 0, 2], 11), (vec![0, 1, 2], 5), (vec![0, 0, 1], 1)]
+        );
+        assert_eq!(
+            grid.neighbors(&[3, 0, 0], Connectivity::Diagonal).count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{Grid, GridError, GridLike, Shape, Stats};
+
+/// A grid paired with a validity mask, for rasters where some cells hold no
+/// data. Operations skip NoData cells, and a result is NoData wherever any
+/// cell it was computed from was.
+#[derive(Debug, Clone)]
+pub struct NoDataGrid<T> {
+    grid: Grid<T>,
+    valid: Grid<bool>,
+}
+
+impl<T> NoDataGrid<T> {
+    /// Wraps `grid` with every cell valid.
+    pub fn new(grid: Grid<T>) -> Self {
+        let valid = Grid::new(true, grid.dimensions.clone());
+        Self { grid, valid }
+    }
+
+    /// Wraps `grid` with a mask that is `true` for cells holding data.
+    pub fn with_mask(grid: Grid<T>, valid: Grid<bool>) -> Result<Self, GridError> {
+        Shape::from(&grid.dimensions[..]).check_eq(&valid.dimensions)?;
+        Ok(Self { grid, valid })
+    }
+
+    /// Wraps `grid`, marking cells equal to `sentinel` as NoData.
+    pub fn from_sentinel(grid: Grid<T>, sentinel: &T) -> Self
+    where
+        T: PartialEq,
+    {
+        let valid = Grid::from_parts(
+            grid.grid.iter().map(|v| v != sentinel).collect(),
+            grid.dimensions.clone(),
+        );
+        Self { grid, valid }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// `true` for cells holding data.
+    pub fn mask(&self) -> &Grid<bool> {
+        &self.valid
+    }
+
+    pub fn into_parts(self) -> (Grid<T>, Grid<bool>) {
+        (self.grid, self.valid)
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        self.grid.dimensions()
+    }
+
+    /// Number of cells holding data.
+    pub fn valid_count(&self) -> usize {
+        self.valid.grid.iter().filter(|&&v| v).count()
+    }
+
+    pub fn is_valid(&self, target: &[usize]) -> Result<bool, GridError> {
+        self.valid.get(target).copied()
+    }
+
+    /// The value at `target`, or `None` if it is NoData.
+    pub fn get(&self, target: &[usize]) -> Result<Option<&T>, GridError> {
+        let value = self.grid.get(target)?;
+        Ok(self.valid.get(target)?.then_some(value))
+    }
+
+    /// Stores a value at `target`, marking it valid.
+    pub fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        self.grid.set(target, val)?;
+        self.valid.set(target, true)
+    }
+
+    /// Marks `target` as NoData. Its stored value is left as it was.
+    pub fn clear(&mut self, target: &[usize]) -> Result<(), GridError> {
+        self.valid.set(target, false)
+    }
+
+    /// A plain grid with NoData cells replaced by `sentinel`, for formats
+    /// that store NoData in band.
+    pub fn to_grid(&self, sentinel: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let mut grid = self.grid.clone();
+        for (v, &valid) in grid.grid.iter_mut().zip(&self.valid.grid) {
+            if !valid {
+                *v = sentinel.clone();
+            }
+        }
+        grid
+    }
+
+    /// Applies `f` to every valid cell. NoData cells stay NoData and hold
+    /// `U::default()`.
+    pub fn map<U, F>(&self, mut f: F) -> NoDataGrid<U>
+    where
+        U: Default,
+        F: FnMut(&T) -> U,
+    {
+        let grid = Grid::from_parts(
+            (self.grid.grid.iter().zip(&self.valid.grid))
+                .map(|(v, &valid)| if valid { f(v) } else { U::default() })
+                .collect(),
+            self.grid.dimensions.clone(),
+        );
+        NoDataGrid {
+            grid,
+            valid: self.valid.clone(),
+        }
+    }
+
+    /// Combines cells of two same-shaped grids with `f`. A cell is NoData if
+    /// it is NoData in either input.
+    pub fn zip_with<U, V, F>(
+        &self,
+        other: &NoDataGrid<U>,
+        mut f: F,
+    ) -> Result<NoDataGrid<V>, GridError>
+    where
+        V: Default,
+        F: FnMut(&T, &U) -> V,
+    {
+        Shape::from(&self.grid.dimensions[..]).check_eq(&other.grid.dimensions)?;
+        let valid: Vec<bool> = (self.valid.grid.iter().zip(&other.valid.grid))
+            .map(|(&a, &b)| a && b)
+            .collect();
+        let grid = (self.grid.grid.iter().zip(&other.grid.grid).zip(&valid))
+            .map(|((a, b), &valid)| if valid { f(a, b) } else { V::default() })
+            .collect();
+
+        let dimensions = self.grid.dimensions.clone();
+        Ok(NoDataGrid {
+            grid: Grid::from_parts(grid, dimensions.clone()),
+            valid: Grid::from_parts(valid, dimensions),
+        })
+    }
+}
+
+impl<T: Copy + Into<f64>> NoDataGrid<T> {
+    /// Statistics over the valid cells.
+    pub fn stats(&self) -> Stats {
+        self.grid
+            .stats_masked(Some(&self.valid))
+            .expect("mask always matches the grid's shape")
+    }
+}
+
+impl NoDataGrid<f64> {
+    /// Wraps `grid`, marking NaN cells as NoData.
+    pub fn from_nan(grid: Grid<f64>) -> Self {
+        let valid = Grid::from_parts(
+            grid.grid.iter().map(|v| !v.is_nan()).collect(),
+            grid.dimensions.clone(),
+        );
+        Self { grid, valid }
+    }
+
+    /// Like [`GridLike::convolve`], but an output cell is NoData if the kernel
+    /// gives nonzero weight to any NoData cell around it.
+    pub fn convolve(&self, kernel: &Grid<f64>) -> Result<NoDataGrid<f64>, GridError> {
+        let mut values = self.grid.clone();
+        let mut missing = Grid::new(0.0, self.grid.dimensions.clone());
+        for ((v, m), &valid) in values
+            .grid
+            .iter_mut()
+            .zip(&mut missing.grid)
+            .zip(&self.valid.grid)
+        {
+            if !valid {
+                (*v, *m) = (0.0, 1.0);
+            }
+        }
+        let weights = Grid::from_parts(
+            kernel.grid.iter().map(|k| k.abs()).collect(),
+            kernel.dimensions.clone(),
+        );
+
+        let grid = values.convolve(kernel)?;
+        let touched = missing.convolve(&weights)?;
+        let valid = Grid::from_parts(
+            touched.grid.iter().map(|&t| t == 0.0).collect(),
+            grid.dimensions.clone(),
+        );
+        Ok(NoDataGrid { grid, valid })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nodata_propagates() {
+        let mut grid = Grid::new(1.0, vec![3, 4]);
+        grid.set(&[0, 0], -9999.0).unwrap();
+        let raster = NoDataGrid::from_sentinel(grid, &-9999.0);
+        assert_eq!(raster.valid_count(), 11);
+        assert_eq!(raster.get(&[0, 0]).unwrap(), None);
+        assert_eq!(raster.stats().mean, 1.0);
+
+        let doubled = raster.map(|v| v * 2.0);
+        assert_eq!(doubled.get(&[0, 1]).unwrap(), Some(&2.0));
+        assert_eq!(doubled.get(&[0, 0]).unwrap(), None);
+
+        let mut other = NoDataGrid::new(Grid::new(3.0, vec![3, 4]));
+        other.clear(&[2, 3]).unwrap();
+        let sum = raster.zip_with(&other, |a, b| a + b).unwrap();
+        assert_eq!(sum.valid_count(), 10);
+        assert_eq!(sum.to_grid(f64::NAN).get_opt(&[1, 1]), Some(&4.0));
+        assert!(sum.to_grid(f64::NAN).get_opt(&[2, 3]).unwrap().is_nan());
+
+        let smoothed = raster.convolve(&Grid::new(1.0, vec![3, 3])).unwrap();
+        assert_eq!(smoothed.get(&[1, 1]).unwrap(), None);
+        assert_eq!(smoothed.get(&[1, 2]).unwrap(), Some(&9.0));
+        assert_eq!(smoothed.get(&[0, 2]).unwrap(), Some(&6.0));
+
+        let mismatched = NoDataGrid::new(Grid::new(0.0, vec![4, 3]));
+        assert!(raster.zip_with(&mismatched, |a, b| a + b).is_err());
+    }
+}
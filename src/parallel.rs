@@ -0,0 +1,68 @@
+use rayon::prelude::*;
+
+use crate::{false_index, Coord, Grid};
+
+impl<T: Sync> Grid<T> {
+    /// Iterates over all cells in parallel. Row-major order is kept by
+    /// order-preserving adapters such as `collect`.
+    pub fn par_iter(&self) -> rayon::slice::Iter<'_, T> {
+        self.grid.par_iter()
+    }
+
+    /// Iterates over all cells in parallel, with their coordinates.
+    pub fn par_iter_indexed(&self) -> impl IndexedParallelIterator<Item = (Coord, &T)> {
+        let dimensions = &self.dimensions;
+        self.grid
+            .par_iter()
+            .enumerate()
+            .map(move |(i, v)| (false_index(i, dimensions), v))
+    }
+}
+
+impl<T: Send> Grid<T> {
+    /// Iterates mutably over all cells in parallel.
+    pub fn par_iter_mut(&mut self) -> rayon::slice::IterMut<'_, T> {
+        self.grid.par_iter_mut()
+    }
+
+    /// Iterates mutably over all cells in parallel, with their coordinates.
+    pub fn par_iter_mut_indexed(&mut self) -> impl IndexedParallelIterator<Item = (Coord, &mut T)> {
+        let dimensions = &self.dimensions;
+        self.grid
+            .par_iter_mut()
+            .enumerate()
+            .map(move |(i, v)| (false_index(i, dimensions), v))
+    }
+
+    /// Splits the grid into its lanes along the last axis (the rows of a 2D
+    /// grid), which are contiguous, and hands them out in parallel with
+    /// their index in row-major order. Working a lane at a time keeps each
+    /// thread on its own cache lines.
+    pub fn par_lanes_mut(&mut self) -> impl IndexedParallelIterator<Item = (usize, &mut [T])> {
+        let len = self.dimensions.last().copied().unwrap_or(1).max(1);
+        self.grid.par_chunks_mut(len).enumerate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_updates() {
+        let mut field = Grid::new(0.0, vec![64, 32]);
+        field
+            .par_iter_mut_indexed()
+            .for_each(|(c, v)| *v = (c[0] * 32 + c[1]) as f64);
+        let serial: Vec<f64> = (0..64 * 32).map(|i| i as f64).collect();
+        assert_eq!(field.par_iter().copied().collect::<Vec<_>>(), serial);
+
+        field
+            .par_lanes_mut()
+            .for_each(|(row, lane)| lane.iter_mut().for_each(|v| *v -= (row * 32) as f64));
+        assert_eq!(field.get_opt(&[63, 5]), Some(&5.0));
+
+        let total: f64 = field.par_iter_indexed().map(|(c, v)| c[1] as f64 - v).sum();
+        assert_eq!(total, 0.0);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::{direction::offset_coords, false_index, Connectivity, Grid};
+
+/// Connected regions of a grid and how they border each other, as returned
+/// by [`Grid::partition_regions`].
+#[derive(Debug, Clone)]
+pub struct Partition {
+    labels: Grid<usize>,
+    sizes: Vec<usize>,
+    borders: BTreeMap<(usize, usize), usize>,
+}
+
+impl Partition {
+    /// The region label of every cell. Labels are numbered from 0 in the
+    /// row-major order of each region's first cell.
+    pub fn labels(&self) -> &Grid<usize> {
+        &self.labels
+    }
+
+    pub fn into_labels(self) -> Grid<usize> {
+        self.labels
+    }
+
+    pub fn region_count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Number of cells in each region, indexed by label.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// Every pair of touching regions `(a, b)` with `a < b`, mapped to the
+    /// number of cell faces they share.
+    pub fn borders(&self) -> &BTreeMap<(usize, usize), usize> {
+        &self.borders
+    }
+
+    /// Number of cell faces shared by two regions, or 0 if they don't touch.
+    pub fn border_length(&self, a: usize, b: usize) -> usize {
+        self.borders
+            .get(&(a.min(b), a.max(b)))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The regions touching `region`, with their shared border lengths.
+    pub fn neighbors(&self, region: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.borders.iter().filter_map(move |(&(a, b), &len)| {
+            if a == region {
+                Some((b, len))
+            } else if b == region {
+                Some((a, len))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Splits the grid into regions of orthogonally connected cells, where two
+    /// neighboring cells share a region when `same_region(a, b)` is true
+    /// (e.g. `|a, b| a == b` for a political map).
+    pub fn partition_regions<F>(&self, mut same_region: F) -> Partition
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        const UNLABELED: usize = usize::MAX;
+
+        let offsets = Connectivity::Orthogonal.offsets(self.axes);
+        let mut labels = vec![UNLABELED; self.grid.len()];
+        let mut sizes = Vec::new();
+        let mut queue = VecDeque::new();
+
+        for seed in 0..self.grid.len() {
+            if labels[seed] != UNLABELED {
+                continue;
+            }
+
+            let label = sizes.len();
+            let mut size = 0;
+            labels[seed] = label;
+            queue.push_back(seed);
+            while let Some(index) = queue.pop_front() {
+                size += 1;
+                let coords = false_index(index, &self.dimensions);
+                for offset in &offsets {
+                    let Some(next) = offset_coords(&coords, offset, &self.dimensions) else {
+                        continue;
+                    };
+                    let next = self.translate_index(&next).unwrap();
+                    if labels[next] == UNLABELED && same_region(&self.grid[index], &self.grid[next])
+                    {
+                        labels[next] = label;
+                        queue.push_back(next);
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+
+        // Count each shared face once by only looking in the positive
+        // direction along every axis.
+        let mut borders = BTreeMap::new();
+        for (index, &label) in labels.iter().enumerate() {
+            let coords = false_index(index, &self.dimensions);
+            for axis in 0..self.axes {
+                let mut next = coords.clone();
+                next[axis] += 1;
+                let Ok(next) = self.translate_index(&next) else {
+                    continue;
+                };
+                let other = labels[next];
+                if other != label {
+                    *borders
+                        .entry((label.min(other), label.max(other)))
+                        .or_insert(0) += 1;
+                }
+            }
+        }
+
+        Partition {
+            labels: Grid::from_parts(labels, self.dimensions.clone()),
+            sizes,
+            borders,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partition_regions() {
+        // AAB
+        // ACB
+        // AAB
+        let mut grid = Grid::new('A', vec![3, 3]);
+        for row in 0..3 {
+            grid.set(&[row, 2], 'B').unwrap();
+        }
+        grid.set(&[1, 1], 'C').unwrap();
+
+        let partition = grid.partition_regions(|a, b| a == b);
+        assert_eq!(partition.region_count(), 3);
+        assert_eq!(partition.sizes(), &[5, 3, 1]);
+        assert_eq!(*partition.labels().get(&[1, 1]).unwrap(), 2);
+
+        assert_eq!(partition.border_length(0, 1), 2);
+        assert_eq!(partition.border_length(1, 2), 1);
+        assert_eq!(partition.border_length(2, 0), 3);
+        assert_eq!(
+            partition.neighbors(2).collect::<Vec<_>>(),
+            vec![(0, 3), (1, 1)]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use crate::{direction::offset_coords, Connectivity, GridError, GridLike, Metric, Shape};
+
+/// Cell coordinates from start to goal, inclusive.
+pub(crate) type Path = Vec<Vec<usize>>;
+
+/// Backs [`GridLike::bfs_shortest_path`].
+pub(crate) fn bfs_shortest_path<G, P>(
+    grid: &G,
+    start: &[usize],
+    goal: &[usize],
+    mut passable: P,
+) -> Result<Option<Path>, GridError>
+where
+    G: GridLike,
+    P: FnMut(&G::Item) -> bool,
+{
+    if !passable(grid.get(start)?) || !passable(grid.get(goal)?) {
+        return Ok(None);
+    }
+
+    let shape = Shape::from(grid.dimensions());
+    let (start, goal) = (index(&shape, start), index(&shape, goal));
+    let mut previous = vec![usize::MAX; shape.cell_count()];
+    let mut queue = VecDeque::from([start]);
+    previous[start] = start;
+    while let Some(current) = queue.pop_front() {
+        if current == goal {
+            return Ok(Some(trace_path(&shape, &previous, goal)));
+        }
+        for next in orthogonal_steps(&shape, current) {
+            if previous[next] == usize::MAX && passable(cell(grid, &shape, next)) {
+                previous[next] = current;
+                queue.push_back(next);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Backs [`GridLike::astar_with_cost`].
+pub(crate) fn astar_with_cost<G, P, C>(
+    grid: &G,
+    start: &[usize],
+    goal: &[usize],
+    mut passable: P,
+    mut cost: C,
+) -> Result<Option<(Path, usize)>, GridError>
+where
+    G: GridLike,
+    P: FnMut(&G::Item) -> bool,
+    C: FnMut(&G::Item) -> usize,
+{
+    if !passable(grid.get(start)?) || !passable(grid.get(goal)?) {
+        return Ok(None);
+    }
+
+    let shape = Shape::from(grid.dimensions());
+    let heuristic =
+        |i: usize| Metric::Manhattan.distance(&shape.coords_of(i).unwrap(), goal) as usize;
+    let (start, goal) = (index(&shape, start), index(&shape, goal));
+    let mut costs = vec![usize::MAX; shape.cell_count()];
+    let mut previous = vec![usize::MAX; shape.cell_count()];
+    let mut open = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+    costs[start] = 0;
+    previous[start] = start;
+    while let Some(Reverse((_, so_far, current))) = open.pop() {
+        if current == goal {
+            return Ok(Some((trace_path(&shape, &previous, goal), so_far)));
+        }
+        if so_far > costs[current] {
+            continue;
+        }
+        for next in orthogonal_steps(&shape, current) {
+            let next_cell = cell(grid, &shape, next);
+            if !passable(next_cell) {
+                continue;
+            }
+            let next_cost = so_far + cost(next_cell).max(1);
+            if next_cost < costs[next] {
+                costs[next] = next_cost;
+                previous[next] = current;
+                open.push(Reverse((next_cost + heuristic(next), next_cost, next)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn index(shape: &Shape, coords: &[usize]) -> usize {
+    shape.flat_index(coords).expect("checked against the grid")
+}
+
+fn cell<'a, G: GridLike>(grid: &'a G, shape: &Shape, i: usize) -> &'a G::Item {
+    grid.get_opt(&shape.coords_of(i).unwrap()).unwrap()
+}
+
+/// Flat indices of the orthogonal neighbors of the cell at `i`.
+fn orthogonal_steps(shape: &Shape, i: usize) -> impl Iterator<Item = usize> + '_ {
+    let coords = shape.coords_of(i).unwrap();
+    Connectivity::Orthogonal
+        .offsets(shape.axes())
+        .into_iter()
+        .filter_map(move |offset| shape.flat_index(&offset_coords(&coords, &offset, shape)?))
+}
+
+/// Follows `previous` back from `goal` to the cell that is its own
+/// predecessor.
+fn trace_path(shape: &Shape, previous: &[usize], goal: usize) -> Path {
+    let mut path = vec![shape.coords_of(goal).unwrap().to_vec()];
+    let mut i = goal;
+    while previous[i] != i {
+        i = previous[i];
+        path.push(shape.coords_of(i).unwrap().to_vec());
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Grid, GridLike};
+
+    #[test]
+    fn shortest_and_cheapest_paths() {
+        let map = Grid::from_fn(vec![3, 5], |c| match (c[0], c[1]) {
+            (0..=1, 2) => '#',
+            (0, 1) => '~',
+            _ => '.',
+        });
+        let open = |c: &char| *c != '#';
+
+        let path = map.bfs_shortest_path(&[0, 0], &[0, 4], open).unwrap();
+        let path = path.unwrap();
+        assert_eq!(path.len(), 9);
+        assert_eq!(path[0], [0, 0]);
+        assert_eq!(path[8], [0, 4]);
+        assert_eq!(map.astar(&[0, 0], &[0, 4], open).unwrap().unwrap().len(), 9);
+
+        // Wading through water costs 5, so the cheapest route avoids it.
+        let cost = |c: &char| if *c == '~' { 5 } else { 1 };
+        let (path, total) = map
+            .astar_with_cost(&[0, 1], &[0, 4], open, cost)
+            .unwrap()
+            .unwrap();
+        assert_eq!(total, 7);
+        assert_eq!(path[1], [1, 1]);
+
+        assert_eq!(map.astar(&[0, 0], &[0, 2], open), Ok(None));
+        assert!(map.bfs_shortest_path(&[3, 0], &[0, 0], open).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{Grid, GridError, Shape};
+
+/// Recycles the storage of same-shaped grids, so that algorithms needing
+/// scratch grids every step don't allocate a fresh buffer each time.
+#[derive(Debug, Clone)]
+pub struct GridPool<T> {
+    fill: T,
+    shape: Shape,
+    free: Vec<Vec<T>>,
+}
+
+impl<T: Clone> GridPool<T> {
+    /// An empty pool of grids with the given `dimensions`, handed out with
+    /// every cell set to `fill`.
+    pub fn new(fill: T, dimensions: Vec<usize>) -> Self {
+        Self {
+            fill,
+            shape: Shape::new(dimensions),
+            free: Vec::new(),
+        }
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Number of released buffers waiting to be reused.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// A grid with every cell set to the pool's fill value, reusing a
+    /// released buffer if there is one.
+    pub fn acquire(&mut self) -> Grid<T> {
+        match self.free.pop() {
+            Some(mut grid) => {
+                grid.fill(self.fill.clone());
+                Grid::from_parts(grid, self.shape.to_vec())
+            }
+            None => Grid::new(self.fill.clone(), self.shape.to_vec()),
+        }
+    }
+
+    /// Returns a grid's buffer to the pool. Fails if the grid has a
+    /// different shape than the pool's.
+    pub fn release(&mut self, grid: Grid<T>) -> Result<(), GridError> {
+        self.shape.check_eq(&grid.dimensions)?;
+        self.free.push(grid.grid);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_buffers() {
+        let mut pool = GridPool::new(0u8, vec![4, 4]);
+        let mut scratch = pool.acquire();
+        scratch.set(&[1, 1], 9).unwrap();
+        let ptr = scratch.grid.as_ptr();
+        pool.release(scratch).unwrap();
+        assert_eq!(pool.available(), 1);
+
+        let again = pool.acquire();
+        assert_eq!(again.grid.as_ptr(), ptr);
+        assert_eq!(again.get_opt(&[1, 1]), Some(&0));
+        assert_eq!(pool.available(), 0);
+
+        assert!(pool.release(Grid::new(0, vec![4, 3])).is_err());
+        assert_eq!(pool.available(), 0);
+    }
+}
@@ -0,0 +1,310 @@
+//! A coarse-to-fine encoding of a grid, so that any prefix of the stream
+//! decodes to a lower-resolution preview.
+//!
+//! The cells are split into levels by stride. The coarsest level holds every
+//! cell whose coordinates are all multiples of the largest stride; each finer
+//! level halves the stride and holds only the cells not written before, so no
+//! cell is stored twice and the last level completes the grid.
+//!
+//! ```
+//! use md_grid::{progressive, Grid};
+//!
+//! let grid = Grid::from_fn(vec![8, 8], |c| (c[0] * 8 + c[1]) as u16);
+//! let bytes = progressive::encode(&grid);
+//!
+//! // Half the stream is enough for a quarter-resolution preview.
+//! let preview = progressive::decode_prefix::<u16>(&bytes[..bytes.len() / 2])
+//!     .unwrap()
+//!     .unwrap();
+//! assert_eq!(preview.stride(), 2);
+//! assert_eq!(preview.grid().dimensions(), &[4, 4]);
+//! assert_eq!(preview.grid().get_opt(&[1, 3]), Some(&22));
+//!
+//! assert!(progressive::decode::<u16>(&bytes).unwrap().iter().eq(grid.iter()));
+//! ```
+
+use crate::{
+    container::{ContainerError, ElementCodec},
+    indexed::CoordCounter,
+    Grid, Shape,
+};
+
+const MAGIC: &[u8; 4] = b"MDGP";
+const FORMAT_VERSION: u16 = 1;
+
+/// The grid subsampled at `stride`: cell `c` of the preview is cell
+/// `c * stride` of the full grid.
+#[derive(Debug, Clone)]
+pub struct Preview<T> {
+    grid: Grid<T>,
+    stride: usize,
+    dimensions: Vec<usize>,
+}
+
+impl<T> Preview<T> {
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The dimensions of the full-resolution grid.
+    pub fn full_dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    /// Whether every level was decoded, so the preview is the full grid.
+    pub fn is_complete(&self) -> bool {
+        self.stride == 1
+    }
+
+    /// Scales the preview up to the full dimensions, each cell taking the
+    /// value of the preview cell it falls in.
+    pub fn upscale(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let stride = self.stride;
+        Grid::from_fn(self.dimensions.clone(), |c| {
+            let flat = c
+                .iter()
+                .zip(&self.grid.dimensions)
+                .fold(0, |i, (&c, &d)| i * d + c / stride);
+            self.grid.grid[flat].clone()
+        })
+    }
+}
+
+/// The stride of the coarsest level: the smallest power of two leaving at
+/// most two cells along every axis.
+fn coarsest_stride(dimensions: &[usize]) -> usize {
+    let mut stride = 1usize;
+    while dimensions.iter().any(|&d| d.div_ceil(stride) > 2) {
+        stride *= 2;
+    }
+    stride
+}
+
+fn level_dimensions(dimensions: &[usize], stride: usize) -> Vec<usize> {
+    dimensions.iter().map(|d| d.div_ceil(stride)).collect()
+}
+
+/// Walks the cells of the level at `stride`, calling `f` with their
+/// coordinates in that level and whether a coarser level already holds them.
+/// Stops early if `f` returns `None`.
+fn walk_level(
+    dimensions: &[usize],
+    stride: usize,
+    coarsest: bool,
+    mut f: impl FnMut(&[usize], bool) -> Option<()>,
+) -> Option<()> {
+    let dims = level_dimensions(dimensions, stride);
+    let len: usize = dims.iter().product();
+    let mut counter = CoordCounter::new(&dims);
+    for _ in 0..len {
+        let coords = counter.coords();
+        f(coords, !coarsest && coords.iter().all(|c| c % 2 == 0))?;
+        counter.advance();
+    }
+    Some(())
+}
+
+/// Encodes `grid` coarse to fine.
+pub fn encode<T: ElementCodec>(grid: &Grid<T>) -> Vec<u8> {
+    let dimensions = &grid.dimensions;
+    let mut out = Vec::with_capacity(grid.grid.len() + 64);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&(T::SCHEMA.len() as u32).to_le_bytes());
+    out.extend_from_slice(T::SCHEMA.as_bytes());
+    out.extend_from_slice(&(dimensions.len() as u32).to_le_bytes());
+    for &d in dimensions {
+        out.extend_from_slice(&(d as u64).to_le_bytes());
+    }
+
+    let top = coarsest_stride(dimensions);
+    let mut stride = top;
+    let mut level = Vec::new();
+    loop {
+        level.clear();
+        walk_level(dimensions, stride, stride == top, |coords, seen| {
+            if !seen {
+                let flat = coords
+                    .iter()
+                    .zip(dimensions)
+                    .fold(0, |i, (&c, &d)| i * d + c * stride);
+                grid.grid[flat].encode(&mut level);
+            }
+            Some(())
+        });
+        // Each level is prefixed with its length so a reader can tell a
+        // complete level from a truncated one.
+        out.extend_from_slice(&(level.len() as u64).to_le_bytes());
+        out.extend_from_slice(&level);
+        if stride == 1 {
+            return out;
+        }
+        stride /= 2;
+    }
+}
+
+/// Decodes the finest preview contained in `prefix`, a prefix of the output
+/// of [`encode`]. Returns `None` if the prefix doesn't yet hold the header
+/// and the coarsest level.
+pub fn decode_prefix<T: Clone + ElementCodec>(
+    prefix: &[u8],
+) -> Result<Option<Preview<T>>, ContainerError> {
+    if !MAGIC.starts_with(&prefix[..prefix.len().min(MAGIC.len())]) {
+        return Err(ContainerError::BadMagic);
+    }
+    let mut input = prefix;
+    let mut split = |len: usize| -> Option<&[u8]> {
+        if input.len() < len {
+            return None;
+        }
+        let (head, rest) = input.split_at(len);
+        input = rest;
+        Some(head)
+    };
+    // Everything up to the levels is needed before anything can be shown.
+    macro_rules! take {
+        ($len:expr) => {
+            match split($len) {
+                Some(bytes) => bytes,
+                None => return Ok(None),
+            }
+        };
+    }
+
+    take!(MAGIC.len());
+    let format = u16::decode(&mut take!(2)).unwrap();
+    if format != FORMAT_VERSION {
+        return Err(ContainerError::UnsupportedFormat(format));
+    }
+    let schema_len = u32::decode(&mut take!(4)).unwrap() as usize;
+    let schema = take!(schema_len);
+    if schema != T::SCHEMA.as_bytes() {
+        return Err(ContainerError::SchemaMismatch {
+            expected: T::SCHEMA.to_string(),
+            found: String::from_utf8_lossy(schema).into_owned(),
+        });
+    }
+    let axes = u32::decode(&mut take!(4)).unwrap() as usize;
+    let mut dimensions = Vec::new();
+    for _ in 0..axes {
+        let d = u64::decode(&mut take!(8)).unwrap();
+        dimensions.push(usize::try_from(d).map_err(|_| ContainerError::Corrupt)?);
+    }
+    Shape::from(&dimensions[..])
+        .checked_cell_count()
+        .ok_or(ContainerError::Corrupt)?;
+
+    let top = coarsest_stride(&dimensions);
+    let mut stride = top;
+    let mut preview: Option<Preview<T>> = None;
+    loop {
+        let Some(len) = input.get(..8).and_then(|mut b| u64::decode(&mut b)) else {
+            return Ok(preview);
+        };
+        let len = usize::try_from(len).map_err(|_| ContainerError::Corrupt)?;
+        let Some(mut level) = input.get(8..).and_then(|rest| rest.get(..len)) else {
+            return Ok(preview);
+        };
+        input = &input[8 + len..];
+
+        // Cells are only pushed as they decode, so a lying header can't
+        // force a large allocation.
+        let mut cells = Vec::new();
+        let walked = walk_level(&dimensions, stride, stride == top, |coords, seen| {
+            let val = if seen {
+                let coarse = preview.as_ref().unwrap();
+                let flat = coords
+                    .iter()
+                    .zip(&coarse.grid.dimensions)
+                    .fold(0, |i, (&c, &d)| i * d + c / 2);
+                Some(coarse.grid.grid[flat].clone())
+            } else {
+                T::decode(&mut level)
+            };
+            cells.push(val?);
+            Some(())
+        });
+        if walked.is_none() || !level.is_empty() {
+            return Err(ContainerError::Corrupt);
+        }
+
+        let level_dims = level_dimensions(&dimensions, stride);
+        preview = Some(Preview {
+            grid: Grid::from_parts(cells, level_dims),
+            stride,
+            dimensions: dimensions.clone(),
+        });
+        if stride == 1 {
+            if !input.is_empty() {
+                return Err(ContainerError::Corrupt);
+            }
+            return Ok(preview);
+        }
+        stride /= 2;
+    }
+}
+
+/// Decodes the full grid from the complete output of [`encode`].
+pub fn decode<T: Clone + ElementCodec>(bytes: &[u8]) -> Result<Grid<T>, ContainerError> {
+    match decode_prefix(bytes)? {
+        Some(preview) if preview.is_complete() => Ok(preview.grid),
+        _ => Err(ContainerError::Corrupt),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previews_refine() {
+        let grid = Grid::from_fn(vec![5, 3, 6], |c| (c[0] * 100 + c[1] * 10 + c[2]) as u32);
+        let bytes = encode(&grid);
+
+        let mut strides = Vec::new();
+        for end in 0..=bytes.len() {
+            match decode_prefix::<u32>(&bytes[..end]) {
+                Ok(Some(preview)) => {
+                    let stride = preview.stride();
+                    for (c, v) in preview.grid().iter_indexed() {
+                        let full: Vec<usize> = c.iter().map(|c| c * stride).collect();
+                        assert_eq!(grid.get_opt(&full), Some(v));
+                    }
+                    if strides.last() != Some(&stride) {
+                        strides.push(stride);
+                    }
+                }
+                Ok(None) => assert!(strides.is_empty()),
+                Err(err) => panic!("{err}"),
+            }
+        }
+        assert_eq!(strides, vec![4, 2, 1]);
+
+        let preview = decode_prefix::<u32>(&bytes[..bytes.len() - 1])
+            .unwrap()
+            .unwrap();
+        assert_eq!(preview.upscale().get_opt(&[3, 2, 5]), Some(&224));
+        assert!(decode::<u32>(&bytes).unwrap().iter().eq(grid.iter()));
+        assert!(decode::<u32>(&bytes[..bytes.len() - 1]).is_err());
+        assert!(matches!(decode_prefix::<u32>(&bytes[..2]), Ok(None)));
+        assert!(matches!(
+            decode_prefix::<u32>(b"MX"),
+            Err(ContainerError::BadMagic)
+        ));
+        assert!(matches!(
+            decode::<u16>(&bytes),
+            Err(ContainerError::SchemaMismatch { .. })
+        ));
+    }
+}
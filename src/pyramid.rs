@@ -0,0 +1,132 @@
+use crate::{Coord, Grid, GridError, Region};
+
+/// A grid together with successively halved copies of it, each cell of a
+/// coarser level summarizing up to `2^axes` cells of the level below.
+///
+/// Level 0 is the full-resolution grid; the last level has a single cell.
+/// Writes through [`GridPyramid::set`] update every coarser level.
+pub struct GridPyramid<T, R>
+where
+    R: Fn(&[&T]) -> T,
+{
+    levels: Vec<Grid<T>>,
+    reducer: R,
+}
+
+impl<T, R> GridPyramid<T, R>
+where
+    R: Fn(&[&T]) -> T,
+{
+    /// Builds the pyramid, combining each block of child cells with
+    /// `reducer` (e.g. a mean for terrain, `any` for occupancy).
+    pub fn new(base: Grid<T>, reducer: R) -> Self {
+        let mut levels = vec![base];
+        loop {
+            let below = levels.last().unwrap();
+            if below.dimensions.iter().all(|&d| d <= 1) {
+                break;
+            }
+
+            let dimensions: Vec<_> = below.dimensions.iter().map(|d| d.div_ceil(2)).collect();
+            let parents = Region::new(dimensions.iter().map(|&d| 0..d).collect());
+            let grid = parents
+                .iter()
+                .map(|coords| Self::reduce(below, &coords, &reducer))
+                .collect();
+            levels.push(Grid::from_parts(grid, dimensions));
+        }
+
+        Self { levels, reducer }
+    }
+
+    pub fn base(&self) -> &Grid<T> {
+        &self.levels[0]
+    }
+
+    pub fn levels(&self) -> &[Grid<T>] {
+        &self.levels
+    }
+
+    pub fn level(&self, level: usize) -> Option<&Grid<T>> {
+        self.levels.get(level)
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Consumes the pyramid, returning the full-resolution grid.
+    pub fn into_base(mut self) -> Grid<T> {
+        self.levels.swap_remove(0)
+    }
+
+    /// Sets a cell in the base level and recomputes its ancestors.
+    pub fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        let index = self.levels[0].translate_index(target)?;
+        self.levels[0].grid[index] = val;
+
+        let mut coords = Coord::from_slice(target);
+        for level in 1..self.levels.len() {
+            coords.iter_mut().for_each(|c| *c /= 2);
+            let reduced = Self::reduce(&self.levels[level - 1], &coords, &self.reducer);
+            let index = self.levels[level].translate_index(&coords)?;
+            self.levels[level].grid[index] = reduced;
+        }
+        Ok(())
+    }
+
+    /// The cells of level `level - 1` summarized by the cell at `target` in
+    /// `level`, for descending from coarse to fine.
+    pub fn children(&self, level: usize, target: &[usize]) -> Option<Region> {
+        let below = self.levels.get(level.checked_sub(1)?)?;
+        self.levels[level].translate_index(target).ok()?;
+        Some(Self::child_region(below, target))
+    }
+
+    fn child_region(below: &Grid<T>, parent: &[usize]) -> Region {
+        Region::new(
+            parent
+                .iter()
+                .zip(&below.dimensions)
+                .map(|(&p, &len)| 2 * p..(2 * p + 2).min(len))
+                .collect(),
+        )
+    }
+
+    fn reduce(below: &Grid<T>, parent: &[usize], reducer: &R) -> T {
+        let children: Vec<&T> = Self::child_region(below, parent)
+            .iter()
+            .map(|coords| &below.grid[below.translate_index(&coords).unwrap()])
+            .collect();
+        reducer(&children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pyramid_levels_stay_in_sync() {
+        let max = |cells: &[&u32]| cells.iter().copied().copied().max().unwrap();
+        let mut pyramid = GridPyramid::new(Grid::new(0u32, vec![5, 4]), max);
+
+        let shapes: Vec<_> = pyramid
+            .levels()
+            .iter()
+            .map(|l| l.dimensions().to_vec())
+            .collect();
+        assert_eq!(shapes, vec![vec![5, 4], vec![3, 2], vec![2, 1], vec![1, 1]]);
+
+        pyramid.set(&[4, 3], 7).unwrap();
+        assert_eq!(*pyramid.level(1).unwrap().get(&[2, 1]).unwrap(), 7);
+        assert_eq!(*pyramid.level(3).unwrap().get(&[0, 0]).unwrap(), 7);
+        assert_eq!(*pyramid.level(2).unwrap().get(&[0, 0]).unwrap(), 0);
+
+        assert_eq!(
+            pyramid.children(1, &[2, 1]),
+            Some(Region::new(vec![4..5, 2..4]))
+        );
+        assert!(pyramid.set(&[5, 0], 1).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use std::ops::Range;
+
+use crate::{Coord, Grid, GridError};
+
+/// An axis-aligned box of cells, stored as one half-open range per axis.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Region {
+    ranges: Vec<Range<usize>>,
+}
+
+impl Region {
+    pub fn new(ranges: Vec<Range<usize>>) -> Self {
+        Self { ranges }
+    }
+
+    /// The region `shape` cells long on each axis starting at `origin`. Ends
+    /// past `usize::MAX` are cut off there, which no grid reaches, so such a
+    /// region is never within one.
+    pub fn from_origin_shape(origin: &[usize], shape: &[usize]) -> Self {
+        let ranges = (origin.iter().zip(shape))
+            .map(|(&o, &s)| o..o.saturating_add(s))
+            .collect();
+        Self { ranges }
+    }
+
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    pub fn axes(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn origin(&self) -> Coord {
+        self.ranges.iter().map(|r| r.start).collect()
+    }
+
+    pub fn shape(&self) -> Vec<usize> {
+        self.ranges.iter().map(|r| r.len()).collect()
+    }
+
+    /// Number of cells covered by the region.
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|r| r.len()).product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, coords: &[usize]) -> bool {
+        coords.len() == self.axes() && self.ranges.iter().zip(coords).all(|(r, c)| r.contains(c))
+    }
+
+    /// The overlap of two regions, or `None` if they are disjoint or have a
+    /// different number of axes.
+    pub fn intersection(&self, other: &Region) -> Option<Region> {
+        if self.axes() != other.axes() {
+            return None;
+        }
+        let ranges: Vec<_> = self
+            .ranges
+            .iter()
+            .zip(&other.ranges)
+            .map(|(a, b)| a.start.max(b.start)..a.end.min(b.end))
+            .collect();
+        if ranges.iter().any(|r| r.is_empty()) {
+            return None;
+        }
+        Some(Region { ranges })
+    }
+
+    /// The smallest region containing both regions, or `None` if they have a
+    /// different number of axes.
+    pub fn union_bounds(&self, other: &Region) -> Option<Region> {
+        if self.axes() != other.axes() {
+            return None;
+        }
+        let ranges = self
+            .ranges
+            .iter()
+            .zip(&other.ranges)
+            .map(|(a, b)| a.start.min(b.start)..a.end.max(b.end))
+            .collect();
+        Some(Region { ranges })
+    }
+
+    /// Shrinks the region so it lies within a grid of the given dimensions.
+    pub fn clamp_to(&self, dimensions: &[usize]) -> Region {
+        let ranges = self
+            .ranges
+            .iter()
+            .zip(dimensions)
+            .map(|(r, &len)| r.start.min(len)..r.end.min(len))
+            .collect();
+        Region { ranges }
+    }
+
+    pub fn clamp_to_grid<T>(&self, grid: &Grid<T>) -> Region {
+        self.clamp_to(grid.dimensions())
+    }
+
+    /// Iterates over the coordinates in the region in row-major order.
+    pub fn iter(&self) -> RegionIter<'_> {
+        RegionIter {
+            region: self,
+            next: (!self.is_empty()).then(|| self.origin()),
+        }
+    }
+
+    /// Grid coordinates of a cell given relative to the region's origin,
+    /// or `None` if it lies outside the region.
+    pub(crate) fn to_grid_coords(&self, local: &[usize]) -> Option<Coord> {
+        if local.len() != self.axes() {
+            return None;
+        }
+        self.ranges
+            .iter()
+            .zip(local)
+            .map(|(r, &l)| (l < r.len()).then_some(r.start + l))
+            .collect()
+    }
+
+    /// The first cell of each run along the last axis, as a region, and the
+    /// length of those runs. Each run is contiguous in a row-major grid.
+    pub(crate) fn row_starts(&self) -> (Region, usize) {
+        let mut ranges = self.ranges.clone();
+        let row_len = match ranges.last_mut() {
+            Some(last) => {
+                let len = last.len();
+                last.end = last.start + len.min(1);
+                len
+            }
+            None => 1,
+        };
+        (Region::new(ranges), row_len)
+    }
+
+    pub(crate) fn check_within(&self, dimensions: &[usize]) -> Result<(), GridError> {
+        if self.axes() != dimensions.len() || self.clamp_to(dimensions) != *self {
+            return Err(GridError::RegionOutOfBounds {
+                ranges: self.ranges.clone(),
+                shape: dimensions.to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a Region {
+    type Item = Coord;
+    type IntoIter = RegionIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct RegionIter<'a> {
+    region: &'a Region,
+    next: Option<Coord>,
+}
+
+impl<'a> Iterator for RegionIter<'a> {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+
+        let mut next = current.clone();
+        for (axis, range) in self.region.ranges.iter().enumerate().rev() {
+            next[axis] += 1;
+            if next[axis] < range.end {
+                self.next = Some(next);
+                break;
+            }
+            next[axis] = range.start;
+        }
+
+        Some(current)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// The region covering the whole grid.
+    pub fn bounds(&self) -> Region {
+        Region::new(self.dimensions().iter().map(|&d| 0..d).collect())
+    }
+
+    /// Copies the cells inside `region` into a new grid.
+    pub fn extract(&self, region: &Region) -> Result<Grid<T>, GridError> {
+        region.check_within(self.dimensions())?;
+
+        let mut grid = Vec::with_capacity(region.len());
+        for coords in region {
+            grid.push(self.get(&coords)?.clone());
+        }
+
+        Ok(Grid::from_parts(grid, region.shape()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_ops() {
+        let a = Region::new(vec![0..4, 2..6]);
+        let b = Region::from_origin_shape(&[2, 0], &[5, 3]);
+
+        assert_eq!(a.intersection(&b), Some(Region::new(vec![2..4, 2..3])));
+        assert_eq!(a.union_bounds(&b), Some(Region::new(vec![0..7, 0..6])));
+        assert!(a.contains(&[3, 5]));
+        assert!(!a.contains(&[3, 6]));
+        assert_eq!(b.clamp_to(&[4, 4]), Region::new(vec![2..4, 0..3]));
+        let far = Region::from_origin_shape(&[usize::MAX - 1, 0], &[4, 1]);
+        assert_eq!(far.ranges(), &[usize::MAX - 1..usize::MAX, 0..1]);
+        assert!(far.check_within(&[4, 4]).is_err());
+        assert!(Grid::new(0, vec![4, 4])
+            .view(&[usize::MAX, 0], &[2, 2])
+            .is_err());
+        assert_eq!(
+            Region::new(vec![0..2, 1..3])
+                .iter()
+                .map(|c| c.to_vec())
+                .collect::<Vec<_>>(),
+            vec![vec![0, 1], vec![0, 2], vec![1, 1], vec![1, 2]]
+        );
+    }
+
+    #[test]
+    fn extract() {
+        let mut grid = Grid::new(0, vec![4, 4]);
+        grid.set(&[1, 2], 5).unwrap();
+
+        let sub = grid.extract(&Region::new(vec![1..3, 1..4])).unwrap();
+        assert_eq!(sub.dimensions(), &[2, 3]);
+        assert_eq!(*sub.get(&[0, 1]).unwrap(), 5);
+
+        assert!(grid.extract(&Region::new(vec![3..5, 0..1])).is_err());
+    }
+}
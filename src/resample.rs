@@ -0,0 +1,329 @@
+use crate::{Coord, GeoTransform, Grid, GridError, SpatialGrid};
+
+/// How [`Grid::resample`] computes each output cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResampleMethod {
+    /// The value of the source cell nearest the output cell's center.
+    Nearest,
+    /// Linear interpolation between the source cells around the output
+    /// cell's center, along every axis.
+    #[default]
+    Linear,
+    /// The mean of the source cells the output cell covers, weighted by how
+    /// much of each it covers. Best for downsampling.
+    Mean,
+}
+
+/// The resolution to resample a [`SpatialGrid`] to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// A number of cells along each axis.
+    Shape(Vec<usize>),
+    /// A cell size along each axis, rounded so a whole number of cells
+    /// covers the same extent.
+    CellSize(Vec<f64>),
+}
+
+/// For each output cell along one axis, the source cells it draws from and
+/// their weights, which sum to 1.
+fn axis_weights(src: usize, dst: usize, method: ResampleMethod) -> Vec<Vec<(usize, f64)>> {
+    if src == 0 {
+        return vec![Vec::new(); dst];
+    }
+    let scale = src as f64 / dst as f64;
+    let last = (src - 1) as f64;
+
+    (0..dst)
+        .map(|i| match method {
+            ResampleMethod::Nearest => {
+                let center = ((i as f64 + 0.5) * scale - 0.5).clamp(0.0, last);
+                vec![(center.round() as usize, 1.0)]
+            }
+            ResampleMethod::Linear => {
+                let center = ((i as f64 + 0.5) * scale - 0.5).clamp(0.0, last);
+                let lower = center.floor() as usize;
+                let t = center - lower as f64;
+                if t == 0.0 {
+                    vec![(lower, 1.0)]
+                } else {
+                    vec![(lower, 1.0 - t), (lower + 1, t)]
+                }
+            }
+            ResampleMethod::Mean => {
+                let (start, end) = (i as f64 * scale, (i + 1) as f64 * scale);
+                let first = start.floor() as usize;
+                let last = (end.ceil() as usize).min(src);
+                (first..last)
+                    .map(|j| {
+                        let overlap = end.min(j as f64 + 1.0) - start.max(j as f64);
+                        (j, overlap / scale)
+                    })
+                    .filter(|&(_, w)| w > 0.0)
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+/// Resamples `grid` along one axis to `weights.len()` cells.
+fn resample_axis(grid: &Grid<f64>, axis: usize, weights: &[Vec<(usize, f64)>]) -> Grid<f64> {
+    let mut dimensions = grid.dimensions.clone();
+    dimensions[axis] = weights.len();
+    let stride: usize = grid.dimensions[axis + 1..].iter().product();
+    let (src_len, dst_len) = (grid.dimensions[axis], weights.len());
+
+    let mut out = Grid::new(0.0, dimensions);
+    for (i, cell) in out.grid.iter_mut().enumerate() {
+        let (outer, rest) = (i / (dst_len * stride), i % (dst_len * stride));
+        let (target, inner) = (rest / stride, rest % stride);
+        let base = outer * src_len * stride + inner;
+        *cell = match weights[target].as_slice() {
+            [] => f64::NAN,
+            cells => cells
+                .iter()
+                .map(|&(j, w)| w * grid.grid[base + j * stride])
+                .sum(),
+        };
+    }
+    out
+}
+
+impl<T: Copy + Into<f64>> Grid<T> {
+    /// Resamples the grid to `shape`, covering the same extent with cells
+    /// of a different size.
+    pub fn resample(
+        &self,
+        shape: &[usize],
+        method: ResampleMethod,
+    ) -> Result<Grid<f64>, GridError> {
+        if shape.len() != self.axes {
+            return Err(GridError::DimensionMismatch {
+                coords: Coord::from_slice(shape),
+                shape: self.dimensions.clone(),
+            });
+        }
+
+        let mut out = Grid::from_parts(
+            self.grid.iter().map(|&v| v.into()).collect(),
+            self.dimensions.clone(),
+        );
+        for (axis, &len) in shape.iter().enumerate() {
+            if len != out.dimensions[axis] {
+                let weights = axis_weights(out.dimensions[axis], len, method);
+                out = resample_axis(&out, axis, &weights);
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl<T: Copy + Into<f64>> SpatialGrid<T> {
+    /// Resamples onto a different resolution covering the same extent,
+    /// adjusting the transform and cell sizes to match. Fails if a length in
+    /// the target shape is zero or a target cell size is not positive and
+    /// finite.
+    pub fn resample(
+        &self,
+        resolution: Resolution,
+        method: ResampleMethod,
+    ) -> Result<SpatialGrid<f64>, GridError> {
+        let dimensions = self.grid().dimensions();
+        let invalid = |expected| GridError::InvalidParameter {
+            name: "resolution",
+            expected,
+            shape: dimensions.to_vec(),
+        };
+        let shape = match resolution {
+            Resolution::Shape(shape) if shape.contains(&0) => return Err(invalid("nonzero")),
+            Resolution::CellSize(size) if !size.iter().all(|&s| s > 0.0 && s.is_finite()) => {
+                return Err(invalid("positive and finite"))
+            }
+            Resolution::Shape(shape) => shape,
+            Resolution::CellSize(size) if size.len() == dimensions.len() => dimensions
+                .iter()
+                .zip(self.cell_size())
+                .zip(size)
+                .map(|((&len, &old), new)| ((len as f64 * old / new).round() as usize).max(1))
+                .collect(),
+            Resolution::CellSize(size) => {
+                return Err(GridError::UnsupportedAxes {
+                    expected: size.len(),
+                    shape: dimensions.to_vec(),
+                })
+            }
+        };
+
+        let grid = self.grid().resample(&shape, method)?;
+        let ratios: Vec<f64> = dimensions
+            .iter()
+            .zip(&shape)
+            .map(|(&old, &new)| old as f64 / new as f64)
+            .collect();
+        let resampled = match self.transform() {
+            Some(t) => SpatialGrid::new(
+                grid,
+                GeoTransform {
+                    pixel_width: t.pixel_width * ratios[1],
+                    column_rotation: t.column_rotation * ratios[1],
+                    row_rotation: t.row_rotation * ratios[0],
+                    pixel_height: t.pixel_height * ratios[0],
+                    ..*t
+                },
+            )?,
+            None => {
+                let cell_size = self.cell_size().iter().zip(&ratios).map(|(s, r)| s * r);
+                SpatialGrid::with_cell_size(grid, cell_size.collect())?
+            }
+        };
+        Ok(match self.unit_label() {
+            Some(unit) => resampled.unit(unit),
+            None => resampled,
+        })
+    }
+}
+
+impl<T: Copy + Into<f64>> SpatialGrid<T> {
+    /// Pulls values into a grid aligned with `target`, which may have a
+    /// different origin, cell size or rotation. Only the target's shape and
+    /// placement are used, not its values. Output cells whose center falls
+    /// outside this grid are NaN.
+    ///
+    /// [`ResampleMethod::Mean`] is approximated by averaging a 4×4 pattern of
+    /// nearest-cell samples across each output cell.
+    pub fn reproject<U>(
+        &self,
+        target: &SpatialGrid<U>,
+        method: ResampleMethod,
+    ) -> Result<SpatialGrid<f64>, GridError> {
+        let unsupported = |shape: &[usize]| GridError::UnsupportedAxes {
+            expected: 2,
+            shape: shape.to_vec(),
+        };
+        let src = self
+            .transform()
+            .ok_or_else(|| unsupported(self.grid().dimensions()))?;
+        let dst = target
+            .transform()
+            .ok_or_else(|| unsupported(target.grid().dimensions()))?;
+        let (rows, cols) = (self.grid().dimensions[0], self.grid().dimensions[1]);
+
+        // The value at a fractional pixel position of the target.
+        let value_at = |row: f64, col: f64, method: ResampleMethod| {
+            let (x, y) = dst.apply(row, col);
+            let Some((r, c)) = src.invert(x, y) else {
+                return f64::NAN;
+            };
+            if !(0.0..rows as f64).contains(&r) || !(0.0..cols as f64).contains(&c) {
+                return f64::NAN;
+            }
+            match method {
+                ResampleMethod::Linear => {
+                    let r = (r - 0.5).clamp(0.0, rows as f64 - 1.0);
+                    let c = (c - 0.5).clamp(0.0, cols as f64 - 1.0);
+                    self.grid().sample(&[r, c]).unwrap_or(f64::NAN)
+                }
+                _ => self.grid().grid[r as usize * cols + c as usize].into(),
+            }
+        };
+
+        let mut out = Grid::new(0.0, target.grid().dimensions.clone());
+        let out_cols = out.dimensions[1];
+        for (i, cell) in out.grid.iter_mut().enumerate() {
+            let (row, col) = ((i / out_cols) as f64, (i % out_cols) as f64);
+            *cell = match method {
+                ResampleMethod::Mean => {
+                    let offsets = [0.125, 0.375, 0.625, 0.875];
+                    let samples: Vec<f64> = offsets
+                        .iter()
+                        .flat_map(|dr| offsets.iter().map(move |dc| (row + dr, col + dc)))
+                        .map(|(r, c)| value_at(r, c, ResampleMethod::Nearest))
+                        .filter(|v| !v.is_nan())
+                        .collect();
+                    samples.iter().sum::<f64>() / samples.len() as f64
+                }
+                _ => value_at(row + 0.5, col + 0.5, method),
+            };
+        }
+
+        let reprojected = SpatialGrid::new(out, *dst)?;
+        Ok(match target.unit_label() {
+            Some(unit) => reprojected.unit(unit),
+            None => reprojected,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resample_methods() {
+        let mut grid = Grid::new(0u8, vec![2, 4]);
+        for (i, v) in grid.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+
+        let mean = grid.resample(&[1, 2], ResampleMethod::Mean).unwrap();
+        assert_eq!(mean.dimensions(), &[1, 2]);
+        assert_eq!(mean.get_opt(&[0, 0]), Some(&2.5));
+        assert_eq!(mean.get_opt(&[0, 1]), Some(&4.5));
+
+        let nearest = grid.resample(&[2, 8], ResampleMethod::Nearest).unwrap();
+        assert_eq!(nearest.get_opt(&[1, 7]), Some(&7.0));
+        assert_eq!(nearest.get_opt(&[0, 2]), Some(&1.0));
+
+        let linear = grid.resample(&[2, 8], ResampleMethod::Linear).unwrap();
+        assert_eq!(linear.get_opt(&[0, 0]), Some(&0.0));
+        assert_eq!(linear.get_opt(&[0, 2]), Some(&0.75));
+        assert!(grid.resample(&[2], ResampleMethod::Linear).is_err());
+
+        let spatial =
+            SpatialGrid::new(grid, GeoTransform::north_up(0.0, 40.0, 10.0, -10.0)).unwrap();
+        let coarse = spatial
+            .resample(Resolution::CellSize(vec![20.0, 20.0]), ResampleMethod::Mean)
+            .unwrap();
+        assert_eq!(coarse.grid().dimensions(), &[1, 2]);
+        assert_eq!(coarse.cell_size(), &[20.0, 20.0]);
+        assert_eq!(coarse.cell_center(&[0, 1]).unwrap(), (30.0, 30.0));
+        for resolution in [
+            Resolution::Shape(vec![0, 2]),
+            Resolution::CellSize(vec![0.0, 20.0]),
+            Resolution::CellSize(vec![20.0, f64::INFINITY]),
+        ] {
+            assert!(matches!(
+                spatial.resample(resolution, ResampleMethod::Mean),
+                Err(GridError::InvalidParameter { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn reproject_between_grids() {
+        let mut grid = Grid::new(0.0, vec![2, 2]);
+        grid.set(&[0, 1], 1.0).unwrap();
+        grid.set(&[1, 1], 3.0).unwrap();
+        let src = SpatialGrid::new(grid, GeoTransform::north_up(0.0, 20.0, 10.0, -10.0)).unwrap();
+
+        // Shifted by half a cell and twice as fine.
+        let target = SpatialGrid::new(
+            Grid::new(0u8, vec![2, 4]),
+            GeoTransform::north_up(5.0, 15.0, 5.0, -5.0),
+        )
+        .unwrap();
+        let nearest = src.reproject(&target, ResampleMethod::Nearest).unwrap();
+        let values: Vec<f64> = nearest.grid().iter().copied().collect();
+        assert_eq!(values[..3], [0.0, 1.0, 1.0]);
+        assert!(values[3].is_nan());
+        assert_eq!(values[4..7], [0.0, 3.0, 3.0]);
+
+        let linear = src.reproject(&target, ResampleMethod::Linear).unwrap();
+        assert_eq!(linear.grid().get_opt(&[1, 1]), Some(&1.875));
+
+        let untransformed =
+            SpatialGrid::with_cell_size(Grid::new(0.0, vec![2]), vec![1.0]).unwrap();
+        assert!(untransformed
+            .reproject(&target, ResampleMethod::Nearest)
+            .is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{Grid, GridError, Shape};
+
+impl<T: Clone> Grid<T> {
+    /// Grows or shrinks `axis` to `new_len`, keeping the cells whose
+    /// coordinate along it stays below `new_len` where they are and filling
+    /// new cells with `fill`. Growing or shrinking the first axis reuses the
+    /// existing buffer.
+    pub fn resize_axis(&mut self, axis: usize, new_len: usize, fill: T) -> Result<(), GridError> {
+        if axis >= self.axes {
+            return Err(GridError::InvalidAxis {
+                axis,
+                shape: self.dimensions.clone(),
+            });
+        }
+        let old_len = self.dimensions[axis];
+        let inner: usize = self.dimensions[axis + 1..].iter().product();
+        let outer: usize = self.dimensions[..axis].iter().product();
+
+        if axis == 0 {
+            self.grid.resize(new_len * inner, fill);
+        } else if old_len != new_len {
+            let (old_block, new_block) = (old_len * inner, new_len * inner);
+            let kept = old_block.min(new_block);
+            let mut grid = Vec::with_capacity(outer * new_block);
+            for o in 0..outer {
+                grid.extend_from_slice(&self.grid[o * old_block..][..kept]);
+                grid.resize(grid.len() + new_block - kept, fill.clone());
+            }
+            self.grid = grid;
+        }
+        self.dimensions[axis] = new_len;
+        self.strides = Shape::from(&self.dimensions[..]).strides();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize_axes() {
+        let mut map = Grid::from_fn(vec![2, 3], |c| c[0] * 10 + c[1]);
+        map.resize_axis(1, 5, 9).unwrap();
+        assert_eq!(map.dimensions(), &[2, 5]);
+        assert!(map.iter().copied().eq([0, 1, 2, 9, 9, 10, 11, 12, 9, 9]));
+
+        map.resize_axis(0, 3, 7).unwrap();
+        map.resize_axis(1, 2, 0).unwrap();
+        assert!(map.iter().copied().eq([0, 1, 10, 11, 7, 7]));
+
+        map.resize_axis(1, 0, 0).unwrap();
+        map.resize_axis(1, 1, 4).unwrap();
+        assert!(map.iter().copied().eq([4, 4, 4]));
+        assert!(map.resize_axis(2, 1, 0).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use serde::{
+    de::{self, Unexpected},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{container::ElementTag, Grid, Shape, TaggedGrid};
+
+/// Grids are written with their cell type's [`ElementTag`] as an `element`
+/// field, such as `"f32"` or `"my_game::Tile"`, and reading one as a
+/// different type fails instead of reinterpreting the data. Data without a
+/// tag is still accepted from self-describing formats.
+impl<T: Serialize + ElementTag> Serialize for Grid<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Grid", 3)?;
+        state.serialize_field("dimensions", &self.dimensions)?;
+        state.serialize_field("element", &Some(T::TAG))?;
+        state.serialize_field("data", &self.grid)?;
+        state.end()
+    }
+}
+
+/// The serialized form of a grid, before its length is checked.
+#[derive(Deserialize)]
+#[serde(rename = "Grid")]
+struct GridData<T> {
+    dimensions: Vec<usize>,
+    #[serde(default)]
+    element: Option<String>,
+    data: Vec<T>,
+}
+
+impl<'de, T: Deserialize<'de> + ElementTag> Deserialize<'de> for Grid<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let GridData {
+            dimensions,
+            element,
+            data,
+        } = GridData::deserialize(deserializer)?;
+        if let Some(element) = element.filter(|e| e != T::TAG) {
+            return Err(de::Error::invalid_value(Unexpected::Str(&element), &T::TAG));
+        }
+        let expected = Shape::from(&dimensions[..]).checked_cell_count();
+        if expected != Some(data.len()) {
+            return Err(de::Error::invalid_length(
+                data.len(),
+                &"one value per cell of `dimensions`",
+            ));
+        }
+
+        Ok(Grid::from_parts(data, dimensions))
+    }
+}
+
+/// Tagged grids are written as the grid and a map from each region's name to
+/// its ranges. Reading fails if a region isn't inside the grid.
+impl<T: Serialize + ElementTag> Serialize for TaggedGrid<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let regions: BTreeMap<_, _> = self
+            .regions()
+            .map(|(name, region)| (name, region.ranges()))
+            .collect();
+        let mut state = serializer.serialize_struct("TaggedGrid", 2)?;
+        state.serialize_field("grid", self.grid())?;
+        state.serialize_field("regions", &regions)?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(
+    rename = "TaggedGrid",
+    bound(deserialize = "T: Deserialize<'de> + ElementTag")
+)]
+struct TaggedGridData<T> {
+    grid: Grid<T>,
+    #[serde(default)]
+    regions: BTreeMap<String, Vec<Range<usize>>>,
+}
+
+impl<'de, T: Deserialize<'de> + ElementTag> Deserialize<'de> for TaggedGrid<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let TaggedGridData { grid, regions } = TaggedGridData::deserialize(deserializer)?;
+        let mut tagged = TaggedGrid::new(grid);
+        for (name, ranges) in regions {
+            tagged
+                .register_region(name, ranges)
+                .map_err(de::Error::custom)?;
+        }
+        Ok(tagged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trip() {
+        let mut grid = Grid::new(0i32, vec![2, 3]);
+        grid.set(&[1, 2], 7).unwrap();
+
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(
+            json,
+            r#"{"dimensions":[2,3],"element":"i32","data":[0,0,0,0,0,7]}"#
+        );
+        let back: Grid<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.dimensions(), grid.dimensions());
+        assert!(back.iter().eq(grid.iter()));
+
+        let err = serde_json::from_str::<Grid<f32>>(&json).unwrap_err();
+        assert!(err.to_string().contains("expected f32"), "{err}");
+        let untagged = r#"{"dimensions":[1,2],"data":[1.5,2.5]}"#;
+        assert!(serde_json::from_str::<Grid<f32>>(untagged).is_ok());
+
+        let short = r#"{"dimensions":[2,3],"data":[0,0,0]}"#;
+        assert!(serde_json::from_str::<Grid<i32>>(short).is_err());
+        let overflow = r#"{"dimensions":[18446744073709551615,2],"data":[]}"#;
+        assert!(serde_json::from_str::<Grid<i32>>(overflow).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Tile(u8);
+
+    impl ElementTag for Tile {
+        const TAG: &'static str = "test::Tile";
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let grid = Grid::from_fn(vec![2, 2], |c| Tile((c[0] * 2 + c[1]) as u8));
+        let bytes = bincode::serialize(&grid).unwrap();
+        let back: Grid<Tile> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(back.dimensions(), grid.dimensions());
+        assert!(back.iter().eq(grid.iter()));
+        assert!(bincode::deserialize::<Grid<u8>>(&bytes).is_err());
+
+        let floats = bincode::serialize(&Grid::new(1.5f32, vec![3])).unwrap();
+        assert!(bincode::deserialize::<Grid<u32>>(&floats).is_err());
+        assert!(bincode::deserialize::<Grid<f32>>(&floats).is_ok());
+    }
+
+    #[test]
+    fn tagged_round_trip() {
+        let mut level = TaggedGrid::new(Grid::new(0u8, vec![2, 2]));
+        level.register_region("spawn", vec![0..1, 0..2]).unwrap();
+
+        let json = serde_json::to_string(&level).unwrap();
+        assert!(json.ends_with(r#""regions":{"spawn":[{"start":0,"end":1},{"start":0,"end":2}]}}"#));
+        let back: TaggedGrid<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.region("spawn"), level.region("spawn"));
+
+        let outside = json.replace(r#""end":2"#, r#""end":3"#);
+        let err = serde_json::from_str::<TaggedGrid<u8>>(&outside).unwrap_err();
+        assert!(err.to_string().contains("not within grid"), "{err}");
+    }
+}
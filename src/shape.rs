@@ -0,0 +1,208 @@
+use std::{fmt, ops::Deref};
+
+use crate::{false_index, Coord, Grid, GridError};
+
+/// The dimensions of a grid, with the checks and index arithmetic that
+/// operations combining several grids need.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Shape(Vec<usize>);
+
+impl Shape {
+    pub fn new(dimensions: Vec<usize>) -> Self {
+        Self(dimensions)
+    }
+
+    pub fn axes(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Total number of cells.
+    pub fn cell_count(&self) -> usize {
+        self.0.iter().product()
+    }
+
+    /// Total number of cells, or `None` if it overflows `usize`.
+    pub fn checked_cell_count(&self) -> Option<usize> {
+        self.0.iter().try_fold(1usize, |n, &d| n.checked_mul(d))
+    }
+
+    /// Row-major flat index of `coords`, or `None` if they are outside the
+    /// shape.
+    pub fn flat_index(&self, coords: &[usize]) -> Option<usize> {
+        if coords.len() != self.0.len() {
+            return None;
+        }
+        coords
+            .iter()
+            .zip(&self.0)
+            .try_fold(0usize, |index, (&c, &len)| {
+                if c >= len {
+                    return None;
+                }
+                index.checked_mul(len)?.checked_add(c)
+            })
+    }
+
+    /// Coordinates of the cell at a row-major flat index, or `None` if the
+    /// index is past the last cell.
+    pub fn coords_of(&self, index: usize) -> Option<Coord> {
+        if index >= self.checked_cell_count().unwrap_or(usize::MAX) {
+            return None;
+        }
+        Some(false_index(index, &self.0))
+    }
+
+    pub fn into_vec(self) -> Vec<usize> {
+        self.0
+    }
+
+    /// The stride of each axis, in cells, for row-major storage: the cell at
+    /// `coords` is at flat index `sum(coords[i] * strides[i])`.
+    pub fn strides(&self) -> Vec<usize> {
+        let mut strides = vec![0; self.0.len()];
+        let mut stride = 1;
+        for (s, &d) in strides.iter_mut().zip(&self.0).rev() {
+            *s = stride;
+            stride *= d;
+        }
+        strides
+    }
+
+    /// Checks that `other` is the same shape, returning
+    /// [`GridError::ShapeMismatch`] otherwise.
+    pub fn check_eq(&self, other: &[usize]) -> Result<(), GridError> {
+        if self.0 != other {
+            return Err(GridError::ShapeMismatch {
+                expected: self.0.clone(),
+                got: other.to_vec(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The shape both `self` and `other` broadcast to, aligning axes from
+    /// the last and stretching axes of length 1, or `None` if they can't.
+    pub fn broadcast(&self, other: &[usize]) -> Option<Shape> {
+        let axes = self.0.len().max(other.len());
+        let padded = |dims: &[usize], axis: usize| {
+            (axis + dims.len()).checked_sub(axes).map_or(1, |i| dims[i])
+        };
+
+        (0..axes)
+            .map(|axis| match (padded(&self.0, axis), padded(other, axis)) {
+                (a, b) if a == b || b == 1 => Some(a),
+                (1, b) => Some(b),
+                _ => None,
+            })
+            .collect::<Option<_>>()
+            .map(Shape)
+    }
+
+    pub fn is_broadcast_compatible(&self, other: &[usize]) -> bool {
+        self.broadcast(other).is_some()
+    }
+}
+
+impl Deref for Shape {
+    type Target = [usize];
+
+    fn deref(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+impl From<Vec<usize>> for Shape {
+    fn from(dimensions: Vec<usize>) -> Self {
+        Self(dimensions)
+    }
+}
+
+impl From<&[usize]> for Shape {
+    fn from(dimensions: &[usize]) -> Self {
+        Self(dimensions.to_vec())
+    }
+}
+
+impl PartialEq<[usize]> for Shape {
+    fn eq(&self, other: &[usize]) -> bool {
+        self.0 == other
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, d) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "x")?;
+            }
+            write!(f, "{d}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn shape(&self) -> Shape {
+        Shape::from(&self.dimensions[..])
+    }
+}
+
+/// Asserts in debug builds that grids (or anything with a `dimensions()`
+/// method) all have the same shape. Like `debug_assert!`, the check is
+/// compiled out of release builds.
+#[macro_export]
+macro_rules! debug_assert_same_shape {
+    ($first:expr $(, $rest:expr)+ $(,)?) => {
+        if cfg!(debug_assertions) {
+            match &$first {
+                first => {
+                    let expected = first.dimensions();
+                    $(
+                        match &$rest {
+                            rest => assert!(
+                                expected == rest.dimensions(),
+                                "shape mismatch: expected {:?}, got {:?}",
+                                expected,
+                                rest.dimensions()
+                            ),
+                        }
+                    )+
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strides_and_broadcasting() {
+        let shape = Shape::new(vec![2, 3, 4]);
+        assert_eq!(shape.strides(), vec![12, 4, 1]);
+        assert_eq!(shape.cell_count(), 24);
+        assert_eq!(shape.to_string(), "2x3x4");
+
+        assert_eq!(shape.broadcast(&[3, 1]), Some(Shape::new(vec![2, 3, 4])));
+        assert_eq!(
+            Shape::new(vec![5, 1]).broadcast(&[1, 6]),
+            Some(Shape::new(vec![5, 6]))
+        );
+        assert!(!shape.is_broadcast_compatible(&[2, 3]));
+
+        assert_eq!(shape.flat_index(&[1, 2, 3]), Some(23));
+        assert_eq!(shape.coords_of(23).unwrap()[..], [1, 2, 3]);
+        assert_eq!(shape.coords_of(24), None);
+        assert_eq!(shape.flat_index(&[2, 0, 0]), None);
+        let huge = Shape::new(vec![usize::MAX, 3]);
+        assert_eq!(huge.checked_cell_count(), None);
+        assert_eq!(huge.flat_index(&[usize::MAX - 1, 2]), None);
+
+        let grid = Grid::new(0, vec![2, 3, 4]);
+        assert_eq!(grid.shape(), shape);
+        assert!(shape.check_eq(grid.dimensions()).is_ok());
+        assert!(shape.check_eq(&[2, 3]).is_err());
+        debug_assert_same_shape!(grid, grid.clone());
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use crate::{error::check_coords, Grid, GridError, Shape};
+
+/// An immutable grid in shared storage. Clones are cheap and point at the
+/// same cells, so many threads can each hold one; the first write through a
+/// clone that isn't the only one copies the cells for it.
+///
+/// ```
+/// use md_grid::{ArcGrid, Grid};
+///
+/// let map = ArcGrid::from(Grid::new(0u8, vec![512, 512]));
+/// let reader = map.clone();
+/// assert!(map.ptr_eq(&reader));
+///
+/// let mut edited = map.clone();
+/// edited.set(&[3, 4], 9).unwrap();
+/// assert!(!edited.ptr_eq(&map));
+/// assert_eq!(reader.get(&[3, 4]), Ok(&0));
+/// ```
+#[derive(Debug)]
+pub struct ArcGrid<T> {
+    cells: Arc<[T]>,
+    dimensions: Arc<[usize]>,
+}
+
+impl<T> Clone for ArcGrid<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cells: Arc::clone(&self.cells),
+            dimensions: Arc::clone(&self.dimensions),
+        }
+    }
+}
+
+impl<T> ArcGrid<T> {
+    pub fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    fn index(&self, target: &[usize]) -> Result<usize, GridError> {
+        check_coords(target, &self.dimensions)?;
+        Ok(Shape::from(&self.dimensions[..])
+            .flat_index(target)
+            .expect("checked against the dimensions"))
+    }
+
+    pub fn get(&self, target: &[usize]) -> Result<&T, GridError> {
+        Ok(&self.cells[self.index(target)?])
+    }
+
+    pub fn get_opt(&self, target: &[usize]) -> Option<&T> {
+        let index = Shape::from(&self.dimensions[..]).flat_index(target)?;
+        Some(&self.cells[index])
+    }
+
+    /// Iterates over all cells in row-major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    /// The cells in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Whether both grids share the same cells.
+    pub fn ptr_eq(&self, other: &ArcGrid<T>) -> bool {
+        Arc::ptr_eq(&self.cells, &other.cells)
+    }
+
+    /// Whether other clones share the cells, so a write would copy them.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.cells) > 1
+    }
+}
+
+impl<T: Clone> ArcGrid<T> {
+    /// The cells for writing, copying them first if other clones share them.
+    pub fn make_mut(&mut self) -> &mut [T] {
+        Arc::make_mut(&mut self.cells)
+    }
+
+    /// Mutable access to a cell, copying the cells first if other clones
+    /// share them.
+    pub fn get_mut(&mut self, target: &[usize]) -> Result<&mut T, GridError> {
+        let index = self.index(target)?;
+        Ok(&mut self.make_mut()[index])
+    }
+
+    pub fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        *self.get_mut(target)? = val;
+        Ok(())
+    }
+
+    /// Copies the cells into an owned [`Grid`].
+    pub fn to_grid(&self) -> Grid<T> {
+        Grid::from_parts(self.cells.to_vec(), self.dimensions.to_vec())
+    }
+}
+
+impl<T> From<Grid<T>> for ArcGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        Self {
+            cells: grid.grid.into(),
+            dimensions: grid.dimensions.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn shared_across_threads() {
+        let map = ArcGrid::from(Grid::from_fn(vec![64, 64], |c| c[0] * 64 + c[1]));
+        let readers: Vec<_> = (0..4)
+            .map(|row| {
+                let map = map.clone();
+                thread::spawn(move || (0..64).map(|col| map.get(&[row, col]).unwrap()).sum())
+            })
+            .collect();
+        let sums: Vec<usize> = readers.into_iter().map(|r| r.join().unwrap()).collect();
+        assert_eq!(sums[1], (64..128).sum::<usize>());
+        assert!(!map.is_shared());
+
+        let mut copy = map.clone();
+        assert!(copy.is_shared());
+        copy.make_mut()[0] = 100;
+        assert_eq!(map.get_opt(&[0, 0]), Some(&0));
+        assert!(copy.get(&[64, 0]).is_err());
+
+        let grid = copy.to_grid();
+        assert_eq!(grid.get_opt(&[0, 0]), Some(&100));
+    }
+}
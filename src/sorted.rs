@@ -0,0 +1,60 @@
+use std::cmp::Reverse;
+
+use crate::{false_index, Coord, Grid};
+
+impl<T: Ord> Grid<T> {
+    /// Every cell from the smallest value to the largest, with its
+    /// coordinates. Equal values keep row-major order.
+    pub fn iter_sorted_by_value(&self) -> impl Iterator<Item = (Coord, &T)> {
+        let mut order: Vec<usize> = (0..self.grid.len()).collect();
+        order.sort_by_key(|&i| &self.grid[i]);
+        order
+            .into_iter()
+            .map(|i| (false_index(i, &self.dimensions), &self.grid[i]))
+    }
+
+    /// The `k` largest cells, largest first, with their coordinates. Equal
+    /// values keep row-major order.
+    pub fn top_k(&self, k: usize) -> Vec<(Coord, &T)> {
+        let mut order: Vec<usize> = (0..self.grid.len()).collect();
+        let key = |&i: &usize| (Reverse(&self.grid[i]), i);
+        if k < order.len() {
+            order.select_nth_unstable_by_key(k, key);
+            order.truncate(k);
+        }
+        order.sort_unstable_by_key(key);
+        order
+            .into_iter()
+            .map(|i| (false_index(i, &self.dimensions), &self.grid[i]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_by_value() {
+        let mut elevation = Grid::new(5, vec![2, 3]);
+        elevation.set(&[0, 2], 1).unwrap();
+        elevation.set(&[1, 0], 9).unwrap();
+        elevation.set(&[1, 1], 9).unwrap();
+
+        let rising: Vec<_> = elevation
+            .iter_sorted_by_value()
+            .map(|(coords, &v)| (coords.to_vec(), v))
+            .collect();
+        assert_eq!(rising[0], (vec![0, 2], 1));
+        assert_eq!(rising[1], (vec![0, 0], 5));
+        assert_eq!(rising[5], (vec![1, 1], 9));
+
+        let peaks = elevation.top_k(3);
+        assert_eq!(peaks.len(), 3);
+        assert_eq!((peaks[0].0.to_vec(), *peaks[0].1), (vec![1, 0], 9));
+        assert_eq!((peaks[1].0.to_vec(), *peaks[1].1), (vec![1, 1], 9));
+        assert_eq!((peaks[2].0.to_vec(), *peaks[2].1), (vec![0, 0], 5));
+        assert_eq!(elevation.top_k(10).len(), 6);
+        assert!(elevation.top_k(0).is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use crate::{error::check_coords, false_index, Coord, Grid, GridError};
+
+/// A grid that only stores cells that have been set, falling back to a
+/// default value everywhere else.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Vec<usize>, T>,
+    default_value: T,
+    dimensions: Vec<usize>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default_value: T, dimensions: Vec<usize>) -> Self {
+        Self {
+            cells: HashMap::new(),
+            default_value,
+            dimensions,
+        }
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default_value
+    }
+
+    /// Number of explicitly stored cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, target: &[usize]) -> Result<&T, GridError> {
+        self.check_bounds(target)?;
+        Ok(self.cells.get(target).unwrap_or(&self.default_value))
+    }
+
+    pub fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        self.check_bounds(target)?;
+        self.cells.insert(target.to_vec(), val);
+        Ok(())
+    }
+
+    /// Removes a stored cell, returning it to the default value.
+    pub fn remove(&mut self, target: &[usize]) -> Result<Option<T>, GridError> {
+        self.check_bounds(target)?;
+        Ok(self.cells.remove(target))
+    }
+
+    /// Iterates over the stored cells in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[usize], &T)> {
+        self.cells.iter().map(|(k, v)| (&k[..], v))
+    }
+
+    /// Iterates over the stored cells in row-major coordinate order, matching
+    /// the order of [`Grid::iter`](crate::Grid::iter).
+    pub fn iter_sorted(&self) -> std::vec::IntoIter<(&[usize], &T)> {
+        let mut cells: Vec<_> = self.iter().collect();
+        cells.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        cells.into_iter()
+    }
+
+    fn check_bounds(&self, target: &[usize]) -> Result<(), GridError> {
+        check_coords(target, &self.dimensions)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Collects the coordinates and values of every cell matching
+    /// `predicate`, in row-major order.
+    pub fn to_points<P>(&self, mut predicate: P) -> Vec<(Coord, &T)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.grid
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| predicate(v))
+            .map(|(i, v)| (false_index(i, &self.dimensions), v))
+            .collect()
+    }
+
+    /// Like [`Grid::to_points`], but consumes the grid to avoid cloning.
+    pub fn into_points<P>(self, mut predicate: P) -> Vec<(Coord, T)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let dimensions = self.dimensions;
+        self.grid
+            .into_iter()
+            .enumerate()
+            .filter(|(_, v)| predicate(v))
+            .map(|(i, v)| (false_index(i, &dimensions), v))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn iter_sorted() {
+        let mut grid = SparseGrid::new(0, vec![10, 10]);
+        grid.set(&[5, 1], 3).unwrap();
+        grid.set(&[0, 9], 1).unwrap();
+        grid.set(&[5, 0], 2).unwrap();
+
+        let sorted: Vec<_> = grid.iter_sorted().map(|(_, v)| *v).collect();
+        assert_eq!(sorted, vec![1, 2, 3]);
+
+        assert_eq!(*grid.get(&[9, 9]).unwrap(), 0);
+        assert!(grid.set(&[10, 0], 1).is_err());
+    }
+
+    #[test]
+    fn to_points() {
+        let mut grid = Grid::new(0, vec![2, 3]);
+        grid.set(&[0, 2], 4).unwrap();
+        grid.set(&[1, 0], 8).unwrap();
+
+        assert_eq!(
+            grid.to_points(|&v| v != 0),
+            vec![(smallvec![0, 2], &4), (smallvec![1, 0], &8)]
+        );
+        assert_eq!(grid.into_points(|&v| v > 4), vec![(smallvec![1, 0], 8)]);
+    }
+}
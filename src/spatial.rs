@@ -0,0 +1,300 @@
+use crate::{smallvec, Coord, Grid, GridError, GridViewMut};
+
+/// Affine mapping from pixel space to world coordinates, in GDAL's
+/// convention: for a fractional pixel position (`row`, `col`),
+///
+/// ```text
+/// x = origin_x + col * pixel_width + row * row_rotation
+/// y = origin_y + col * column_rotation + row * pixel_height
+/// ```
+///
+/// Pixel `(0, 0)` is the outer corner of the first cell, not its center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoTransform {
+    pub origin_x: f64,
+    pub pixel_width: f64,
+    pub row_rotation: f64,
+    pub origin_y: f64,
+    pub column_rotation: f64,
+    pub pixel_height: f64,
+}
+
+impl GeoTransform {
+    /// A transform without rotation. `pixel_height` is usually negative, as
+    /// rows run southwards from a northern origin.
+    pub fn north_up(origin_x: f64, origin_y: f64, pixel_width: f64, pixel_height: f64) -> Self {
+        Self {
+            origin_x,
+            pixel_width,
+            row_rotation: 0.0,
+            origin_y,
+            column_rotation: 0.0,
+            pixel_height,
+        }
+    }
+
+    /// Builds a transform from GDAL's six-coefficient array.
+    pub fn from_gdal(gt: [f64; 6]) -> Self {
+        Self {
+            origin_x: gt[0],
+            pixel_width: gt[1],
+            row_rotation: gt[2],
+            origin_y: gt[3],
+            column_rotation: gt[4],
+            pixel_height: gt[5],
+        }
+    }
+
+    pub fn to_gdal(&self) -> [f64; 6] {
+        [
+            self.origin_x,
+            self.pixel_width,
+            self.row_rotation,
+            self.origin_y,
+            self.column_rotation,
+            self.pixel_height,
+        ]
+    }
+
+    /// World coordinates `(x, y)` of a fractional pixel position.
+    pub fn apply(&self, row: f64, col: f64) -> (f64, f64) {
+        (
+            self.origin_x + col * self.pixel_width + row * self.row_rotation,
+            self.origin_y + col * self.column_rotation + row * self.pixel_height,
+        )
+    }
+
+    /// Fractional pixel position `(row, col)` of world coordinates, or `None`
+    /// if the transform is degenerate.
+    pub fn invert(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        let det = self.pixel_width * self.pixel_height - self.row_rotation * self.column_rotation;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (dx, dy) = (x - self.origin_x, y - self.origin_y);
+        let col = (dx * self.pixel_height - dy * self.row_rotation) / det;
+        let row = (dy * self.pixel_width - dx * self.column_rotation) / det;
+        Some((row, col))
+    }
+}
+
+/// Fractional positions this close to a cell edge are treated as on it, so
+/// round-off from a transform doesn't pick the cell before the edge.
+const EDGE_TOLERANCE: f64 = 1e-9;
+
+/// The cell containing fractional position `pos` along an axis of length
+/// `len`, and how far into that cell it lies, from 0 up to but not
+/// including 1. `None` if the position is off the axis or not finite.
+pub(crate) fn split_position(pos: f64, len: usize) -> Option<(usize, f64)> {
+    let snapped = pos.round();
+    let pos = if (pos - snapped).abs() < EDGE_TOLERANCE {
+        snapped
+    } else {
+        pos
+    };
+    let cell = pos.floor();
+    (cell >= 0.0 && cell < len as f64).then_some((cell as usize, pos - cell))
+}
+
+/// A grid with physical cell sizes along each axis and, for 2D grids, a
+/// [`GeoTransform`] placing it in world coordinates.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid<T> {
+    grid: Grid<T>,
+    transform: Option<GeoTransform>,
+    cell_size: Vec<f64>,
+    unit: Option<String>,
+}
+
+impl<T> SpatialGrid<T> {
+    /// Places a 2D grid in world coordinates. Cell sizes are taken from the
+    /// transform: the distance moved by one row, then by one column.
+    pub fn new(grid: Grid<T>, transform: GeoTransform) -> Result<Self, GridError> {
+        if grid.axes != 2 {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: grid.dimensions.clone(),
+            });
+        }
+        let cell_size = vec![
+            transform.row_rotation.hypot(transform.pixel_height),
+            transform.pixel_width.hypot(transform.column_rotation),
+        ];
+        Ok(Self {
+            grid,
+            transform: Some(transform),
+            cell_size,
+            unit: None,
+        })
+    }
+
+    /// A grid of any number of axes with `cell_size[axis]` between cells
+    /// along each axis. A 2D grid gets a north-up transform with its first
+    /// cell's corner at the origin and rows running in +y.
+    pub fn with_cell_size(grid: Grid<T>, cell_size: Vec<f64>) -> Result<Self, GridError> {
+        if cell_size.len() != grid.axes {
+            return Err(GridError::UnsupportedAxes {
+                expected: cell_size.len(),
+                shape: grid.dimensions.clone(),
+            });
+        }
+        let transform =
+            (grid.axes == 2).then(|| GeoTransform::north_up(0.0, 0.0, cell_size[1], cell_size[0]));
+        Ok(Self {
+            grid,
+            transform,
+            cell_size,
+            unit: None,
+        })
+    }
+
+    /// Labels the unit cell sizes (and world coordinates) are measured in.
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// Write access to the cells. It is a view rather than the grid
+    /// itself, so the grid keeps the shape its cell sizes and transform
+    /// describe.
+    pub fn grid_mut(&mut self) -> GridViewMut<'_, T> {
+        self.grid.whole_view_mut()
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    /// The transform to world coordinates; `None` unless the grid is 2D.
+    pub fn transform(&self) -> Option<&GeoTransform> {
+        self.transform.as_ref()
+    }
+
+    /// Distance between neighboring cells along each axis.
+    pub fn cell_size(&self) -> &[f64] {
+        &self.cell_size
+    }
+
+    pub fn unit_label(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    fn require_transform(&self) -> Result<&GeoTransform, GridError> {
+        self.transform
+            .as_ref()
+            .ok_or_else(|| GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.grid.dimensions.clone(),
+            })
+    }
+
+    /// World coordinates of the center of the cell at `target`.
+    pub fn cell_center(&self, target: &[usize]) -> Result<(f64, f64), GridError> {
+        let transform = self.require_transform()?;
+        self.grid.translate_index(target)?;
+        Ok(transform.apply(target[0] as f64 + 0.5, target[1] as f64 + 0.5))
+    }
+
+    /// The cell containing the world point `(x, y)`, if it is on the grid.
+    pub fn cell_at(&self, x: f64, y: f64) -> Option<Coord> {
+        self.pick(x, y).map(|(cell, _)| cell)
+    }
+
+    /// The cell containing the world point `(x, y)`, if it is on the grid,
+    /// and where in that cell the point lies as `(row, col)` fractions from
+    /// 0 up to but not including 1, measured from the cell's first corner.
+    /// A point on the edge between two cells picks the one it is the first
+    /// corner or edge of.
+    pub fn pick(&self, x: f64, y: f64) -> Option<(Coord, (f64, f64))> {
+        let (row, col) = self.transform?.invert(x, y)?;
+        let (row, row_offset) = split_position(row, self.grid.dimensions[0])?;
+        let (col, col_offset) = split_position(col, self.grid.dimensions[1])?;
+        Some((smallvec![row, col], (row_offset, col_offset)))
+    }
+}
+
+impl<T: Copy + Into<f64>> SpatialGrid<T> {
+    /// [`Grid::gradient`] using this grid's cell sizes.
+    pub fn gradient(&self) -> Vec<Grid<f64>> {
+        self.grid
+            .gradient(&self.cell_size)
+            .expect("the shape is fixed to match the cell sizes")
+    }
+
+    /// [`Grid::laplacian`] using this grid's cell sizes.
+    pub fn laplacian(&self) -> Grid<f64> {
+        self.grid
+            .laplacian(&self.cell_size)
+            .expect("the shape is fixed to match the cell sizes")
+    }
+
+    /// [`Grid::slope`] using this grid's cell sizes.
+    pub fn slope(&self) -> Grid<f64> {
+        self.grid
+            .slope(&self.cell_size)
+            .expect("the shape is fixed to match the cell sizes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn world_coordinates() {
+        let transform = GeoTransform::north_up(100.0, 50.0, 10.0, -5.0);
+        let spatial = SpatialGrid::new(Grid::new(0.0, vec![4, 3]), transform).unwrap();
+
+        assert_eq!(spatial.cell_center(&[0, 0]).unwrap(), (105.0, 47.5));
+        assert_eq!(spatial.cell_center(&[3, 2]).unwrap(), (125.0, 32.5));
+        assert_eq!(spatial.cell_at(125.0, 32.5), Some(smallvec![3, 2]));
+        assert_eq!(spatial.cell_at(99.0, 45.0), None);
+        assert_eq!(spatial.cell_at(110.0, 30.0), None);
+
+        // Exactly on a corner, and a hair short of it after round-off.
+        let (cell, offset) = spatial.pick(120.0, 40.0).unwrap();
+        assert_eq!((cell, offset), (smallvec![2, 2], (0.0, 0.0)));
+        let (cell, offset) = spatial.pick(120.0 - 1e-12, 42.5).unwrap();
+        assert_eq!((cell, offset), (smallvec![1, 2], (0.5, 0.0)));
+        assert_eq!(spatial.pick(130.0, 40.0), None);
+        assert_eq!(spatial.pick(f64::NAN, 40.0), None);
+
+        let rotated = GeoTransform::from_gdal([1.0, 2.0, 0.5, 3.0, -0.25, -1.5]);
+        let (x, y) = rotated.apply(2.0, 7.0);
+        let (row, col) = rotated.invert(x, y).unwrap();
+        assert!((row - 2.0).abs() < 1e-9 && (col - 7.0).abs() < 1e-9);
+
+        assert!(SpatialGrid::new(Grid::new(0.0, vec![4]), transform).is_err());
+        assert_eq!(spatial.cell_size(), &[5.0, 10.0]);
+    }
+
+    #[test]
+    fn anisotropic_cells() {
+        let mut grid = Grid::new(0.0, vec![2, 2, 3]);
+        for (i, v) in grid.iter_mut().enumerate() {
+            *v = (i % 3) as f64;
+        }
+        let spatial = SpatialGrid::with_cell_size(grid, vec![1.0, 1.0, 0.5])
+            .unwrap()
+            .unit("m");
+        assert_eq!(spatial.unit_label(), Some("m"));
+        assert!(spatial.transform().is_none());
+        assert!(spatial.cell_center(&[0, 0, 0]).is_err());
+
+        let mut spatial = spatial;
+        spatial.grid_mut().set(&[1, 1, 1], 1.0).unwrap();
+        assert_eq!(spatial.grid().dimensions(), &[2, 2, 3]);
+        let gradient = spatial.gradient();
+        assert!(gradient[2].iter().all(|&d| d == 2.0));
+        assert!(gradient[0].iter().all(|&d| d == 0.0));
+
+        let flat = SpatialGrid::with_cell_size(Grid::new(0u8, vec![2, 3]), vec![2.0, 4.0]).unwrap();
+        assert_eq!(flat.cell_center(&[1, 2]).unwrap(), (10.0, 3.0));
+        assert!(SpatialGrid::with_cell_size(Grid::new(0u8, vec![2]), vec![1.0, 1.0]).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{indexed::CoordCounter, Grid, GridError};
+
+/// A [`Grid`] whose number of axes is part of its type, so coordinates are
+/// `[usize; D]` and passing the wrong number of them is a compile error.
+/// Cells are stored exactly as in the wrapped grid, and the two convert
+/// into each other freely.
+///
+/// ```
+/// use md_grid::Grid2;
+///
+/// let mut tiles = Grid2::new('.', [3, 4]);
+/// tiles[[1, 2]] = '#';
+/// assert_eq!(tiles.get([1, 2]), Some(&'#'));
+/// assert_eq!(tiles.get([3, 0]), None);
+/// assert_eq!(tiles.dimensions(), [3, 4]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GridN<T, const D: usize> {
+    grid: Grid<T>,
+}
+
+pub type Grid2<T> = GridN<T, 2>;
+pub type Grid3<T> = GridN<T, 3>;
+
+impl<T: Clone, const D: usize> GridN<T, D> {
+    pub fn new(default_value: T, dimensions: [usize; D]) -> Self {
+        Self {
+            grid: Grid::new(default_value, dimensions.to_vec()),
+        }
+    }
+}
+
+impl<T, const D: usize> GridN<T, D> {
+    /// Builds a grid by calling `f` with the coordinates of each cell, in
+    /// row-major order.
+    pub fn from_fn<F>(dimensions: [usize; D], mut f: F) -> Self
+    where
+        F: FnMut([usize; D]) -> T,
+    {
+        Self {
+            grid: Grid::from_fn(dimensions.to_vec(), |c| f(c.try_into().unwrap())),
+        }
+    }
+
+    pub fn dimensions(&self) -> [usize; D] {
+        self.grid.dimensions[..].try_into().unwrap()
+    }
+
+    pub fn get(&self, target: [usize; D]) -> Option<&T> {
+        self.grid.get_opt(&target)
+    }
+
+    pub fn get_mut(&mut self, target: [usize; D]) -> Option<&mut T> {
+        self.grid.get_mut_opt(&target)
+    }
+
+    /// Sets a cell, failing with [`GridError::OutOfBounds`] if it is outside
+    /// the grid.
+    pub fn set(&mut self, target: [usize; D], val: T) -> Result<(), GridError> {
+        self.grid.set(&target, val)
+    }
+
+    /// Iterates over all cells in row-major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.grid.grid.iter()
+    }
+
+    /// Iterates mutably over all cells in row-major order.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.grid.grid.iter_mut()
+    }
+
+    /// Iterates over every cell with its coordinates, in row-major order.
+    pub fn iter_indexed(&self) -> impl Iterator<Item = ([usize; D], &T)> {
+        let mut counter = CoordCounter::new(&self.grid.dimensions);
+        self.grid.grid.iter().map(move |v| {
+            let coords = counter.coords().try_into().unwrap();
+            counter.advance();
+            (coords, v)
+        })
+    }
+
+    pub fn as_grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+}
+
+impl<T, const D: usize> Index<[usize; D]> for GridN<T, D> {
+    type Output = T;
+
+    fn index(&self, target: [usize; D]) -> &T {
+        &self.grid[target]
+    }
+}
+
+impl<T, const D: usize> IndexMut<[usize; D]> for GridN<T, D> {
+    fn index_mut(&mut self, target: [usize; D]) -> &mut T {
+        &mut self.grid[target]
+    }
+}
+
+/// Fails with [`GridError::UnsupportedAxes`] unless the grid has `D` axes.
+impl<T, const D: usize> TryFrom<Grid<T>> for GridN<T, D> {
+    type Error = GridError;
+
+    fn try_from(grid: Grid<T>) -> Result<Self, GridError> {
+        if grid.axes != D {
+            return Err(GridError::UnsupportedAxes {
+                expected: D,
+                shape: grid.dimensions,
+            });
+        }
+        Ok(Self { grid })
+    }
+}
+
+impl<T, const D: usize> From<GridN<T, D>> for Grid<T> {
+    fn from(grid: GridN<T, D>) -> Self {
+        grid.grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn static_coordinates() {
+        let mut volume = Grid3::from_fn([2, 3, 4], |[z, y, x]| z * 100 + y * 10 + x);
+        assert_eq!(volume[[1, 2, 3]], 123);
+        volume.set([0, 1, 1], 7).unwrap();
+        assert!(volume.set([2, 0, 0], 7).is_err());
+
+        let (last, v) = volume.iter_indexed().last().unwrap();
+        assert_eq!((last, *v), ([1, 2, 3], 123));
+
+        let grid: Grid<usize> = volume.into();
+        assert_eq!(grid.get_opt(&[0, 1, 1]), Some(&7));
+        assert!(Grid2::try_from(grid.clone()).is_err());
+        let back = Grid3::try_from(grid).unwrap();
+        assert_eq!(back.dimensions(), [2, 3, 4]);
+    }
+}
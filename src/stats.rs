@@ -0,0 +1,252 @@
+use crate::{Grid, GridError, Shape};
+
+/// Summary statistics accumulated in one pass with Welford's algorithm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    m2: f64,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+}
+
+impl Stats {
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Combines statistics gathered over two disjoint sets of values.
+    pub fn merge(&self, other: &Stats) -> Stats {
+        if self.count == 0 {
+            return *other;
+        }
+        if other.count == 0 {
+            return *self;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        Stats {
+            count,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+            mean: self.mean + delta * other.count as f64 / count as f64,
+            m2: self.m2
+                + other.m2
+                + delta * delta * (self.count * other.count) as f64 / count as f64,
+        }
+    }
+
+    /// Population variance.
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.m2 / self.count as f64
+    }
+
+    /// Population standard deviation.
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+impl FromIterator<f64> for Stats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = Stats::default();
+        iter.into_iter().for_each(|v| stats.push(v));
+        stats
+    }
+}
+
+impl<T: Copy + Into<f64>> Grid<T> {
+    pub fn stats(&self) -> Stats {
+        self.grid.iter().map(|&v| v.into()).collect()
+    }
+
+    /// Statistics of each slice along `axis`: entry `i` summarizes every cell
+    /// whose coordinate on `axis` is `i` (each row for axis 0 of a 2D grid).
+    pub fn axis_stats(&self, axis: usize) -> Result<Vec<Stats>, GridError> {
+        if axis >= self.axes {
+            return Err(GridError::InvalidAxis {
+                axis,
+                shape: self.dimensions.clone(),
+            });
+        }
+
+        let stride: usize = self.dimensions[axis + 1..].iter().product();
+        let len = self.dimensions[axis];
+        let mut stats = vec![Stats::default(); len];
+        for (i, &v) in self.grid.iter().enumerate() {
+            stats[(i / stride) % len].push(v.into());
+        }
+        Ok(stats)
+    }
+
+    /// Values at each of the given percentiles (0 to 100), linearly
+    /// interpolating between cells. Returns NaN for an empty grid.
+    pub fn percentiles(&self, percentiles: &[f64]) -> Vec<f64> {
+        let mut sorted: Vec<f64> = self.grid.iter().map(|&v| v.into()).collect();
+        sorted.sort_unstable_by(f64::total_cmp);
+
+        percentiles
+            .iter()
+            .map(|&p| {
+                if sorted.is_empty() {
+                    return f64::NAN;
+                }
+                let rank = (p / 100.0).clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+                let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+                sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+            })
+            .collect()
+    }
+
+    /// The values of cells that are valid: not NaN, and `true` in `mask` if
+    /// one is given.
+    fn valid_values<'a>(
+        &'a self,
+        mask: Option<&'a Grid<bool>>,
+    ) -> Result<impl Iterator<Item = f64> + 'a, GridError> {
+        if let Some(mask) = mask {
+            Shape::from(&self.dimensions[..]).check_eq(&mask.dimensions)?;
+        }
+        Ok(self
+            .grid
+            .iter()
+            .enumerate()
+            .filter(move |&(i, _)| mask.is_none_or(|m| m.grid[i]))
+            .map(|(_, &v)| v.into())
+            .filter(|v: &f64| !v.is_nan()))
+    }
+
+    /// Like [`Grid::stats`], but skipping NaN cells and cells that are
+    /// `false` in `mask`.
+    pub fn stats_masked(&self, mask: Option<&Grid<bool>>) -> Result<Stats, GridError> {
+        Ok(self.valid_values(mask)?.collect())
+    }
+
+    /// Sum of the valid cells; 0 if there are none.
+    pub fn sum_masked(&self, mask: Option<&Grid<bool>>) -> Result<f64, GridError> {
+        Ok(self.valid_values(mask)?.sum())
+    }
+
+    /// Mean of the valid cells; NaN if there are none.
+    pub fn mean_masked(&self, mask: Option<&Grid<bool>>) -> Result<f64, GridError> {
+        let stats = self.stats_masked(mask)?;
+        Ok(if stats.count == 0 {
+            f64::NAN
+        } else {
+            stats.mean
+        })
+    }
+
+    /// Smallest valid cell, or `None` if there are none.
+    pub fn min_masked(&self, mask: Option<&Grid<bool>>) -> Result<Option<f64>, GridError> {
+        Ok(self.valid_values(mask)?.reduce(f64::min))
+    }
+
+    /// Largest valid cell, or `None` if there are none.
+    pub fn max_masked(&self, mask: Option<&Grid<bool>>) -> Result<Option<f64>, GridError> {
+        Ok(self.valid_values(mask)?.reduce(f64::max))
+    }
+
+    /// Applies `f` to every valid cell, leaving the rest NaN in the output.
+    pub fn map_masked(
+        &self,
+        mask: Option<&Grid<bool>>,
+        mut f: impl FnMut(f64) -> f64,
+    ) -> Result<Grid<f64>, GridError> {
+        if let Some(mask) = mask {
+            Shape::from(&self.dimensions[..]).check_eq(&mask.dimensions)?;
+        }
+        let grid = self
+            .grid
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let v: f64 = v.into();
+                if v.is_nan() || mask.is_some_and(|m| !m.grid[i]) {
+                    f64::NAN
+                } else {
+                    f(v)
+                }
+            })
+            .collect();
+        Ok(Grid::from_parts(grid, self.dimensions.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let mut grid = Grid::new(0u8, vec![2, 4]);
+        for (i, v) in grid.iter_mut().enumerate() {
+            *v = i as u8 + 1;
+        }
+
+        let stats = grid.stats();
+        assert_eq!((stats.count, stats.min, stats.max), (8, 1.0, 8.0));
+        assert_eq!(stats.mean, 4.5);
+        assert!((stats.variance() - 5.25).abs() < 1e-12);
+
+        let rows = grid.axis_stats(0).unwrap();
+        assert_eq!((rows[0].mean, rows[1].mean), (2.5, 6.5));
+        assert!((rows[0].merge(&rows[1]).variance() - 5.25).abs() < 1e-12);
+        let cols = grid.axis_stats(1).unwrap();
+        assert_eq!(
+            cols.iter().map(|s| s.mean).collect::<Vec<_>>(),
+            vec![3.0, 4.0, 5.0, 6.0]
+        );
+
+        assert_eq!(grid.percentiles(&[0.0, 50.0, 100.0]), vec![1.0, 4.5, 8.0]);
+        assert!(grid.axis_stats(2).is_err());
+    }
+
+    #[test]
+    fn masked_nodata() {
+        let mut grid = Grid::new(1.0, vec![2, 3]);
+        grid.set(&[0, 1], f64::NAN).unwrap();
+        grid.set(&[1, 2], 10.0).unwrap();
+        assert!(grid.stats().mean.is_nan());
+
+        assert_eq!(grid.sum_masked(None).unwrap(), 14.0);
+        assert_eq!(grid.mean_masked(None).unwrap(), 2.8);
+        assert_eq!(grid.max_masked(None).unwrap(), Some(10.0));
+
+        let mut mask = Grid::new(true, vec![2, 3]);
+        mask.set(&[1, 2], false).unwrap();
+        assert_eq!(grid.stats_masked(Some(&mask)).unwrap().count, 4);
+        assert_eq!(grid.mean_masked(Some(&mask)).unwrap(), 1.0);
+        assert_eq!(grid.min_masked(Some(&mask)).unwrap(), Some(1.0));
+
+        let doubled = grid.map_masked(Some(&mask), |v| v * 2.0).unwrap();
+        assert_eq!(doubled.get_opt(&[0, 0]), Some(&2.0));
+        assert!(doubled.get_opt(&[1, 2]).unwrap().is_nan());
+
+        let none = Grid::new(false, vec![2, 3]);
+        assert_eq!(grid.min_masked(Some(&none)).unwrap(), None);
+        assert!(grid.mean_masked(Some(&none)).unwrap().is_nan());
+        assert!(grid.sum_masked(Some(&Grid::new(true, vec![3, 2]))).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt::Write;
+
+use crate::{false_index, Color, Coord, Grid, GridError};
+
+/// Space left above and to the left of the cells for index labels.
+const LABEL_MARGIN: usize = 24;
+
+/// Rendering options for [`Grid::to_svg_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Side length of each cell, in SVG user units.
+    pub cell_size: usize,
+    /// Color of lines drawn between cells, if any.
+    pub grid_lines: Option<Color>,
+    /// Whether to label rows and columns with their indices.
+    pub labels: bool,
+}
+
+impl SvgOptions {
+    pub fn new(cell_size: usize) -> Self {
+        Self {
+            cell_size,
+            grid_lines: None,
+            labels: false,
+        }
+    }
+
+    pub fn grid_lines(mut self, color: Color) -> Self {
+        self.grid_lines = Some(color);
+        self
+    }
+
+    pub fn labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+}
+
+impl<T> Grid<T> {
+    /// Renders a 2D grid as an SVG heatmap with one `cell_size` square per
+    /// cell, filled with the color `color` picks for it.
+    pub fn to_svg<F>(&self, color: F, cell_size: usize) -> Result<String, GridError>
+    where
+        F: FnMut(&Coord, &T) -> Color,
+    {
+        self.to_svg_with(color, &SvgOptions::new(cell_size))
+    }
+
+    /// [`Grid::to_svg`] with grid lines and labels configured by `options`.
+    pub fn to_svg_with<F>(&self, mut color: F, options: &SvgOptions) -> Result<String, GridError>
+    where
+        F: FnMut(&Coord, &T) -> Color,
+    {
+        let &[rows, cols] = &self.dimensions[..] else {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            });
+        };
+
+        let size = options.cell_size;
+        let margin = if options.labels { LABEL_MARGIN } else { 0 };
+        let (width, height) = (margin + cols * size, margin + rows * size);
+
+        // Writing to a String can't fail, so the results are ignored below.
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#
+        );
+
+        for (i, val) in self.grid.iter().enumerate() {
+            let coords = false_index(i, &self.dimensions);
+            let (x, y) = (margin + coords[1] * size, margin + coords[0] * size);
+            let fill = color(&coords, val).to_hex();
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{x}" y="{y}" width="{size}" height="{size}" fill="{fill}"/>"#
+            );
+        }
+
+        if let Some(line) = options.grid_lines {
+            let stroke = line.to_hex();
+            let _ = writeln!(svg, r#"<g stroke="{stroke}" stroke-width="1">"#);
+            for r in 0..=rows {
+                let y = margin + r * size;
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="{margin}" y1="{y}" x2="{width}" y2="{y}"/>"#
+                );
+            }
+            for c in 0..=cols {
+                let x = margin + c * size;
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="{x}" y1="{margin}" x2="{x}" y2="{height}"/>"#
+                );
+            }
+            svg.push_str("</g>\n");
+        }
+
+        if options.labels {
+            let _ = writeln!(
+                svg,
+                r#"<g font-family="monospace" font-size="10" text-anchor="middle" dominant-baseline="middle">"#
+            );
+            let offset = margin / 2;
+            for c in 0..cols {
+                let x = margin + c * size + size / 2;
+                let _ = writeln!(svg, r#"<text x="{x}" y="{offset}">{c}</text>"#);
+            }
+            for r in 0..rows {
+                let y = margin + r * size + size / 2;
+                let _ = writeln!(svg, r#"<text x="{offset}" y="{y}">{r}</text>"#);
+            }
+            svg.push_str("</g>\n");
+        }
+
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heatmap() {
+        let mut grid = Grid::new(0.0, vec![2, 3]);
+        grid.set(&[1, 2], 1.0).unwrap();
+
+        let svg = grid
+            .to_svg(|_, &v| Color::BLACK.lerp(Color::WHITE, v), 10)
+            .unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"width="30" height="20""#));
+        assert_eq!(svg.matches("<rect").count(), 6);
+        assert!(svg.contains(r##"<rect x="20" y="10" width="10" height="10" fill="#ffffff"/>"##));
+
+        let options = SvgOptions::new(10).grid_lines(Color::WHITE).labels(true);
+        let svg = grid.to_svg_with(|_, _| Color::BLACK, &options).unwrap();
+        assert_eq!(svg.matches("<line").count(), 3 + 4);
+        assert_eq!(svg.matches("<text").count(), 2 + 3);
+
+        assert!(Grid::new(0, vec![2])
+            .to_svg(|_, _| Color::BLACK, 1)
+            .is_err());
+    }
+}
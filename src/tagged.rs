@@ -0,0 +1,237 @@
+use std::{collections::BTreeMap, fmt, ops::Range};
+
+use crate::{Grid, GridError, GridView, Region};
+
+/// A cell of a named region changing, as seen by the listeners registered
+/// for that region with [`TaggedGrid::on_change`].
+#[derive(Debug)]
+pub struct RegionChange<'e, T> {
+    pub region: &'e str,
+    pub coords: &'e [usize],
+    pub old: &'e T,
+    pub new: &'e T,
+}
+
+type Listener<T> = Box<dyn FnMut(&RegionChange<'_, T>)>;
+
+/// A grid with named regions, such as the spawn points and trigger zones of
+/// a level, that can be looked up, filled and watched by name. With the
+/// `serde` feature the regions are serialized together with the grid.
+///
+/// ```
+/// use std::{cell::Cell, rc::Rc};
+///
+/// use md_grid::{Grid, TaggedGrid};
+///
+/// let mut level = TaggedGrid::new(Grid::new('.', vec![8, 8]));
+/// level.register_region("spawn", vec![0..2, 0..2]).unwrap();
+/// level.register_region("exit", vec![7..8, 6..8]).unwrap();
+///
+/// let exit_changes = Rc::new(Cell::new(0));
+/// let counter = Rc::clone(&exit_changes);
+/// level.on_change("exit", move |_| counter.set(counter.get() + 1)).unwrap();
+///
+/// level.fill_region("exit", '>').unwrap();
+/// level.set(&[0, 0], '@').unwrap();
+/// assert_eq!(exit_changes.get(), 2);
+/// assert_eq!(level.regions_at(&[0, 1]).collect::<Vec<_>>(), ["spawn"]);
+/// assert!(level.region_view("exit").unwrap().iter().all(|&c| c == '>'));
+/// ```
+pub struct TaggedGrid<T> {
+    grid: Grid<T>,
+    regions: BTreeMap<String, Region>,
+    listeners: Vec<(String, Listener<T>)>,
+}
+
+impl<T> TaggedGrid<T> {
+    /// `grid` with no named regions.
+    pub fn new(grid: Grid<T>) -> Self {
+        Self {
+            grid,
+            regions: BTreeMap::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        self.grid.dimensions()
+    }
+
+    /// The grid and its regions, dropping any listeners.
+    pub fn into_parts(self) -> (Grid<T>, BTreeMap<String, Region>) {
+        (self.grid, self.regions)
+    }
+
+    /// Names the cells in `ranges`, replacing and returning any region
+    /// already under `name`. Fails with [`GridError::RegionOutOfBounds`] if
+    /// the region isn't entirely inside the grid.
+    pub fn register_region(
+        &mut self,
+        name: impl Into<String>,
+        ranges: Vec<Range<usize>>,
+    ) -> Result<Option<Region>, GridError> {
+        let region = Region::new(ranges);
+        region.check_within(self.grid.dimensions())?;
+        Ok(self.regions.insert(name.into(), region))
+    }
+
+    /// Forgets the region under `name` along with its listeners.
+    pub fn remove_region(&mut self, name: &str) -> Option<Region> {
+        self.listeners.retain(|(region, _)| region != name);
+        self.regions.remove(name)
+    }
+
+    pub fn region(&self, name: &str) -> Option<&Region> {
+        self.regions.get(name)
+    }
+
+    /// Every named region, in order of name.
+    pub fn regions(&self) -> impl Iterator<Item = (&str, &Region)> {
+        self.regions
+            .iter()
+            .map(|(name, region)| (name.as_str(), region))
+    }
+
+    /// The names of the regions containing `coords`, in order of name.
+    pub fn regions_at<'a>(&'a self, coords: &'a [usize]) -> impl Iterator<Item = &'a str> + 'a {
+        self.regions()
+            .filter(move |(_, region)| region.contains(coords))
+            .map(|(name, _)| name)
+    }
+
+    /// A view of the region under `name`.
+    pub fn region_view(&self, name: &str) -> Result<GridView<'_, T>, GridError> {
+        let region = self.named(name)?;
+        self.grid.view(&region.origin(), &region.shape())
+    }
+
+    pub fn get(&self, target: &[usize]) -> Result<&T, GridError> {
+        self.grid.get(target)
+    }
+
+    /// Sets one cell, telling the listeners of every region containing it.
+    pub fn set(&mut self, target: &[usize], val: T) -> Result<(), GridError> {
+        let old = std::mem::replace(self.grid.get_mut(target)?, val);
+        let new = self.grid.get(target)?;
+        for (name, listener) in &mut self.listeners {
+            if self.regions[name].contains(target) {
+                listener(&RegionChange {
+                    region: name,
+                    coords: target,
+                    old: &old,
+                    new,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces every cell of the region under `name` with what `f` returns
+    /// for its coordinates and current value, returning how many cells were
+    /// visited.
+    pub fn update_region<F>(&mut self, name: &str, mut f: F) -> Result<usize, GridError>
+    where
+        F: FnMut(&[usize], &T) -> T,
+    {
+        let region = self.named(name)?.clone();
+        for coords in &region {
+            let val = f(&coords, self.grid.get(&coords)?);
+            self.set(&coords, val)?;
+        }
+        Ok(region.len())
+    }
+
+    /// Calls `listener` after each change to a cell of the region under
+    /// `name`, until the region is removed. Listeners of a region run in the
+    /// order they were added.
+    pub fn on_change<F>(&mut self, name: &str, listener: F) -> Result<(), GridError>
+    where
+        F: FnMut(&RegionChange<'_, T>) + 'static,
+    {
+        self.named(name)?;
+        self.listeners.push((name.to_string(), Box::new(listener)));
+        Ok(())
+    }
+
+    fn named(&self, name: &str) -> Result<&Region, GridError> {
+        self.regions
+            .get(name)
+            .ok_or_else(|| GridError::UnknownRegion {
+                name: name.to_string(),
+                shape: self.grid.dimensions().to_vec(),
+            })
+    }
+}
+
+impl<T: Clone> TaggedGrid<T> {
+    /// Sets every cell of the region under `name` to `value`, returning how
+    /// many there were.
+    pub fn fill_region(&mut self, name: &str, value: T) -> Result<usize, GridError> {
+        self.update_region(name, |_, _| value.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TaggedGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaggedGrid")
+            .field("grid", &self.grid)
+            .field("regions", &self.regions)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn named_regions_and_listeners() {
+        let mut level = TaggedGrid::new(Grid::new(0u8, vec![4, 4]));
+        assert!(level
+            .register_region("lava", vec![1..3, 1..3])
+            .unwrap()
+            .is_none());
+        level.register_region("row", vec![2..3, 0..4]).unwrap();
+        assert!(level.register_region("outside", vec![3..5, 0..1]).is_err());
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&seen);
+        level
+            .on_change("row", move |change| {
+                log.borrow_mut()
+                    .push((change.coords.to_vec(), *change.old, *change.new));
+            })
+            .unwrap();
+        assert!(matches!(
+            level.on_change("nowhere", |_| {}),
+            Err(GridError::UnknownRegion { .. })
+        ));
+
+        assert_eq!(level.fill_region("lava", 9).unwrap(), 4);
+        level.set(&[2, 0], 1).unwrap();
+        level.set(&[0, 0], 1).unwrap();
+        assert_eq!(
+            *seen.borrow(),
+            [(vec![2, 1], 0, 9), (vec![2, 2], 0, 9), (vec![2, 0], 0, 1)]
+        );
+        assert_eq!(
+            level.regions_at(&[2, 1]).collect::<Vec<_>>(),
+            ["lava", "row"]
+        );
+        assert!(level.region_view("lava").unwrap().iter().all(|&c| c == 9));
+
+        let replaced = level.register_region("lava", vec![0..1, 0..1]).unwrap();
+        assert_eq!(replaced.unwrap().ranges(), &[1..3, 1..3]);
+        level.remove_region("row");
+        level.set(&[2, 0], 2).unwrap();
+        assert_eq!(seen.borrow().len(), 3);
+        assert!(level.update_region("row", |_, &c| c).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt::Write,
+};
+
+use crate::{Color, Coord, Grid, GridError};
+
+/// Largest base64 payload the kitty protocol accepts in one escape sequence.
+const KITTY_CHUNK: usize = 4096;
+
+/// An inline image protocol understood by some terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalGraphics {
+    /// DEC sixel graphics, supported by xterm, foot, WezTerm, mlterm and
+    /// others. Limited to 256 colors per image.
+    Sixel,
+    /// The kitty graphics protocol, supported by kitty, WezTerm and Ghostty.
+    Kitty,
+}
+
+impl<T> Grid<T> {
+    /// Renders a 2D grid as an escape sequence that displays it as an image
+    /// when printed to a terminal supporting `protocol`. Each cell becomes a
+    /// `scale × scale` pixel square of the color `color` picks for it.
+    pub fn to_terminal_image<F>(
+        &self,
+        protocol: TerminalGraphics,
+        scale: usize,
+        color: F,
+    ) -> Result<String, GridError>
+    where
+        F: FnMut(&Coord, &T) -> Color,
+    {
+        let pixels = self.render(scale, color)?;
+        Ok(match protocol {
+            TerminalGraphics::Sixel => encode_sixel(&pixels),
+            TerminalGraphics::Kitty => encode_kitty(&pixels),
+        })
+    }
+}
+
+/// Maps every pixel to a palette index, keeping exact colors when there are
+/// at most 256 and falling back to a 6×6×6 color cube otherwise.
+fn quantize(pixels: &[Color]) -> (Vec<Color>, Vec<u8>) {
+    let mut palette: Vec<Color> = Vec::new();
+    let mut lookup: HashMap<Color, u8> = HashMap::new();
+    let mut overflow = false;
+    for &p in pixels {
+        let next = palette.len();
+        if let Entry::Vacant(entry) = lookup.entry(p) {
+            if next == 256 {
+                overflow = true;
+                break;
+            }
+            entry.insert(next as u8);
+            palette.push(p);
+        }
+    }
+    if !overflow {
+        let indices = pixels.iter().map(|p| lookup[p]).collect();
+        return (palette, indices);
+    }
+
+    let level = |v: u8| (v as u16 * 5 + 127) / 255;
+    let palette = (0..216)
+        .map(|i| {
+            let scale = |l: u16| (l * 51) as u8;
+            Color::rgb(scale(i / 36), scale(i / 6 % 6), scale(i % 6))
+        })
+        .collect();
+    let indices = pixels
+        .iter()
+        .map(|p| (level(p.r) * 36 + level(p.g) * 6 + level(p.b)) as u8)
+        .collect();
+    (palette, indices)
+}
+
+fn encode_sixel(pixels: &Grid<Color>) -> String {
+    let (height, width) = (pixels.dimensions[0], pixels.dimensions[1]);
+    let (palette, indices) = quantize(&pixels.grid);
+
+    let mut out = String::new();
+    let _ = write!(out, "\x1bPq\"1;1;{};{}", width, height);
+    for (i, c) in palette.iter().enumerate() {
+        let percent = |v: u8| (v as u32 * 100 + 127) / 255;
+        let _ = write!(
+            out,
+            "#{};2;{};{};{}",
+            i,
+            percent(c.r),
+            percent(c.g),
+            percent(c.b)
+        );
+    }
+
+    let mut sixels = vec![0u8; width];
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let mut used: Vec<u8> = rows
+            .clone()
+            .flat_map(|r| indices[r * width..(r + 1) * width].iter().copied())
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+
+        for (n, &color) in used.iter().enumerate() {
+            if n > 0 {
+                out.push('$');
+            }
+            sixels.iter_mut().for_each(|s| *s = 0);
+            for r in rows.clone() {
+                for (col, s) in sixels.iter_mut().enumerate() {
+                    if indices[r * width + col] == color {
+                        *s |= 1 << (r - band);
+                    }
+                }
+            }
+
+            let _ = write!(out, "#{}", color);
+            let mut col = 0;
+            while col < width {
+                let run = sixels[col..]
+                    .iter()
+                    .take_while(|&&s| s == sixels[col])
+                    .count();
+                let ch = (63 + sixels[col]) as char;
+                if run > 3 {
+                    let _ = write!(out, "!{}{}", run, ch);
+                } else {
+                    (0..run).for_each(|_| out.push(ch));
+                }
+                col += run;
+            }
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn encode_kitty(pixels: &Grid<Color>) -> String {
+    let (height, width) = (pixels.dimensions[0], pixels.dimensions[1]);
+    let rgb: Vec<u8> = pixels.grid.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+    let payload = base64(&rgb);
+
+    let mut out = String::new();
+    let chunks: Vec<&str> = payload
+        .as_bytes()
+        .chunks(KITTY_CHUNK)
+        .map(|c| std::str::from_utf8(c).unwrap())
+        .collect();
+    if chunks.is_empty() {
+        let _ = write!(out, "\x1b_Ga=T,f=24,s={},v={};\x1b\\", width, height);
+    }
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        if i == 0 {
+            let _ = write!(out, "\x1b_Ga=T,f=24,s={},v={},m={};", width, height, more);
+        } else {
+            let _ = write!(out, "\x1b_Gm={};", more);
+        }
+        out.push_str(chunk);
+        out.push_str("\x1b\\");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sixel() {
+        let mut grid = Grid::new(0u8, vec![1, 8]);
+        grid.set(&[0, 7], 1).unwrap();
+        let color = |_: &Coord, &v: &u8| if v == 1 { Color::WHITE } else { Color::BLACK };
+
+        let image = grid
+            .to_terminal_image(TerminalGraphics::Sixel, 1, color)
+            .unwrap();
+        assert_eq!(
+            image,
+            "\x1bPq\"1;1;8;1#0;2;0;0;0#1;2;100;100;100#0!7@?$#1!7?@-\x1b\\"
+        );
+    }
+
+    #[test]
+    fn kitty() {
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"Man"), "TWFu");
+
+        let grid = Grid::new(Color::rgb(1, 2, 3), vec![40, 40]);
+        let image = grid
+            .to_terminal_image(TerminalGraphics::Kitty, 1, |_, &c| c)
+            .unwrap();
+        // 40 × 40 × 3 bytes is 6400 base64 characters, sent in two chunks.
+        assert!(image.starts_with("\x1b_Ga=T,f=24,s=40,v=40,m=1;AQID"));
+        assert!(image.contains("\x1b\\\x1b_Gm=0;"));
+        assert!(image.ends_with("\x1b\\"));
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::{self, Display, Write};
+
+use crate::{Grid, GridError};
+
+impl<T> Grid<T> {
+    /// Writes the cells row by row, a row being a lane along the last axis.
+    /// Cells are split by `separator` and rows by newlines, with a blank
+    /// line between the 2D slices of grids with more than two axes.
+    fn write_rows<W, F>(&self, out: &mut W, separator: &str, mut cell: F) -> fmt::Result
+    where
+        W: Write,
+        F: FnMut(&mut W, &T) -> fmt::Result,
+    {
+        let row = self.dimensions.last().copied().unwrap_or(1);
+        let slice = match self.axes {
+            0..=2 => usize::MAX,
+            _ => row * self.dimensions[self.axes - 2],
+        };
+        for (i, v) in self.grid.iter().enumerate() {
+            if i > 0 && i % row == 0 {
+                out.write_char('\n')?;
+                if i % slice == 0 {
+                    out.write_char('\n')?;
+                }
+            } else if i > 0 {
+                out.write_str(separator)?;
+            }
+            cell(out, v)?;
+        }
+        Ok(())
+    }
+
+    /// Renders a 2D grid as text, one line per row, with the cells shown by
+    /// `cell` right-aligned to `width` and joined by `separator`.
+    ///
+    /// ```
+    /// use md_grid::Grid;
+    ///
+    /// let grid = Grid::from_fn(vec![2, 3], |c| c[0] * 10 + c[1]);
+    /// let text = grid.render_2d(" | ", 2, |v| v * 2).unwrap();
+    /// assert_eq!(text, " 0 |  2 |  4\n20 | 22 | 24");
+    /// ```
+    pub fn render_2d<F, D>(
+        &self,
+        separator: &str,
+        width: usize,
+        mut cell: F,
+    ) -> Result<String, GridError>
+    where
+        F: FnMut(&T) -> D,
+        D: Display,
+    {
+        if self.axes != 2 {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            });
+        }
+        let mut out = String::new();
+        self.write_rows(&mut out, separator, |out, v| {
+            write!(out, "{:>width$}", cell(v))
+        })
+        .unwrap();
+        Ok(out)
+    }
+}
+
+/// Prints the grid row by row with cells separated by spaces. Width,
+/// alignment and precision apply to every cell, so `{:3}` lines up columns
+/// of numbers, and the alternate form `{:#}` drops the separator, which
+/// suits grids of characters.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if f.alternate() { "" } else { " " };
+        self.write_rows(f, separator, |f, v| v.fmt(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_rows() {
+        let maze = Grid::from_fn(vec![2, 4], |c| if c[1] == c[0] { '#' } else { '.' });
+        assert_eq!(format!("{:#}", maze), "#...\n.#..");
+        assert_eq!(maze.to_string(), "# . . .\n. # . .");
+
+        let numbers = Grid::from_fn(vec![2, 2], |c| c[0] as f64 + c[1] as f64 / 2.0);
+        assert_eq!(format!("{:4.1}", numbers), " 0.0  0.5\n 1.0  1.5");
+
+        let cube = Grid::from_fn(vec![2, 1, 2], |c| c[0] * 2 + c[2]);
+        assert_eq!(cube.to_string(), "0 1\n\n2 3");
+        assert!(cube.render_2d(",", 1, |v| *v).is_err());
+    }
+}
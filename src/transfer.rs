@@ -0,0 +1,63 @@
+use std::mem;
+
+use crate::{Grid, GridError};
+
+impl<T: Default> Grid<T> {
+    /// Moves the value at `src` in this grid to `dst` in `other`, leaving
+    /// `T::default()` behind, and returns the value it displaced. Both
+    /// coordinates are checked first, so on error neither grid is changed.
+    pub fn transfer(
+        &mut self,
+        other: &mut Grid<T>,
+        src: &[usize],
+        dst: &[usize],
+    ) -> Result<T, GridError> {
+        let from = self.translate_index(src)?;
+        let to = other.translate_index(dst)?;
+
+        let val = mem::take(&mut self.grid[from]);
+        Ok(mem::replace(&mut other.grid[to], val))
+    }
+
+    /// Moves the value at `src` to `dst` within this grid, leaving
+    /// `T::default()` behind, and returns the value it displaced. On error
+    /// the grid is unchanged; moving a cell onto itself displaces nothing.
+    pub fn move_cell(&mut self, src: &[usize], dst: &[usize]) -> Result<T, GridError> {
+        let from = self.translate_index(src)?;
+        let to = self.translate_index(dst)?;
+        if from == to {
+            return Ok(T::default());
+        }
+
+        let val = mem::take(&mut self.grid[from]);
+        Ok(mem::replace(&mut self.grid[to], val))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_and_move() {
+        let mut backpack = Grid::new(None, vec![2, 2]);
+        let mut chest = Grid::new(None, vec![3, 3]);
+        backpack.set(&[0, 1], Some("sword")).unwrap();
+        chest.set(&[2, 2], Some("shield")).unwrap();
+
+        let displaced = backpack.transfer(&mut chest, &[0, 1], &[2, 2]).unwrap();
+        assert_eq!(displaced, Some("shield"));
+        assert_eq!(backpack.get_opt(&[0, 1]), Some(&None));
+        assert_eq!(chest.get_opt(&[2, 2]), Some(&Some("sword")));
+
+        // A bad destination leaves the source untouched.
+        chest.set(&[0, 0], Some("potion")).unwrap();
+        assert!(chest.transfer(&mut backpack, &[0, 0], &[2, 0]).is_err());
+        assert_eq!(chest.get_opt(&[0, 0]), Some(&Some("potion")));
+
+        assert_eq!(chest.move_cell(&[0, 0], &[1, 1]).unwrap(), None);
+        assert_eq!(chest.get_opt(&[1, 1]), Some(&Some("potion")));
+        assert_eq!(chest.move_cell(&[1, 1], &[1, 1]).unwrap(), None);
+        assert_eq!(chest.get_opt(&[1, 1]), Some(&Some("potion")));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{Grid, GridError};
+
+impl<T: Clone> Grid<T> {
+    /// A copy with the axes in reverse order, so cell `[a, b, c]` moves to
+    /// `[c, b, a]`. For a 2D grid this swaps rows and columns, turning
+    /// row-major data into column-major.
+    pub fn transpose(&self) -> Grid<T> {
+        let order: Vec<usize> = (0..self.axes).rev().collect();
+        self.permute_axes(&order)
+            .expect("a reversed axis list is a permutation")
+    }
+
+    /// A copy whose axis `i` is axis `order[i]` of `self`, so
+    /// `permute_axes(&[2, 0, 1])` on a `[z, y, x]` grid gives an `[x, z, y]`
+    /// one. Fails with [`GridError::UnsupportedAxes`] if `order` doesn't
+    /// name every axis, or [`GridError::InvalidAxis`] if it names one that
+    /// doesn't exist or names one twice.
+    pub fn permute_axes(&self, order: &[usize]) -> Result<Grid<T>, GridError> {
+        if order.len() != self.axes {
+            return Err(GridError::UnsupportedAxes {
+                expected: order.len(),
+                shape: self.dimensions.clone(),
+            });
+        }
+        let mut seen = vec![false; self.axes];
+        for &axis in order {
+            if axis >= self.axes || std::mem::replace(&mut seen[axis], true) {
+                return Err(GridError::InvalidAxis {
+                    axis,
+                    shape: self.dimensions.clone(),
+                });
+            }
+        }
+
+        let strides = self.strides();
+        let strides: Vec<usize> = order.iter().map(|&axis| strides[axis]).collect();
+        let dimensions = order.iter().map(|&axis| self.dimensions[axis]).collect();
+        Ok(Grid::from_fn(dimensions, |coords| {
+            let index = coords.iter().zip(&strides).map(|(c, s)| c * s).sum();
+            self.cell(index).clone()
+        }))
+    }
+
+    /// A copy with the cells along `axis` in reverse order, mirroring the
+    /// grid across that axis. Fails with [`GridError::InvalidAxis`] if there
+    /// is no such axis.
+    pub fn flip_axis(&self, axis: usize) -> Result<Grid<T>, GridError> {
+        if axis >= self.axes {
+            return Err(GridError::InvalidAxis {
+                axis,
+                shape: self.dimensions.clone(),
+            });
+        }
+        let last = self.dimensions[axis].saturating_sub(1);
+        Ok(self.remap(self.dimensions.clone(), |coords| {
+            coords[axis] = last - coords[axis];
+        }))
+    }
+
+    /// A copy of a 2D grid turned a quarter clockwise, so the first row
+    /// becomes the last column. Fails with [`GridError::UnsupportedAxes`]
+    /// for other grids.
+    ///
+    /// Together with [`Grid::flip_axis`] this reaches all eight
+    /// orientations of a square tile:
+    ///
+    /// ```
+    /// use md_grid::Grid;
+    ///
+    /// let tile = Grid::from_fn(vec![2, 2], |c| c[0] * 2 + c[1]);
+    /// let mut orientations = Vec::new();
+    /// let mut turned = tile.clone();
+    /// for _ in 0..4 {
+    ///     orientations.push(turned.flip_axis(1).unwrap().into_flat_vec());
+    ///     turned = turned.rotate_cw().unwrap();
+    ///     orientations.push(turned.as_slice().to_vec());
+    /// }
+    /// assert_eq!(turned.as_slice(), tile.as_slice());
+    /// orientations.sort();
+    /// orientations.dedup();
+    /// assert_eq!(orientations.len(), 8);
+    /// ```
+    pub fn rotate_cw(&self) -> Result<Grid<T>, GridError> {
+        let (rows, cols) = self.rows_and_cols()?;
+        Ok(self.remap(vec![cols, rows], |coords| {
+            let (r, c) = (coords[0], coords[1]);
+            coords[0] = rows - 1 - c;
+            coords[1] = r;
+        }))
+    }
+
+    /// A copy of a 2D grid turned a quarter counterclockwise, so the first
+    /// row becomes the first column. Fails with
+    /// [`GridError::UnsupportedAxes`] for other grids.
+    pub fn rotate_ccw(&self) -> Result<Grid<T>, GridError> {
+        let (rows, cols) = self.rows_and_cols()?;
+        Ok(self.remap(vec![cols, rows], |coords| {
+            let (r, c) = (coords[0], coords[1]);
+            coords[0] = c;
+            coords[1] = cols - 1 - r;
+        }))
+    }
+
+    /// A copy of a 2D grid turned half a turn. Fails with
+    /// [`GridError::UnsupportedAxes`] for other grids.
+    pub fn rotate_180(&self) -> Result<Grid<T>, GridError> {
+        let (rows, cols) = self.rows_and_cols()?;
+        Ok(self.remap(vec![rows, cols], |coords| {
+            coords[0] = rows - 1 - coords[0];
+            coords[1] = cols - 1 - coords[1];
+        }))
+    }
+
+    fn rows_and_cols(&self) -> Result<(usize, usize), GridError> {
+        match self.dimensions[..] {
+            [rows, cols] => Ok((rows, cols)),
+            _ => Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            }),
+        }
+    }
+
+    /// A grid of `dimensions` whose cells are copied from `self` at the
+    /// coordinates `source` turns each new cell's coordinates into.
+    fn remap(&self, dimensions: Vec<usize>, mut source: impl FnMut(&mut [usize])) -> Grid<T> {
+        let strides = self.strides();
+        let mut from = Vec::with_capacity(self.axes);
+        Grid::from_fn(dimensions, |coords| {
+            from.clear();
+            from.extend_from_slice(coords);
+            source(&mut from);
+            let index = from.iter().zip(&strides).map(|(c, s)| c * s).sum();
+            self.cell(index).clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transpose_and_permute() {
+        let rows = Grid::from_fn(vec![2, 3], |c| c[0] * 3 + c[1]);
+        let cols = rows.transpose();
+        assert_eq!(cols.dimensions(), &[3, 2]);
+        assert!(cols.iter().eq(&[0, 3, 1, 4, 2, 5]));
+        assert!(cols.transpose().iter().eq(rows.iter()));
+
+        let volume = Grid::from_fn(vec![2, 3, 4], |c| c[0] * 100 + c[1] * 10 + c[2]);
+        let moved = volume.permute_axes(&[2, 0, 1]).unwrap();
+        assert_eq!(moved.dimensions(), &[4, 2, 3]);
+        assert_eq!(moved[[3, 1, 2]], 123);
+        assert!(volume.permute_axes(&[0, 1]).is_err());
+        assert!(matches!(
+            volume.permute_axes(&[0, 1, 1]),
+            Err(GridError::InvalidAxis { axis: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        // 0 1 2
+        // 3 4 5
+        let tile = Grid::from_fn(vec![2, 3], |c| c[0] * 3 + c[1]);
+        let cw = tile.rotate_cw().unwrap();
+        assert_eq!(cw.dimensions(), &[3, 2]);
+        assert!(cw.iter().eq(&[3, 0, 4, 1, 5, 2]));
+        assert!(tile.rotate_ccw().unwrap().iter().eq(&[2, 5, 1, 4, 0, 3]));
+        assert!(tile.rotate_180().unwrap().iter().eq(&[5, 4, 3, 2, 1, 0]));
+        assert!(cw.rotate_ccw().unwrap().iter().eq(tile.iter()));
+        assert!(cw.rotate_cw().unwrap().iter().eq(&[5, 4, 3, 2, 1, 0]));
+
+        assert!(tile.flip_axis(0).unwrap().iter().eq(&[3, 4, 5, 0, 1, 2]));
+        assert!(tile.flip_axis(1).unwrap().iter().eq(&[2, 1, 0, 5, 4, 3]));
+        assert!(tile.flip_axis(2).is_err());
+        assert!(Grid::new(0, vec![2, 2, 2]).rotate_cw().is_err());
+    }
+}
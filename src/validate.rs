@@ -0,0 +1,200 @@
+use std::fmt::{self, Display};
+
+use crate::{indexed::CoordCounter, Connectivity, Coord, Grid};
+
+/// A rule broken by one cell, found by [`Validator::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub coords: Coord,
+    /// Name of the rule, as registered.
+    pub rule: String,
+    pub message: String,
+}
+
+/// Every violation found by [`Validator::validate`], in row-major order of
+/// their cells and then in the order the rules were registered.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValidationReport {
+    violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// The violations of the rule registered as `rule`.
+    pub fn of_rule<'a>(&'a self, rule: &'a str) -> impl Iterator<Item = &'a Violation> + 'a {
+        self.violations.iter().filter(move |v| v.rule == rule)
+    }
+
+    pub fn into_violations(self) -> Vec<Violation> {
+        self.violations
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return writeln!(f, "grid is valid");
+        }
+        writeln!(f, "{} violation(s):", self.violations.len())?;
+        for v in &self.violations {
+            writeln!(f, "  at {:?}: {}: {}", v.coords, v.rule, v.message)?;
+        }
+        Ok(())
+    }
+}
+
+type Check<'a, T> = Box<dyn Fn(&[usize], &T, &[(Coord, &T)]) -> Option<String> + 'a>;
+
+/// A set of named rules that every cell of a grid must satisfy, for
+/// linting generated levels and maps.
+///
+/// Each rule sees a cell's coordinates, its value and its in-bounds
+/// neighbors under the validator's [`Connectivity`], and returns a message
+/// if the cell breaks it.
+///
+/// ```
+/// use md_grid::{Grid, Validator};
+///
+/// let level = Grid::from_fn(vec![3, 4], |c| match (c[0], c[1]) {
+///     (1, 1) => '+',
+///     (1, 3) => '+',
+///     (1, 2) => '#',
+///     (_, 0) | (_, 3) => '#',
+///     _ => '.',
+/// });
+/// let report = Validator::new()
+///     .neighborhood_rule("door-access", |_, &cell, neighbors| {
+///         let touches_floor = neighbors.iter().any(|(_, &n)| n == '.');
+///         (cell == '+' && !touches_floor).then(|| "door has no floor beside it".to_string())
+///     })
+///     .validate(&level);
+/// assert_eq!(report.violations().len(), 1);
+/// assert_eq!(report.violations()[0].coords[..], [1, 3]);
+/// ```
+pub struct Validator<'a, T> {
+    connectivity: Connectivity,
+    rules: Vec<(String, Check<'a, T>)>,
+}
+
+impl<'a, T> Validator<'a, T> {
+    /// An empty validator looking at orthogonal neighbors.
+    pub fn new() -> Self {
+        Self {
+            connectivity: Connectivity::Orthogonal,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Which cells count as neighbors for neighborhood rules.
+    pub fn connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    /// Adds a rule that only looks at each cell on its own.
+    pub fn cell_rule<F>(self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn(&[usize], &T) -> Option<String> + 'a,
+    {
+        self.neighborhood_rule(name, move |coords, cell, _| check(coords, cell))
+    }
+
+    /// Adds a rule that looks at each cell together with its neighbors.
+    pub fn neighborhood_rule<F>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn(&[usize], &T, &[(Coord, &T)]) -> Option<String> + 'a,
+    {
+        self.rules.push((name.into(), Box::new(check)));
+        self
+    }
+
+    /// Checks every cell against every rule.
+    pub fn validate(&self, grid: &Grid<T>) -> ValidationReport {
+        let mut violations = Vec::new();
+        let mut counter = CoordCounter::new(&grid.dimensions);
+        for cell in grid.iter() {
+            let coords = counter.coords();
+            let neighbors: Vec<_> = grid.neighbors(coords, self.connectivity).collect();
+            for (rule, check) in &self.rules {
+                if let Some(message) = check(coords, cell, &neighbors) {
+                    violations.push(Violation {
+                        coords: Coord::from_slice(coords),
+                        rule: rule.clone(),
+                        message,
+                    });
+                }
+            }
+            counter.advance();
+        }
+        ValidationReport { violations }
+    }
+}
+
+impl<T> Default for Validator<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Validator<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<_> = self.rules.iter().map(|(name, _)| name).collect();
+        f.debug_struct("Validator")
+            .field("connectivity", &self.connectivity)
+            .field("rules", &rules)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_linting() {
+        let mut level = Grid::new('.', vec![4, 4]);
+        for coords in [[0, 0], [0, 1], [1, 0], [1, 1], [3, 3]] {
+            level[coords] = '#';
+        }
+        level[[2, 0]] = '?';
+
+        let validator = Validator::new()
+            .connectivity(Connectivity::Diagonal)
+            .cell_rule("known-tile", |_, &c| {
+                (!".#".contains(c)).then(|| format!("unknown tile {c:?}"))
+            })
+            .neighborhood_rule("no-2x2-walls", |coords, &c, neighbors| {
+                let wall = |dr, dc| {
+                    neighbors
+                        .iter()
+                        .any(|(n, &v)| v == '#' && n[..] == [coords[0] + dr, coords[1] + dc])
+                };
+                (c == '#' && wall(0, 1) && wall(1, 0) && wall(1, 1))
+                    .then(|| "top-left of a 2x2 block of walls".to_string())
+            });
+        let report = validator.validate(&level);
+        assert_eq!(report.violations().len(), 2);
+        assert_eq!(
+            report.of_rule("no-2x2-walls").next().unwrap().coords[..],
+            [0, 0]
+        );
+        assert_eq!(
+            report.of_rule("known-tile").next().unwrap().coords[..],
+            [2, 0]
+        );
+        assert!(report
+            .to_string()
+            .contains("at [2, 0]: known-tile: unknown tile '?'"));
+
+        level[[0, 0]] = '.';
+        level[[2, 0]] = '#';
+        assert!(validator.validate(&level).is_valid());
+    }
+}
@@ -0,0 +1,322 @@
+use std::mem::size_of;
+
+use crate::{error::check_coords, layout::view_layout, Coord, Grid, GridError, Layout, Region};
+
+/// A borrowed rectangular part of a grid, addressed by coordinates relative
+/// to its origin. Created with [`Grid::view`].
+#[derive(Debug, Clone)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    region: Region,
+    shape: Vec<usize>,
+}
+
+/// A mutably borrowed rectangular part of a grid. Created with
+/// [`Grid::view_mut`].
+#[derive(Debug)]
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    region: Region,
+    shape: Vec<usize>,
+}
+
+/// Flat index into `grid` of a cell given relative to `region`, checking it
+/// against the view's `shape`.
+fn flat_index<T>(
+    grid: &Grid<T>,
+    region: &Region,
+    shape: &[usize],
+    local: &[usize],
+) -> Result<usize, GridError> {
+    check_coords(local, shape)?;
+    let coords = region
+        .to_grid_coords(local)
+        .expect("checked against the view's shape");
+    grid.translate_index(&coords)
+}
+
+/// Flat `(start, len)` spans of the contiguous runs of `region` in `grid`,
+/// in increasing order.
+fn spans<T>(grid: &Grid<T>, region: &Region) -> impl Iterator<Item = (usize, usize)> {
+    let (starts, row_len) = region.row_starts();
+    let starts: Vec<usize> = starts
+        .iter()
+        .map(|coords| {
+            grid.translate_index(&coords)
+                .expect("view is inside the grid")
+        })
+        .collect();
+    starts.into_iter().map(move |start| (start, row_len))
+}
+
+impl<'a, T> GridView<'a, T> {
+    /// The part of the grid this view covers.
+    pub fn region(&self) -> &Region {
+        &self.region
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn get(&self, local: &[usize]) -> Result<&'a T, GridError> {
+        let index = flat_index(self.grid, &self.region, &self.shape, local)?;
+        Ok(&self.grid.grid[index])
+    }
+
+    pub fn get_opt(&self, local: &[usize]) -> Option<&'a T> {
+        self.grid.get_opt(&self.region.to_grid_coords(local)?)
+    }
+
+    /// Iterates over the view's cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> {
+        let grid = self.grid;
+        spans(grid, &self.region).flat_map(move |(start, len)| &grid.grid[start..start + len])
+    }
+
+    /// A narrower view within this one, with `origin` relative to this
+    /// view's origin.
+    pub fn view(&self, origin: &[usize], shape: &[usize]) -> Result<GridView<'a, T>, GridError> {
+        let region = Region::from_origin_shape(origin, shape);
+        region.check_within(&self.shape)?;
+        let origin = self.region.origin();
+        self.grid.view(
+            &region
+                .origin()
+                .iter()
+                .zip(&origin)
+                .map(|(a, b)| a + b)
+                .collect::<Coord>(),
+            shape,
+        )
+    }
+}
+
+impl<'a, T> GridViewMut<'a, T> {
+    /// The part of the grid this view covers.
+    pub fn region(&self) -> &Region {
+        &self.region
+    }
+
+    pub fn dimensions(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn get(&self, local: &[usize]) -> Result<&T, GridError> {
+        let index = flat_index(self.grid, &self.region, &self.shape, local)?;
+        Ok(&self.grid.grid[index])
+    }
+
+    pub fn get_mut(&mut self, local: &[usize]) -> Result<&mut T, GridError> {
+        let index = flat_index(self.grid, &self.region, &self.shape, local)?;
+        Ok(&mut self.grid.grid[index])
+    }
+
+    pub fn set(&mut self, local: &[usize], val: T) -> Result<(), GridError> {
+        *self.get_mut(local)? = val;
+        Ok(())
+    }
+
+    pub fn get_opt(&self, local: &[usize]) -> Option<&T> {
+        self.grid.get_opt(&self.region.to_grid_coords(local)?)
+    }
+
+    pub fn get_mut_opt(&mut self, local: &[usize]) -> Option<&mut T> {
+        self.grid.get_mut_opt(&self.region.to_grid_coords(local)?)
+    }
+
+    /// Iterates over the view's cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let grid = &*self.grid;
+        spans(grid, &self.region).flat_map(move |(start, len)| &grid.grid[start..start + len])
+    }
+
+    /// Iterates mutably over the view's cells in row-major order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let spans: Vec<_> = spans(self.grid, &self.region).collect();
+        let mut rest = &mut self.grid.grid[..];
+        let mut consumed = 0;
+        let mut rows = Vec::with_capacity(spans.len());
+        for (start, len) in spans {
+            let (_, tail) = std::mem::take(&mut rest).split_at_mut(start - consumed);
+            let (row, tail) = tail.split_at_mut(len);
+            rows.push(row);
+            rest = tail;
+            consumed = start + len;
+        }
+        rows.into_iter().flatten()
+    }
+
+    /// A read-only view of the same cells.
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self.grid,
+            region: self.region.clone(),
+            shape: self.shape.clone(),
+        }
+    }
+}
+
+impl<T: Clone> GridView<'_, T> {
+    /// Copies the view's cells into a new grid.
+    pub fn to_grid(&self) -> Grid<T> {
+        Grid::from_parts(self.iter().cloned().collect(), self.shape.clone())
+    }
+}
+
+impl<T: Clone> GridViewMut<'_, T> {
+    /// Sets every cell in the view to `val`.
+    pub fn fill(&mut self, val: T) {
+        let spans: Vec<_> = spans(self.grid, &self.region).collect();
+        for (start, len) in spans {
+            self.grid.grid[start..start + len].fill(val.clone());
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Sets the `shape` cells starting at `origin` to `value`, or none of
+    /// them if the region doesn't fit in the grid.
+    pub fn fill_region(
+        &mut self,
+        origin: &[usize],
+        shape: &[usize],
+        value: T,
+    ) -> Result<(), GridError> {
+        self.view_mut(origin, shape)?.fill(value);
+        Ok(())
+    }
+}
+
+impl<T> GridView<'_, T> {
+    /// The strides of the underlying grid, in elements.
+    pub fn strides(&self) -> Vec<usize> {
+        self.grid.strides()
+    }
+
+    /// Whether the view's cells are one unbroken run of the grid's buffer,
+    /// as when it spans whole rows.
+    pub fn is_contiguous(&self) -> bool {
+        self.layout().is_contiguous()
+    }
+
+    pub fn layout(&self) -> Layout {
+        view_layout(self.grid, &self.region, &self.shape)
+    }
+
+    /// Pointer to the view's first cell. Other cells are found through
+    /// [`GridView::strides`], as the view's rows needn't be adjacent.
+    pub fn as_ptr(&self) -> *const T {
+        self.grid.as_ptr().wrapping_add(self.layout().offset)
+    }
+
+    pub fn element_size(&self) -> usize {
+        size_of::<T>()
+    }
+}
+
+impl<T> GridViewMut<'_, T> {
+    pub fn strides(&self) -> Vec<usize> {
+        self.grid.strides()
+    }
+
+    pub fn is_contiguous(&self) -> bool {
+        self.layout().is_contiguous()
+    }
+
+    pub fn layout(&self) -> Layout {
+        view_layout(self.grid, &self.region, &self.shape)
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.grid.as_ptr().wrapping_add(self.layout().offset)
+    }
+
+    /// Mutable counterpart of [`GridViewMut::as_ptr`]. Only cells inside the
+    /// view may be written through it.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        let offset = self.layout().offset;
+        self.grid.as_mut_ptr().wrapping_add(offset)
+    }
+
+    pub fn element_size(&self) -> usize {
+        size_of::<T>()
+    }
+}
+
+impl<T> Grid<T> {
+    /// Borrows the `shape` cells starting at `origin` without copying them.
+    pub fn view(&self, origin: &[usize], shape: &[usize]) -> Result<GridView<'_, T>, GridError> {
+        let region = Region::from_origin_shape(origin, shape);
+        region.check_within(&self.dimensions)?;
+        Ok(GridView {
+            grid: self,
+            region,
+            shape: shape.to_vec(),
+        })
+    }
+
+    /// Mutably borrows the `shape` cells starting at `origin`.
+    pub fn view_mut(
+        &mut self,
+        origin: &[usize],
+        shape: &[usize],
+    ) -> Result<GridViewMut<'_, T>, GridError> {
+        let region = Region::from_origin_shape(origin, shape);
+        region.check_within(&self.dimensions)?;
+        Ok(GridViewMut {
+            grid: self,
+            region,
+            shape: shape.to_vec(),
+        })
+    }
+
+    /// Mutably borrows every cell, for handing out write access without
+    /// letting the grid be resized.
+    pub(crate) fn whole_view_mut(&mut self) -> GridViewMut<'_, T> {
+        let shape = self.dimensions.clone();
+        GridViewMut {
+            region: Region::from_origin_shape(&vec![0; shape.len()], &shape),
+            grid: self,
+            shape,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views_over_tiles() {
+        let mut grid = Grid::new(0, vec![4, 5]);
+        for (i, v) in grid.iter_mut().enumerate() {
+            *v = i;
+        }
+
+        let view = grid.view(&[1, 2], &[2, 3]).unwrap();
+        assert_eq!(view.dimensions(), &[2, 3]);
+        assert_eq!(*view.get(&[0, 0]).unwrap(), 7);
+        assert_eq!(view.get_opt(&[1, 2]), Some(&14));
+        assert!(view.get(&[2, 0]).is_err());
+        assert_eq!(
+            view.iter().copied().collect::<Vec<_>>(),
+            vec![7, 8, 9, 12, 13, 14]
+        );
+        let inner = view.view(&[1, 1], &[1, 2]).unwrap();
+        assert_eq!(
+            inner.to_grid().iter().copied().collect::<Vec<_>>(),
+            vec![13, 14]
+        );
+        assert!(view.view(&[1, 1], &[2, 2]).is_err());
+        assert!(grid.view(&[3, 3], &[2, 2]).is_err());
+
+        let mut tile = grid.view_mut(&[2, 0], &[2, 2]).unwrap();
+        tile.set(&[0, 1], 100).unwrap();
+        tile.iter_mut().for_each(|v| *v += 1);
+        assert_eq!(tile.as_view().iter().count(), 4);
+        assert_eq!(grid.get_opt(&[2, 1]), Some(&101));
+        assert_eq!(grid.get_opt(&[3, 0]), Some(&16));
+        assert_eq!(grid.get_opt(&[2, 2]), Some(&12));
+    }
+}
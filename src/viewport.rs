@@ -0,0 +1,245 @@
+use crate::{spatial::split_position, Coord, Grid, GridError, GridView};
+
+/// Smallest zoom a viewport accepts, so the visible extent stays finite.
+const MIN_ZOOM: f64 = 1e-6;
+
+/// A camera over a grid: a position in cell units and a zoom in screen
+/// units (pixels, terminal characters) per cell, shared by every axis so
+/// cells keep their shape at any zoom. Each frame it works out which cells
+/// are on screen and where they land.
+///
+/// Cell `[r, c]` covers positions `r..r + 1` and `c..c + 1`, so a camera at
+/// `[10.5, 10.5]` is centered on the middle of cell `[10, 10]`.
+///
+/// ```
+/// use md_grid::{Grid, GridViewport};
+///
+/// let world = Grid::new(0u8, vec![100, 100]);
+/// let mut camera = GridViewport::new(vec![24, 80]);
+/// camera.set_zoom(2.0);
+/// camera.look_at(&[50.0, 50.0]);
+///
+/// let frame = camera.frame(&world).unwrap();
+/// let view = frame.view.unwrap();
+/// assert_eq!(view.region().origin()[..], [44, 30]);
+/// assert_eq!(view.dimensions(), &[12, 40]);
+/// assert_eq!(frame.screen_offset, [0.0, 0.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridViewport {
+    center: Vec<f64>,
+    screen: Vec<usize>,
+    zoom: f64,
+    clamp_to_edges: bool,
+}
+
+/// What a [`GridViewport`] sees of a grid in one frame.
+#[derive(Debug, Clone)]
+pub struct ViewportFrame<'a, T> {
+    /// The cells at least partly on screen, or `None` if the camera is
+    /// looking entirely past the grid.
+    pub view: Option<GridView<'a, T>>,
+    /// Position, in cell units, shown at the screen's first corner. May be
+    /// negative or past the grid when the camera sees beyond its edges.
+    pub origin: Vec<f64>,
+    /// Screen position of the first corner of the view's first cell. It is
+    /// negative along an axis where that cell is cut off by the screen's
+    /// edge, and positive where empty space comes before the grid.
+    pub screen_offset: Vec<f64>,
+    /// Screen units per cell.
+    pub zoom: f64,
+}
+
+impl<T> ViewportFrame<'_, T> {
+    /// The on-screen cell under the screen point `screen`, if any, and where
+    /// in that cell the point lies as a fraction from 0 up to but not
+    /// including 1 along each axis. A point on the edge between two cells
+    /// picks the one it is the first corner or edge of.
+    pub fn pick(&self, screen: &[f64]) -> Option<(Coord, Vec<f64>)> {
+        let region = self.view.as_ref()?.region();
+        if screen.len() != self.origin.len() {
+            return None;
+        }
+        let mut cell = Coord::new();
+        let mut offset = Vec::with_capacity(screen.len());
+        for ((&s, &origin), range) in screen.iter().zip(&self.origin).zip(region.ranges()) {
+            let (c, o) = split_position(origin + s / self.zoom, range.end)?;
+            if c < range.start {
+                return None;
+            }
+            cell.push(c);
+            offset.push(o);
+        }
+        Some((cell, offset))
+    }
+}
+
+impl GridViewport {
+    /// A camera at the grid's first corner, at one screen unit per cell,
+    /// for a screen of `screen` units along each axis. It stays inside the
+    /// grid by default; see [`GridViewport::clamp_to_edges`].
+    pub fn new(screen: Vec<usize>) -> Self {
+        Self {
+            center: vec![0.0; screen.len()],
+            screen,
+            zoom: 1.0,
+            clamp_to_edges: true,
+        }
+    }
+
+    /// Whether to keep the camera from showing space beyond the grid's
+    /// edges. When the grid is smaller than the screen along an axis, it is
+    /// centered along that axis instead.
+    pub fn clamp_to_edges(mut self, clamp: bool) -> Self {
+        self.clamp_to_edges = clamp;
+        self
+    }
+
+    pub fn center(&self) -> &[f64] {
+        &self.center
+    }
+
+    pub fn screen(&self) -> &[usize] {
+        &self.screen
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Moves the camera to `center`, in cell units.
+    pub fn look_at(&mut self, center: &[f64]) {
+        self.center = center.to_vec();
+    }
+
+    /// Moves the camera by `delta` cells along each axis.
+    pub fn pan(&mut self, delta: &[f64]) {
+        for (c, d) in self.center.iter_mut().zip(delta) {
+            *c += d;
+        }
+    }
+
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.zoom = zoom.max(MIN_ZOOM);
+    }
+
+    /// Multiplies the zoom by `factor`, keeping the camera's center fixed.
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.set_zoom(self.zoom * factor);
+    }
+
+    /// Changes the screen size, e.g. when the window is resized.
+    pub fn resize(&mut self, screen: Vec<usize>) {
+        self.center.resize(screen.len(), 0.0);
+        self.screen = screen;
+    }
+
+    /// Number of cells that fit on screen along each axis at the current
+    /// zoom.
+    pub fn extent(&self) -> Vec<f64> {
+        self.screen.iter().map(|&s| s as f64 / self.zoom).collect()
+    }
+
+    /// The part of `grid` on screen this frame. Fails with
+    /// [`GridError::UnsupportedAxes`] if the grid and screen have different
+    /// numbers of axes.
+    pub fn frame<'a, T>(&self, grid: &'a Grid<T>) -> Result<ViewportFrame<'a, T>, GridError> {
+        let dimensions = grid.dimensions();
+        if dimensions.len() != self.screen.len() {
+            return Err(GridError::UnsupportedAxes {
+                expected: self.screen.len(),
+                shape: dimensions.to_vec(),
+            });
+        }
+
+        let mut origin = Vec::with_capacity(dimensions.len());
+        let mut first = Vec::with_capacity(dimensions.len());
+        let mut shape = Vec::with_capacity(dimensions.len());
+        let mut screen_offset = Vec::with_capacity(dimensions.len());
+        for ((&len, &center), extent) in dimensions.iter().zip(&self.center).zip(self.extent()) {
+            let len_f = len as f64;
+            let mut start = center - extent / 2.0;
+            if self.clamp_to_edges {
+                start = if extent >= len_f {
+                    (len_f - extent) / 2.0
+                } else {
+                    start.clamp(0.0, len_f - extent)
+                };
+            }
+            let low = start.floor().clamp(0.0, len_f) as usize;
+            let high = (start + extent).ceil().clamp(0.0, len_f) as usize;
+            origin.push(start);
+            first.push(low);
+            shape.push(high.saturating_sub(low));
+            screen_offset.push((low as f64 - start) * self.zoom);
+        }
+
+        let view = if shape.contains(&0) {
+            None
+        } else {
+            Some(grid.view(&first, &shape)?)
+        };
+        Ok(ViewportFrame {
+            view,
+            origin,
+            screen_offset,
+            zoom: self.zoom,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_pick(
+        camera: &GridViewport,
+        grid: &Grid<usize>,
+        screen: &[f64],
+    ) -> Option<(Coord, Vec<f64>)> {
+        camera.frame(grid).unwrap().pick(screen)
+    }
+
+    #[test]
+    fn edges_and_zoom() {
+        let world = Grid::from_fn(vec![100, 100], |c| c[0] * 100 + c[1]);
+        let mut camera = GridViewport::new(vec![20, 40]);
+        camera.set_zoom(2.0);
+        assert_eq!(camera.extent(), [10.0, 20.0]);
+
+        // Half a cell off the grid lines, the first cells are cut in half.
+        camera.look_at(&[50.5, 50.25]);
+        let frame = camera.frame(&world).unwrap();
+        let view = frame.view.unwrap();
+        assert_eq!(view.region().origin()[..], [45, 40]);
+        assert_eq!(view.dimensions(), &[11, 21]);
+        assert_eq!(frame.screen_offset, [-1.0, -0.5]);
+
+        // Clamped at the corner, then free to look past it.
+        camera.look_at(&[0.0, 0.0]);
+        assert_eq!(camera.frame(&world).unwrap().origin, [0.0, 0.0]);
+        let camera_free = camera.clone().clamp_to_edges(false);
+        let frame = camera_free.frame(&world).unwrap();
+        assert_eq!(frame.view.unwrap().dimensions(), &[5, 10]);
+        assert_eq!(frame.screen_offset, [10.0, 20.0]);
+
+        // Zoomed far out, the whole grid is centered on screen.
+        camera.zoom_by(0.05);
+        let frame = camera.frame(&world).unwrap();
+        assert_eq!(frame.view.unwrap().dimensions(), &[100, 100]);
+        assert_eq!(frame.screen_offset, [5.0, 15.0]);
+
+        // Picking through the clamped, zoomed-out frame: 5 screen units of
+        // margin, then 0.1 screen units per cell.
+        let (cell, offset) = frame_pick(&camera, &world, &[5.0, 15.05]).unwrap();
+        assert_eq!(cell[..], [0, 0]);
+        assert!((offset[1] - 0.5).abs() < 1e-9);
+        assert_eq!(frame_pick(&camera, &world, &[4.9, 20.0]), None);
+        assert_eq!(frame_pick(&camera, &world, &[25.0, 35.0]), None);
+
+        let mut lost = camera_free;
+        lost.look_at(&[-50.0, 0.0]);
+        assert!(lost.frame(&world).unwrap().view.is_none());
+        assert!(GridViewport::new(vec![10]).frame(&world).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{Coord, Grid, GridError};
+
+/// Cells on the Bresenham line from `from` to `to`, excluding both ends.
+fn between(from: [usize; 2], to: [usize; 2]) -> impl Iterator<Item = [usize; 2]> {
+    let (mut r, mut c) = (from[0] as isize, from[1] as isize);
+    let (r1, c1) = (to[0] as isize, to[1] as isize);
+    let (dr, dc) = ((r1 - r).abs(), -(c1 - c).abs());
+    let (sr, sc) = ((r1 - r).signum(), (c1 - c).signum());
+    let mut err = dr + dc;
+
+    std::iter::from_fn(move || {
+        let e2 = 2 * err;
+        if e2 >= dc {
+            err += dc;
+            r += sr;
+        }
+        if e2 <= dr {
+            err += dr;
+            c += sc;
+        }
+        ((r, c) != (r1, c1)).then_some([r as usize, c as usize])
+    })
+}
+
+impl<T> Grid<T> {
+    /// Whether a straight line from `from` to `to` in a 2D grid passes no
+    /// cell for which `blocks` is true. The end cells themselves never block,
+    /// so walls are visible but not seen through.
+    pub fn line_of_sight<B>(
+        &self,
+        from: &[usize],
+        to: &[usize],
+        mut blocks: B,
+    ) -> Result<bool, GridError>
+    where
+        B: FnMut(&T) -> bool,
+    {
+        self.check_2d()?;
+        let from = self.translate_index(from).map(|_| [from[0], from[1]])?;
+        let to = self.translate_index(to).map(|_| [to[0], to[1]])?;
+        if from == to {
+            return Ok(true);
+        }
+
+        let cols = self.dimensions[1];
+        Ok(between(from, to).all(|[r, c]| !blocks(&self.grid[r * cols + c])))
+    }
+
+    /// Counts, for every cell of a 2D grid, how many `observers` can see it.
+    ///
+    /// Each observer casts one ray to every cell on the grid's border and
+    /// marks the cells along it until an opaque one, which is marked too.
+    /// The rays share their cells near the observer, so this is much cheaper
+    /// than a [`Grid::line_of_sight`] test per cell, and agrees with it up to
+    /// how lines are rasterized.
+    pub fn visibility_map<B>(
+        &self,
+        observers: &[Coord],
+        mut blocks: B,
+    ) -> Result<Grid<usize>, GridError>
+    where
+        B: FnMut(&T) -> bool,
+    {
+        self.check_2d()?;
+        let (rows, cols) = (self.dimensions[0], self.dimensions[1]);
+
+        // Evaluate `blocks` once per cell rather than once per ray step.
+        let opaque: Vec<bool> = self.grid.iter().map(&mut blocks).collect();
+
+        let border: Vec<[usize; 2]> = (0..cols)
+            .flat_map(|c| [[0, c], [rows - 1, c]])
+            .chain((1..rows.saturating_sub(1)).flat_map(|r| [[r, 0], [r, cols - 1]]))
+            .collect();
+
+        let mut counts = vec![0; self.grid.len()];
+        // The last observer to have seen each cell, so rays that overlap
+        // count it once.
+        let mut seen_by = vec![usize::MAX; self.grid.len()];
+        for (n, observer) in observers.iter().enumerate() {
+            let start = self.translate_index(observer)?;
+            let from = [observer[0], observer[1]];
+            seen_by[start] = n;
+            counts[start] += 1;
+
+            for &to in &border {
+                if to == from {
+                    continue;
+                }
+                for [r, c] in between(from, to).chain(std::iter::once(to)) {
+                    let i = r * cols + c;
+                    if seen_by[i] != n {
+                        seen_by[i] = n;
+                        counts[i] += 1;
+                    }
+                    if opaque[i] {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(Grid::from_parts(counts, self.dimensions.clone()))
+    }
+
+    fn check_2d(&self) -> Result<(), GridError> {
+        if self.axes != 2 {
+            return Err(GridError::UnsupportedAxes {
+                expected: 2,
+                shape: self.dimensions.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smallvec;
+
+    #[test]
+    fn visibility_map() {
+        // .....
+        // ..#..
+        // .....
+        let mut grid = Grid::new('.', vec![3, 5]);
+        grid.set(&[1, 2], '#').unwrap();
+
+        assert!(!grid.line_of_sight(&[1, 0], &[1, 4], |&c| c == '#').unwrap());
+        assert!(grid.line_of_sight(&[1, 0], &[1, 2], |&c| c == '#').unwrap());
+        assert!(grid.line_of_sight(&[0, 0], &[0, 4], |&c| c == '#').unwrap());
+
+        let map = grid
+            .visibility_map(&[smallvec![1, 0], smallvec![1, 4]], |&c| c == '#')
+            .unwrap();
+        assert_eq!(*map.get(&[1, 2]).unwrap(), 2);
+        assert_eq!(*map.get(&[1, 3]).unwrap(), 1);
+        assert_eq!(*map.get(&[0, 2]).unwrap(), 2);
+
+        assert!(grid.visibility_map(&[smallvec![3, 0]], |_| false).is_err());
+
+        let open = grid.visibility_map(&[smallvec![1, 1]], |_| false).unwrap();
+        assert!(open.iter().all(|&n| n == 1));
+
+        // The wall shadows the far corner of a room seen from a corner.
+        let mut room = Grid::new('.', vec![6, 6]);
+        room.set(&[2, 2], '#').unwrap();
+        let map = room
+            .visibility_map(&[smallvec![0, 0]], |&c| c == '#')
+            .unwrap();
+        assert_eq!(*map.get(&[2, 2]).unwrap(), 1);
+        assert_eq!(*map.get(&[5, 5]).unwrap(), 0);
+        assert_eq!(*map.get(&[0, 5]).unwrap(), 1);
+        assert_eq!(*map.get(&[5, 0]).unwrap(), 1);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{direction::offset_coords, rng::Rng, Coord, Direction, Grid, GridError};
+
+/// Parameters for [`Grid::random_walk`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomWalk {
+    /// Seed for every walker's steps. The same seed and parameters always
+    /// carve the same cells.
+    pub seed: u64,
+    /// Number of walkers, each starting from the same cell.
+    pub walkers: usize,
+    /// Steps each walker takes at most.
+    pub steps: usize,
+    /// A direction walkers favor, and the chance from 0 to 1 that a step
+    /// goes that way instead of in a uniformly random direction.
+    pub bias: Option<(Direction, f64)>,
+    /// Stop every walker once this fraction of the grid has been written,
+    /// from 0 to 1.
+    pub coverage: f64,
+}
+
+impl RandomWalk {
+    pub fn new(steps: usize, seed: u64) -> Self {
+        Self {
+            steps,
+            seed,
+            ..Self::default()
+        }
+    }
+
+    pub fn walkers(mut self, walkers: usize) -> Self {
+        self.walkers = walkers;
+        self
+    }
+
+    pub fn bias(mut self, direction: Direction, strength: f64) -> Self {
+        self.bias = Some((direction, strength));
+        self
+    }
+
+    pub fn coverage(mut self, coverage: f64) -> Self {
+        self.coverage = coverage;
+        self
+    }
+}
+
+impl Default for RandomWalk {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            walkers: 1,
+            steps: 1_000,
+            bias: None,
+            coverage: 1.0,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Drunkard's-walk carving: walkers start at `start` and step to a random
+    /// orthogonal neighbor each turn, writing `value` to every cell they
+    /// visit. Steps off the edge of the grid are skipped. Returns the number
+    /// of distinct cells written.
+    ///
+    /// ```
+    /// use md_grid::{Grid, RandomWalk};
+    ///
+    /// let mut cave = Grid::new('#', vec![40, 60]);
+    /// let params = RandomWalk::new(2_000, 7).walkers(4).coverage(0.4);
+    /// let carved = cave.random_walk(&[20, 30], '.', &params).unwrap();
+    /// assert_eq!(carved, cave.iter().filter(|&&c| c == '.').count());
+    /// assert!(carved <= 960);
+    /// ```
+    pub fn random_walk(
+        &mut self,
+        start: &[usize],
+        value: T,
+        params: &RandomWalk,
+    ) -> Result<usize, GridError> {
+        let start = Coord::from_slice(start);
+        self.translate_index(&start)?;
+        let directions: Vec<Vec<isize>> = (0..self.axes)
+            .flat_map(|axis| [Direction::Negative(axis), Direction::Positive(axis)])
+            .map(|dir| dir.offset(self.axes))
+            .collect();
+        let bias = params
+            .bias
+            .map(|(dir, strength)| (dir.offset(self.axes), strength));
+        let target = (params.coverage.clamp(0.0, 1.0) * self.grid.len() as f64).ceil() as usize;
+
+        let mut written = vec![false; self.grid.len()];
+        let mut count = 0;
+        'walkers: for walker in 0..params.walkers {
+            let mut rng = Rng::for_stream(params.seed, walker as u64);
+            let mut coords = start.clone();
+            for step in 0..=params.steps {
+                if step > 0 {
+                    let offset = match &bias {
+                        Some((offset, strength)) if rng.next_f64() < *strength => offset,
+                        _ => &directions[rng.below(directions.len())],
+                    };
+                    if let Some(next) = offset_coords(&coords, offset, &self.dimensions) {
+                        coords = next;
+                    }
+                }
+                let index = self.translate_index(&coords)?;
+                if !written[index] {
+                    written[index] = true;
+                    count += 1;
+                    *self.cell_mut(index) = value.clone();
+                }
+                if count >= target {
+                    break 'walkers;
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_and_biased() {
+        let params = RandomWalk::new(200, 3).walkers(3);
+        let mut a = Grid::new(0u8, vec![30, 30]);
+        let mut b = Grid::new(0u8, vec![30, 30]);
+        let carved = a.random_walk(&[15, 15], 1, &params).unwrap();
+        b.random_walk(&[15, 15], 1, &params).unwrap();
+        assert!(a.iter().eq(b.iter()));
+        assert_eq!(a.iter().filter(|&&v| v == 1).count(), carved);
+
+        // Walkers pushed east always reach the right-hand edge.
+        let mut corridor = Grid::new(0u8, vec![5, 50]);
+        let east = RandomWalk::new(500, 3).bias(Direction::EAST, 0.9);
+        corridor.random_walk(&[2, 0], 1, &east).unwrap();
+        assert!((0..5).any(|row| corridor[[row, 49]] == 1));
+
+        let mut small = Grid::new(0u8, vec![10, 10]);
+        let half = RandomWalk::new(10_000, 1).coverage(0.5);
+        assert_eq!(small.random_walk(&[0, 0], 1, &half).unwrap(), 50);
+        assert!(small.random_walk(&[10, 0], 1, &half).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use js_sys::{Float32Array, Float64Array, Int32Array, Uint16Array, Uint8Array};
+
+use crate::{Grid, GridError, Shape};
+
+macro_rules! impl_typed_array {
+    ($($ty:ty => $array:ident),* $(,)?) => {$(
+        impl Grid<$ty> {
+            /// A JS typed array viewing the cells in place, in row-major
+            /// order, without copying them.
+            ///
+            /// # Safety
+            ///
+            /// The view points into WebAssembly memory and is invalidated by
+            /// any allocation that grows it, or by modifying or dropping the
+            /// grid. Use it immediately (e.g. to upload one frame to a
+            /// canvas) and don't keep it around.
+            pub unsafe fn typed_array_view(&self) -> $array {
+                // SAFETY: the caller guarantees the view is dropped before
+                // wasm memory grows or the grid is modified or dropped.
+                unsafe { $array::view(&self.grid) }
+            }
+
+            /// Copies the cells into a new JS typed array, in row-major order.
+            pub fn to_typed_array(&self) -> $array {
+                $array::from(&self.grid[..])
+            }
+
+            /// Builds a grid by copying a row-major JS typed array, which
+            /// must hold exactly as many cells as `dimensions` describe.
+            pub fn from_typed_array(
+                array: &$array,
+                dimensions: Vec<usize>,
+            ) -> Result<Self, GridError> {
+                let len = array.length() as usize;
+                if Shape::from(&dimensions[..]).checked_cell_count() != Some(len) {
+                    return Err(GridError::LengthMismatch {
+                        len,
+                        shape: dimensions,
+                    });
+                }
+                Ok(Grid::from_parts(array.to_vec(), dimensions))
+            }
+
+            /// Overwrites every cell from a row-major JS typed array of the
+            /// same length, reusing the grid's storage.
+            pub fn copy_from_typed_array(&mut self, array: &$array) -> Result<(), GridError> {
+                if array.length() as usize != self.grid.len() {
+                    return Err(GridError::LengthMismatch {
+                        len: array.length() as usize,
+                        shape: self.dimensions.clone(),
+                    });
+                }
+                array.copy_to(&mut self.grid);
+                Ok(())
+            }
+        }
+    )*};
+}
+
+impl_typed_array!(
+    u8 => Uint8Array,
+    u16 => Uint16Array,
+    i32 => Int32Array,
+    f32 => Float32Array,
+    f64 => Float64Array,
+);
@@ -0,0 +1,95 @@
+use crate::{Connectivity, Coord, Grid, GridError};
+
+impl<T> Grid<T> {
+    /// Wraps signed coordinates onto the grid, treating every axis as
+    /// periodic: `-1` is the last cell of an axis and its length is the
+    /// first.
+    pub fn wrap_coords(&self, target: &[isize]) -> Result<Coord, GridError> {
+        if target.len() != self.axes {
+            return Err(GridError::UnsupportedAxes {
+                expected: target.len(),
+                shape: self.dimensions.clone(),
+            });
+        }
+        let coords: Coord = target
+            .iter()
+            .zip(&self.dimensions)
+            .map(|(&c, &len)| match len {
+                0 => 0,
+                len => c.rem_euclid(len as isize) as usize,
+            })
+            .collect();
+        // Only an empty axis can leave the wrapped coordinates out of bounds.
+        self.translate_index(&coords)?;
+        Ok(coords)
+    }
+
+    /// Like [`Grid::get`], but coordinates wrap around every axis.
+    pub fn get_wrapped(&self, target: &[isize]) -> Result<&T, GridError> {
+        let coords = self.wrap_coords(target)?;
+        self.get(&coords)
+    }
+
+    /// Like [`Grid::get_mut`], but coordinates wrap around every axis.
+    pub fn get_wrapped_mut(&mut self, target: &[isize]) -> Result<&mut T, GridError> {
+        let coords = self.wrap_coords(target)?;
+        self.get_mut(&coords)
+    }
+
+    /// Like [`Grid::set`], but coordinates wrap around every axis.
+    pub fn set_wrapped(&mut self, target: &[isize], val: T) -> Result<(), GridError> {
+        let coords = self.wrap_coords(target)?;
+        self.set(&coords, val)
+    }
+
+    /// Like [`Grid::neighbors`], but on a torus: neighbors past an edge
+    /// come from the opposite edge. On axes shorter than 3 the same cell
+    /// can be adjacent from both sides and is yielded once for each.
+    pub fn neighbors_wrapped(
+        &self,
+        target: &[isize],
+        connectivity: Connectivity,
+    ) -> Result<impl Iterator<Item = (Coord, &T)>, GridError> {
+        let center = self.wrap_coords(target)?;
+        Ok(connectivity
+            .offsets(self.axes)
+            .into_iter()
+            .map(move |offset| {
+                let coords: Coord = center
+                    .iter()
+                    .zip(&offset)
+                    .zip(&self.dimensions)
+                    .map(|((&c, &o), &len)| (c as isize + o).rem_euclid(len as isize) as usize)
+                    .collect();
+                let index = self.checked_index(&coords).expect("wrapped onto the grid");
+                (coords, self.cell(index))
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn torus() {
+        let mut grid = Grid::new(0, vec![3, 4]);
+        grid.set_wrapped(&[-1, -1], 7).unwrap();
+        assert_eq!(grid.get_opt(&[2, 3]), Some(&7));
+        *grid.get_wrapped_mut(&[3, 8]).unwrap() = 1;
+        assert_eq!(*grid.get_wrapped(&[0, 0]).unwrap(), 1);
+        assert!(grid.get_wrapped(&[0]).is_err());
+
+        let around: Vec<_> = grid
+            .neighbors_wrapped(&[0, 0], Connectivity::Diagonal)
+            .unwrap()
+            .map(|(coords, &v)| (coords.to_vec(), v))
+            .collect();
+        assert_eq!(around.len(), 8);
+        assert!(around.contains(&(vec![2, 3], 7)));
+        assert!(around.contains(&(vec![1, 1], 0)));
+
+        let empty = Grid::new(0, vec![0, 4]);
+        assert!(empty.get_wrapped(&[5, 5]).is_err());
+    }
+}
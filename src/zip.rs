@@ -0,0 +1,196 @@
+use core::slice::{Iter, IterMut};
+
+use crate::{Grid, GridError, Shape};
+
+/// A grid borrowed shared or mutably, for iterating in lockstep with other
+/// grids via [`zip_iter!`](crate::zip_iter).
+pub trait ZipSource {
+    type Item;
+    type Iter: Iterator<Item = Self::Item>;
+
+    fn dimensions(&self) -> &[usize];
+    fn into_cells(self) -> Self::Iter;
+}
+
+impl<'a, T> ZipSource for &'a Grid<T> {
+    type Item = &'a T;
+    type Iter = Iter<'a, T>;
+
+    fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    fn into_cells(self) -> Self::Iter {
+        self.grid.iter()
+    }
+}
+
+impl<'a, T> ZipSource for &'a mut Grid<T> {
+    type Item = &'a mut T;
+    type Iter = IterMut<'a, T>;
+
+    fn dimensions(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    fn into_cells(self) -> Self::Iter {
+        self.grid.iter_mut()
+    }
+}
+
+/// One cell from each of three zipped grids.
+pub type Cells3<A, B, C> = (
+    <A as ZipSource>::Item,
+    <B as ZipSource>::Item,
+    <C as ZipSource>::Item,
+);
+
+/// One cell from each of four zipped grids.
+pub type Cells4<A, B, C, D> = (
+    <A as ZipSource>::Item,
+    <B as ZipSource>::Item,
+    <C as ZipSource>::Item,
+    <D as ZipSource>::Item,
+);
+
+fn check_shapes(shapes: &[&[usize]]) -> Result<(), GridError> {
+    let expected = Shape::from(shapes[0]);
+    shapes[1..].iter().try_for_each(|s| expected.check_eq(s))
+}
+
+pub fn zip2<A, B>(a: A, b: B) -> Result<impl Iterator<Item = (A::Item, B::Item)>, GridError>
+where
+    A: ZipSource,
+    B: ZipSource,
+{
+    check_shapes(&[a.dimensions(), b.dimensions()])?;
+    Ok(a.into_cells().zip(b.into_cells()))
+}
+
+pub fn zip3<A, B, C>(a: A, b: B, c: C) -> Result<impl Iterator<Item = Cells3<A, B, C>>, GridError>
+where
+    A: ZipSource,
+    B: ZipSource,
+    C: ZipSource,
+{
+    check_shapes(&[a.dimensions(), b.dimensions(), c.dimensions()])?;
+    Ok(a.into_cells()
+        .zip(b.into_cells())
+        .zip(c.into_cells())
+        .map(|((a, b), c)| (a, b, c)))
+}
+
+pub fn zip4<A, B, C, D>(
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+) -> Result<impl Iterator<Item = Cells4<A, B, C, D>>, GridError>
+where
+    A: ZipSource,
+    B: ZipSource,
+    C: ZipSource,
+    D: ZipSource,
+{
+    check_shapes(&[
+        a.dimensions(),
+        b.dimensions(),
+        c.dimensions(),
+        d.dimensions(),
+    ])?;
+    Ok(a.into_cells()
+        .zip(b.into_cells())
+        .zip(c.into_cells())
+        .zip(d.into_cells())
+        .map(|(((a, b), c), d)| (a, b, c, d)))
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid of the same shape by applying `f` to every cell.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid::from_parts(self.grid.iter().map(f).collect(), self.dimensions.clone())
+    }
+
+    /// Builds a grid by combining the cells of two same-shaped grids with
+    /// `f`, failing with [`GridError::ShapeMismatch`] if their shapes differ.
+    pub fn zip_with<U, V, F>(&self, other: &Grid<U>, mut f: F) -> Result<Grid<V>, GridError>
+    where
+        F: FnMut(&T, &U) -> V,
+    {
+        Ok(Grid::from_parts(
+            zip2(self, other)?.map(|(a, b)| f(a, b)).collect(),
+            self.dimensions.clone(),
+        ))
+    }
+}
+
+/// Iterates 2 to 4 same-shaped grids in lockstep, in row-major order,
+/// yielding a tuple of one reference per grid. Pass `&grid` or `&mut grid`
+/// to choose shared or mutable access per grid. Shapes are checked once
+/// up front, returning [`GridError::ShapeMismatch`] if they differ.
+///
+/// ```
+/// use md_grid::{zip_iter, Grid};
+///
+/// let velocity = Grid::new(2.0, vec![4, 4]);
+/// let mut position = Grid::new(0.0, vec![4, 4]);
+/// for (p, v) in zip_iter!(&mut position, &velocity).unwrap() {
+///     *p += v;
+/// }
+/// ```
+#[macro_export]
+macro_rules! zip_iter {
+    ($a:expr, $b:expr $(,)?) => {
+        $crate::zip::zip2($a, $b)
+    };
+    ($a:expr, $b:expr, $c:expr $(,)?) => {
+        $crate::zip::zip3($a, $b, $c)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr $(,)?) => {
+        $crate::zip::zip4($a, $b, $c, $d)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zip_iter() {
+        let height = Grid::new(1, vec![2, 2]);
+        let mut water = Grid::new(2, vec![2, 2]);
+        let mut total = Grid::new(0, vec![2, 2]);
+
+        for (h, w, t) in zip_iter!(&height, &mut water, &mut total).unwrap() {
+            *w -= 1;
+            *t = h + *w;
+        }
+        assert!(total.iter().all(|&t| t == 2));
+        assert!(water.iter().all(|&w| w == 1));
+
+        let other = Grid::new(0, vec![4]);
+        assert_eq!(
+            zip_iter!(&height, &water, &other).err(),
+            Some(GridError::ShapeMismatch {
+                expected: vec![2, 2],
+                got: vec![4]
+            })
+        );
+    }
+
+    #[test]
+    fn map_and_zip_with() {
+        let a = Grid::from_fn(vec![2, 3], |c| (c[0] * 3 + c[1]) as f64);
+        let doubled = a.map(|v| v * 2.0);
+        assert_eq!(doubled.get_opt(&[1, 2]), Some(&10.0));
+
+        let sum = a.zip_with(&doubled, |x, y| x + y).unwrap();
+        assert!(sum.iter().copied().eq((0..6).map(|i| i as f64 * 3.0)));
+        let labels = a.map(|v| v.to_string());
+        assert_eq!(labels.get_opt(&[0, 1]).map(String::as_str), Some("1"));
+        assert!(a.zip_with(&Grid::new(0, vec![3, 2]), |x, _| *x).is_err());
+    }
+}
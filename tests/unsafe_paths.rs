@@ -0,0 +1,166 @@
+//! Exercises every path that relies on `unsafe`, with the edge cases most
+//! likely to expose an out-of-bounds access. These run as ordinary tests,
+//! but exist to be checked under Miri:
+//!
+//! ```text
+//! cargo +nightly miri test --test unsafe_paths --features unchecked-indexing,ffi
+//! ```
+//!
+//! or under AddressSanitizer:
+//!
+//! ```text
+//! RUSTFLAGS=-Zsanitizer=address cargo +nightly test --test unsafe_paths \
+//!     --features unchecked-indexing,ffi --target x86_64-unknown-linux-gnu
+//! ```
+
+use md_grid::{Connectivity, Grid, GridLike};
+
+#[test]
+fn indexing_at_the_edges() {
+    let mut grid = Grid::new(0u8, vec![3, 4]);
+    for coords in [[0, 0], [0, 3], [2, 0], [2, 3]] {
+        grid.set(&coords, 7).unwrap();
+        *grid.get_mut(&coords).unwrap() += 1;
+        *grid.get_mut_opt(&coords).unwrap() += 1;
+        assert_eq!(*grid.get(&coords).unwrap(), 9);
+        assert_eq!(grid.get_opt(&coords), Some(&9));
+    }
+    for coords in [&[3, 0][..], &[0, 4], &[usize::MAX, 0], &[0], &[0, 0, 0]] {
+        assert!(grid.get(coords).is_err());
+        assert!(grid.get_mut(coords).is_err());
+        assert!(grid.set(coords, 1).is_err());
+        assert_eq!(grid.get_opt(coords), None);
+        assert_eq!(grid.get_mut_opt(coords), None);
+    }
+}
+
+#[test]
+fn degenerate_shapes() {
+    let mut empty = Grid::new(1i32, vec![4, 0]);
+    assert!(empty.get(&[0, 0]).is_err());
+    assert!(empty.set(&[3, 0], 2).is_err());
+
+    let mut scalar = Grid::new(5i32, vec![]);
+    assert_eq!(scalar.get_opt(&[]), Some(&5));
+    scalar.set(&[], 6).unwrap();
+    assert_eq!(*scalar.get(&[]).unwrap(), 6);
+
+    let mut unit = Grid::new((), vec![2, 2]);
+    unit.set(&[1, 1], ()).unwrap();
+    assert!(unit.get(&[2, 1]).is_err());
+}
+
+#[test]
+fn convolution_and_neighbors() {
+    let one = Grid::new(2.0, vec![1, 1]);
+    let kernel = Grid::new(1.0, vec![5, 5]);
+    assert_eq!(one.convolve(&kernel).unwrap().get_opt(&[0, 0]), Some(&2.0));
+
+    let grid = Grid::new(1.0, vec![3, 2]);
+    let out = grid.convolve(&Grid::new(1.0, vec![3, 3])).unwrap();
+    assert_eq!(out.get_opt(&[0, 0]), Some(&4.0));
+    assert_eq!(out.get_opt(&[1, 1]), Some(&6.0));
+
+    for coords in [[0, 0], [2, 1]] {
+        let around = grid.successors(&coords, Connectivity::Diagonal, |_| true);
+        assert_eq!(around.len(), 3);
+    }
+    assert!(grid
+        .successors(&[5, 5], Connectivity::Orthogonal, |_| true)
+        .is_empty());
+}
+
+#[test]
+fn unchecked_lookups_at_the_edges() {
+    let mut grid = Grid::from_fn(vec![3, 1, 4], |c| c[0] * 4 + c[2]);
+    let corners = [[0, 0, 0], [0, 0, 3], [2, 0, 0], [2, 0, 3]];
+    for coords in corners {
+        let expected = coords[0] * 4 + coords[2];
+        // SAFETY: every corner is inside the 3x1x4 grid.
+        unsafe {
+            assert_eq!(*grid.get_unchecked(&coords), expected);
+            *grid.get_unchecked_mut(&coords) += 100;
+        }
+    }
+
+    let cells = grid.accessor::<3>().unwrap();
+    for coords in corners {
+        // SAFETY: as above.
+        assert!(unsafe { *cells.get_unchecked(coords) } >= 100);
+    }
+
+    let mut cells = grid.accessor_mut::<3>().unwrap();
+    for coords in corners {
+        // SAFETY: as above.
+        unsafe {
+            *cells.get_unchecked_mut(coords) -= 100;
+            assert_eq!(*cells.get_unchecked(coords), coords[0] * 4 + coords[2]);
+        }
+    }
+    assert!(grid.accessor::<2>().is_err());
+
+    let mut scalar = Grid::new(5u8, vec![]);
+    // SAFETY: a grid with no axes has exactly one cell, at `[]`.
+    unsafe {
+        *scalar.accessor_mut::<0>().unwrap().get_unchecked_mut([]) += 1;
+        assert_eq!(*scalar.accessor::<0>().unwrap().get_unchecked([]), 6);
+        assert_eq!(*scalar.get_unchecked(&[]), 6);
+    }
+}
+
+#[cfg(feature = "ffi")]
+mod ffi {
+    use std::{ffi::c_void, os::raw::c_int, ptr};
+
+    use md_grid::ffi::*;
+
+    extern "C" fn stop_at_second(_: *const usize, _: f64, user: *mut c_void) -> c_int {
+        // SAFETY: `handles_and_raw_data` passes a pointer to a `usize`.
+        let seen = unsafe { &mut *(user as *mut usize) };
+        *seen += 1;
+        if *seen == 2 {
+            9
+        } else {
+            0
+        }
+    }
+
+    #[test]
+    fn handles_and_raw_data() {
+        // SAFETY: the handle is live until `md_grid_free`, every array is as
+        // long as the grid has axes, and raw data is only accessed within
+        // `md_grid_len` cells while the grid lives.
+        unsafe {
+            let grid = md_grid_new([2usize, 2].as_ptr(), 2, 0.0);
+            let data = md_grid_data_mut(grid);
+            for i in 0..md_grid_len(grid) {
+                *data.add(i) = i as f64;
+            }
+
+            let mut out = 0.0;
+            assert_eq!(
+                md_grid_get(grid, [1usize, 1].as_ptr(), &mut out),
+                MD_GRID_OK
+            );
+            assert_eq!(out, 3.0);
+            assert_eq!(
+                md_grid_get(grid, [2usize, 0].as_ptr(), &mut out),
+                MD_GRID_OUT_OF_BOUNDS
+            );
+            assert_eq!(md_grid_set(grid, ptr::null(), 1.0), MD_GRID_NULL);
+            assert_eq!(md_grid_strides(grid, ptr::null_mut()), MD_GRID_NULL);
+
+            let mut seen = 0usize;
+            let status = md_grid_for_each(
+                grid,
+                Some(stop_at_second),
+                &mut seen as *mut usize as *mut c_void,
+            );
+            assert_eq!((status, seen), (9, 2));
+
+            md_grid_free(grid);
+            assert!(md_grid_new(ptr::null(), 2, 0.0).is_null());
+            assert!(md_grid_data(ptr::null()).is_null());
+        }
+    }
+}